@@ -0,0 +1,126 @@
+use async_compression::tokio::write::{BrotliEncoder, GzipEncoder, ZstdEncoder};
+use async_compression::Level;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rust_compress_comparison::harness::{generate_test_data, TestData};
+use std::io::{Read, Write};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::runtime::{Builder, Runtime};
+
+const DATA_SIZES: [usize; 3] = [1024 * 64, 1024 * 1024, 1024 * 1024 * 10]; // 64KB, 1MB, 10MB
+                                                                           // Explicit levels on both paths; brotli's default quality (11) is far slower than the others
+const GZIP_LEVEL: u32 = 6;
+const ZSTD_LEVEL: i32 = 3;
+const BROTLI_QUALITY: u32 = 5;
+const BROTLI_WINDOW: u32 = 22;
+
+fn gzip_sync(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(GZIP_LEVEL));
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn zstd_sync(data: &[u8]) -> Vec<u8> {
+    let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), ZSTD_LEVEL).unwrap();
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn brotli_sync(data: &[u8]) -> Vec<u8> {
+    let mut encoder =
+        brotli::CompressorWriter::new(Vec::new(), 4096, BROTLI_QUALITY, BROTLI_WINDOW);
+    encoder.write_all(data).unwrap();
+    encoder.into_inner()
+}
+
+// Drives one AsyncWrite encoder over `data` on the runtime, shutting it down to write trailers
+fn run_async<E>(runtime: &Runtime, mut encoder: E, data: &[u8]) -> E
+where
+    E: AsyncWrite + Unpin,
+{
+    runtime.block_on(async {
+        encoder.write_all(data).await.unwrap();
+        encoder.shutdown().await.unwrap();
+    });
+    encoder
+}
+
+fn gzip_async(runtime: &Runtime, data: &[u8]) -> Vec<u8> {
+    let encoder = GzipEncoder::with_quality(Vec::new(), Level::Precise(GZIP_LEVEL as i32));
+    run_async(runtime, encoder, data).into_inner()
+}
+
+fn zstd_async(runtime: &Runtime, data: &[u8]) -> Vec<u8> {
+    let encoder = ZstdEncoder::with_quality(Vec::new(), Level::Precise(ZSTD_LEVEL));
+    run_async(runtime, encoder, data).into_inner()
+}
+
+fn brotli_async(runtime: &Runtime, data: &[u8]) -> Vec<u8> {
+    let encoder = BrotliEncoder::with_quality(Vec::new(), Level::Precise(BROTLI_QUALITY as i32));
+    run_async(runtime, encoder, data).into_inner()
+}
+
+type AsyncCodec = (
+    &'static str,
+    fn(&[u8]) -> Vec<u8>,
+    fn(&Runtime, &[u8]) -> Vec<u8>,
+    fn(&[u8]) -> Vec<u8>,
+);
+
+const ASYNC_CODECS: [AsyncCodec; 3] = [
+    ("gzip", gzip_sync, gzip_async, |compressed| {
+        let mut restored = Vec::new();
+        flate2::read::GzDecoder::new(compressed)
+            .read_to_end(&mut restored)
+            .unwrap();
+        restored
+    }),
+    ("zstd", zstd_sync, zstd_async, |compressed| {
+        zstd::stream::decode_all(compressed).unwrap()
+    }),
+    ("brotli", brotli_sync, brotli_async, |compressed| {
+        let mut restored = Vec::new();
+        brotli::Decompressor::new(compressed, 4096)
+            .read_to_end(&mut restored)
+            .unwrap();
+        restored
+    }),
+];
+
+// The same codecs through async-compression's AsyncWrite adapters on a current-thread tokio
+// runtime versus their sync writers: the cost of the async wrapper, not the codec
+fn benchmark_async_overhead(c: &mut Criterion) {
+    let runtime = Builder::new_current_thread().build().unwrap();
+    for &size in &DATA_SIZES {
+        let data = generate_test_data(&TestData::Mixed, size);
+        let mut group = c.benchmark_group(format!("Async_{}B", size));
+        group.throughput(Throughput::Bytes(size as u64));
+
+        for (name, sync, r#async, decompress) in ASYNC_CODECS {
+            // Both paths must produce a stream the sync decoder restores
+            assert!(
+                decompress(&sync(&data)) == data,
+                "{} sync failed to round-trip",
+                name
+            );
+            assert!(
+                decompress(&r#async(&runtime, &data)) == data,
+                "{} async failed to round-trip",
+                name
+            );
+
+            group.bench_function(format!("{}_sync", name), |b| {
+                b.iter(|| black_box(sync(black_box(&data))))
+            });
+            group.bench_function(format!("{}_async", name), |b| {
+                b.iter(|| black_box(r#async(&runtime, black_box(&data))))
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, benchmark_async_overhead);
+criterion_main!(benches);
@@ -1,166 +1,140 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use flate2::read::GzDecoder;
-use flate2::write::GzEncoder;
+use bytes::buf::Writer;
+use bytes::{BufMut, Bytes, BytesMut};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use flate2::Compression;
-use lz4::Decoder;
-use lz4::EncoderBuilder;
-use lz4_flex::{compress_prepend_size, decompress_size_prepended};
-use rand::{thread_rng, Rng};
-use std::io::{Read, Write};
+use rust_compress_comparison::backends::{self, Gzip, Lz4Rs};
+use rust_compress_comparison::harness::{self, generate_test_data, TestData, TEST_CASES};
+use std::io::Write;
 
 const DATA_SIZES: [usize; 3] = [1024, 1024 * 1024, 1024 * 1024 * 10]; // 1KB, 1MB, 10MB
 
-fn generate_binary_data(size: usize) -> Vec<u8> {
-    let mut data = vec![0u8; size];
-    thread_rng().fill(&mut data[..]);
-    data
+fn gzip_compression_into<W: Write>(data: &[u8], sink: W) -> W {
+    Gzip::new(Compression::default())
+        .compress_into(data, sink)
+        .unwrap()
 }
 
-// Gzip Compression & Decompression
-fn gzip_compression(data: &[u8], level: Compression) -> Vec<u8> {
-    let mut encoder = GzEncoder::new(Vec::new(), level);
-    encoder.write_all(data).unwrap();
-    encoder.finish().unwrap()
+fn lz4_compression_into<W: Write>(data: &[u8], sink: W) -> W {
+    Lz4Rs::new(4).compress_into(data, sink).unwrap()
 }
 
-fn gzip_decompression(data: &[u8]) -> Vec<u8> {
-    let mut decoder = GzDecoder::new(data);
-    let mut decompressed = Vec::new();
-    decoder.read_to_end(&mut decompressed).unwrap();
-    decompressed
-}
-
-// LZ4-Flex Compression & Decompression
-fn lz4_flex_compression(data: &[u8]) -> Vec<u8> {
-    compress_prepend_size(data)
-}
+type BytesSink = Writer<BytesMut>;
+type PipelineCodec = (
+    &'static str,
+    fn(&[u8], Vec<u8>) -> Vec<u8>,
+    fn(&[u8], BytesSink) -> BytesSink,
+);
 
-fn lz4_flex_decompression(data: &[u8]) -> Vec<u8> {
-    decompress_size_prepended(data).unwrap()
+// Stand-in for handing a frame to a tokio/tonic transport
+fn send(frame: Bytes) {
+    black_box(frame);
 }
 
-// LZ4 Compression & Decompression with Different Levels
-fn lz4_compression(data: &[u8], level: u32) -> Vec<u8> {
-    let mut encoder = EncoderBuilder::new()
-        .level(level) // Set LZ4 compression level (0-16)
-        .build(Vec::new())
-        .unwrap();
-    encoder.write_all(data).unwrap();
-    let (compressed, result) = encoder.finish();
-    result.unwrap();
-    compressed
-}
+// Same data types and configurations as the `bench` subcommand, swept over input sizes. One
+// group per data type and direction, with the size as the parameter, so criterion reports MB/s
+// and charts each configuration across sizes; decompression throughput counts uncompressed bytes
+fn benchmark_compression_speed(c: &mut Criterion) {
+    for (data_name, data_type) in &TEST_CASES {
+        // Generated once per size
+        let inputs: Vec<Vec<u8>> = DATA_SIZES
+            .iter()
+            .map(|&size| generate_test_data(data_type, size))
+            .collect();
+
+        let mut group = c.benchmark_group(format!("Compress_{}", data_name));
+        for codec in backends::configurations() {
+            let id = format!("{}_{}", codec.name(), codec.level()).to_lowercase();
+            for data in &inputs {
+                group.throughput(Throughput::Bytes(data.len() as u64));
+                group.bench_with_input(BenchmarkId::new(&id, data.len()), data, |b, data| {
+                    b.iter(|| black_box(codec.compress(black_box(data)).unwrap()))
+                });
+            }
+        }
+        group.finish();
 
-fn lz4_decompression(data: &[u8]) -> Vec<u8> {
-    let mut decoder = Decoder::new(data).unwrap();
-    let mut decompressed = Vec::new();
-    decoder.read_to_end(&mut decompressed).unwrap();
-    decompressed
+        let mut group = c.benchmark_group(format!("Decompress_{}", data_name));
+        for codec in backends::configurations() {
+            let id = format!("{}_{}", codec.name(), codec.level()).to_lowercase();
+            for data in &inputs {
+                let compressed = harness::compress(codec, data).unwrap();
+                harness::verify(codec, data, &compressed).unwrap();
+                group.throughput(Throughput::Bytes(data.len() as u64));
+                group.bench_with_input(
+                    BenchmarkId::new(&id, data.len()),
+                    &compressed,
+                    |b, compressed| {
+                        b.iter(|| black_box(codec.decompress(black_box(compressed)).unwrap()))
+                    },
+                );
+            }
+        }
+        group.finish();
+    }
 }
 
-fn benchmark_compression_speed(c: &mut Criterion) {
+// Vec<u8> vs bytes::Bytes through the full compress-send path, one group across all sizes
+fn benchmark_bytes_pipeline(c: &mut Criterion) {
+    let codecs: [PipelineCodec; 2] = [
+        ("gzip", gzip_compression_into, gzip_compression_into),
+        ("lz4", lz4_compression_into, lz4_compression_into),
+    ];
+    let mut group = c.benchmark_group("Pipeline");
     for &size in &DATA_SIZES {
-        let data = generate_binary_data(size); // Generate once per size
-        let mut group = c.benchmark_group(format!("Compression_{}B", size));
-
-        // Gzip Compression Benchmarks
-        group.bench_function("gzip_fast", |b| {
-            b.iter(|| {
-                let compressed = gzip_compression(black_box(&data), Compression::fast());
-                black_box(compressed);
-            })
-        });
-
-        group.bench_function("gzip_default", |b| {
-            b.iter(|| {
-                let compressed = gzip_compression(black_box(&data), Compression::default());
-                black_box(compressed);
-            })
-        });
-
-        group.bench_function("gzip_best", |b| {
-            b.iter(|| {
-                let compressed = gzip_compression(black_box(&data), Compression::best());
-                black_box(compressed);
-            })
-        });
-
-        // LZ4-Flex Compression Benchmark
-        group.bench_function("lz4_flex", |b| {
-            b.iter(|| {
-                let compressed = lz4_flex_compression(black_box(&data));
-                black_box(compressed);
-            })
-        });
-
-        // LZ4 Compression Benchmarks at Different Levels
-        group.bench_function("lz4_fast", |b| {
-            b.iter(|| {
-                let compressed = lz4_compression(black_box(&data), 0); // Fastest mode
-                black_box(compressed);
-            })
-        });
-
-        group.bench_function("lz4_default", |b| {
-            b.iter(|| {
-                let compressed = lz4_compression(black_box(&data), 4); // Default mode
-                black_box(compressed);
-            })
-        });
-
-        group.bench_function("lz4_best", |b| {
-            b.iter(|| {
-                let compressed = lz4_compression(black_box(&data), 16); // Best compression
-                black_box(compressed);
-            })
-        });
-
-        // Gzip Decompression Benchmark
-        let gzip_compressed = gzip_compression(&data, Compression::default());
-        group.bench_function("gzip_decompress", |b| {
-            b.iter(|| {
-                let decompressed = gzip_decompression(black_box(&gzip_compressed));
-                black_box(decompressed);
-            })
-        });
-
-        // LZ4-Flex Decompression Benchmark
-        let lz4_flex_compressed = lz4_flex_compression(&data);
-        group.bench_function("lz4_flex_decompress", |b| {
-            b.iter(|| {
-                let decompressed = lz4_flex_decompression(black_box(&lz4_flex_compressed));
-                black_box(decompressed);
-            })
-        });
-
-        // LZ4 Decompression Benchmark at Different Levels
-        let lz4_compressed_fast = lz4_compression(&data, 0);
-        group.bench_function("lz4_decompress_fast", |b| {
-            b.iter(|| {
-                let decompressed = lz4_decompression(black_box(&lz4_compressed_fast));
-                black_box(decompressed);
-            })
-        });
-
-        let lz4_compressed_default = lz4_compression(&data, 4);
-        group.bench_function("lz4_decompress_default", |b| {
-            b.iter(|| {
-                let decompressed = lz4_decompression(black_box(&lz4_compressed_default));
-                black_box(decompressed);
-            })
-        });
-
-        let lz4_compressed_best = lz4_compression(&data, 16);
-        group.bench_function("lz4_decompress_best", |b| {
-            b.iter(|| {
-                let decompressed = lz4_decompression(black_box(&lz4_compressed_best));
-                black_box(decompressed);
-            })
-        });
-
-        group.finish();
+        let data = Bytes::from(generate_test_data(&TestData::Mixed, size));
+        group.throughput(Throughput::Bytes(size as u64));
+
+        for (name, compress_vec, compress_bytes) in codecs {
+            // Vec output handed over without conversion (baseline)
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_vec", name), size),
+                &data,
+                |b, data| {
+                    b.iter(|| {
+                        let compressed = compress_vec(black_box(data), Vec::new());
+                        black_box(compressed);
+                    })
+                },
+            );
+
+            // Vec output converted to Bytes by taking ownership (no copy)
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_vec_into_bytes", name), size),
+                &data,
+                |b, data| b.iter(|| send(Bytes::from(compress_vec(black_box(data), Vec::new())))),
+            );
+
+            // Vec output copied into a fresh Bytes, as happens behind `&[u8]` APIs
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_vec_copy_to_bytes", name), size),
+                &data,
+                |b, data| {
+                    b.iter(|| {
+                        let compressed = compress_vec(black_box(data), Vec::new());
+                        send(Bytes::copy_from_slice(&compressed))
+                    })
+                },
+            );
+
+            // Encoder writes straight into a BytesMut which is frozen for sending
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_bytesmut", name), size),
+                &data,
+                |b, data| {
+                    b.iter(|| {
+                        let writer = compress_bytes(black_box(data), BytesMut::new().writer());
+                        send(writer.into_inner().freeze())
+                    })
+                },
+            );
+        }
     }
+    group.finish();
 }
 
-criterion_group!(benches, benchmark_compression_speed);
+criterion_group!(
+    benches,
+    benchmark_compression_speed,
+    benchmark_bytes_pipeline
+);
 criterion_main!(benches);
@@ -0,0 +1,55 @@
+use std::fs;
+use std::process::Command;
+
+// Codec crates whose resolved versions are recorded with stored runs
+const CODEC_CRATES: [&str; 4] = ["flate2", "lz4", "lz4_flex", "zstd"];
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+
+    let lock = fs::read_to_string("Cargo.lock").unwrap_or_default();
+    let mut versions = Vec::new();
+    let mut name = None;
+    for line in lock.lines() {
+        if let Some(value) = line.strip_prefix("name = ") {
+            name = Some(value.trim_matches('"'));
+        } else if let (Some(crate_name), Some(value)) = (name, line.strip_prefix("version = ")) {
+            if CODEC_CRATES.contains(&crate_name) {
+                versions.push(format!("{}={}", crate_name, value.trim_matches('"')));
+            }
+            name = None;
+        }
+    }
+    println!(
+        "cargo:rustc-env=CODEC_CRATE_VERSIONS={}",
+        versions.join(",")
+    );
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_COMMIT={}", commit);
+
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+// Regenerates the checked-in C header from the `ccb_*` functions alone
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let config = cbindgen::Config::from_file("cbindgen.toml").expect("cbindgen.toml is valid");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src("src/ffi.rs")
+        .generate()
+        .expect("src/ffi.rs is parseable by cbindgen")
+        .write_to_file("include/compress_comparison.h");
+}
@@ -0,0 +1,111 @@
+// Serves benchmark runs as JSON over HTTP, e.g. for a dashboard polling several hosts:
+//
+//     cargo run --release --example bench_server -- 127.0.0.1:8080
+//     curl 'http://127.0.0.1:8080/?config=gzip:fast&config=lz4-flex'
+//
+// Each request runs a small suite on its own thread and answers with the `RunResults` JSON.
+use rust_compress_comparison::{backends, BenchmarkSuite, Compressor, RunResults};
+use std::error::Error;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::{env, thread};
+
+// Runs are serialized so concurrent requests don't skew each other's timings
+static RUN_LOCK: Mutex<()> = Mutex::new(());
+
+const DATA_SIZE: usize = 1024 * 1024;
+const TRIALS: usize = 5;
+
+// Configurations named by `config=` query parameters, or every registered one
+fn configurations(target: &str) -> Result<Vec<&'static dyn Compressor>, String> {
+    let query = target.split_once('?').map_or("", |(_, query)| query);
+    let specs: Vec<&str> = query
+        .split('&')
+        .filter_map(|pair| pair.strip_prefix("config="))
+        .collect();
+    if specs.is_empty() {
+        return Ok(backends::configurations().collect());
+    }
+    specs.into_iter().map(backends::find).collect()
+}
+
+fn run(target: &str) -> Result<RunResults, Box<dyn Error>> {
+    let algorithms = configurations(target)?;
+    let _guard = RUN_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let results = BenchmarkSuite::builder()
+        .algorithms(algorithms)
+        .data_size(DATA_SIZE)
+        .trials(TRIALS)
+        .run()?;
+    Ok(results)
+}
+
+fn handle(mut stream: TcpStream) -> Result<(), Box<dyn Error>> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+
+    let (status, body) = match run(target) {
+        Ok(results) => ("200 OK", serde_json::to_string_pretty(&results)?),
+        Err(e) => (
+            "400 Bad Request",
+            serde_json::json!({ "error": e.to_string() }).to_string(),
+        ),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let listener = TcpListener::bind(&address)?;
+    println!("Serving benchmark runs on http://{}", address);
+    for stream in listener.incoming() {
+        let stream = stream?;
+        thread::spawn(move || {
+            if let Err(e) = handle(stream) {
+                eprintln!("request failed: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_configurations_from_the_query() {
+        let codecs = configurations("/?config=gzip:fast&config=lz4-flex").unwrap();
+        let labels: Vec<String> = codecs
+            .iter()
+            .map(|c| format!("{} {}", c.name(), c.level()))
+            .collect();
+        assert_eq!(labels, ["Gzip Fast", "LZ4-Flex Default"]);
+    }
+
+    #[test]
+    fn defaults_to_every_configuration() {
+        assert_eq!(
+            configurations("/").unwrap().len(),
+            backends::configurations().count()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_configuration() {
+        assert!(configurations("/?config=brotli").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+// Compares two configurations on the contents of a file:
+//
+//     cargo run --release --example compare_file -- data.bin gzip:best zstd:default
+use rust_compress_comparison::harness;
+use rust_compress_comparison::results::CellResult;
+use rust_compress_comparison::suite::CompressionStats;
+use rust_compress_comparison::{backends, CompressionBenchError};
+use std::error::Error;
+use std::{env, fs};
+
+const TRIALS: usize = 10;
+
+// Verified round trips of `data` through each configuration, one summarized cell per spec
+fn compare(
+    name: &str,
+    data: &[u8],
+    specs: &[String],
+    trials: usize,
+) -> Result<Vec<CellResult>, Box<dyn Error>> {
+    let mut cells = Vec::new();
+    for spec in specs {
+        let codec = backends::find(spec)?;
+        let mut stats = CompressionStats::default();
+        for _ in 0..trials {
+            let trial = harness::round_trip(codec, data, true)?;
+            stats.record(data.len(), trial.compressed_size, trial.compress_secs);
+            stats.record_decompression(trial.decompress_secs);
+        }
+        cells.push(stats.to_cell(name, codec.name(), &codec.level()));
+    }
+    Ok(cells)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [path, specs @ ..] = args.as_slice() else {
+        return Err("usage: compare_file FILE [CONFIG ...]".into());
+    };
+    let data = fs::read(path).map_err(|source| CompressionBenchError::Io {
+        path: path.into(),
+        source,
+    })?;
+    let specs = if specs.is_empty() {
+        vec!["gzip".to_string(), "lz4-flex".to_string()]
+    } else {
+        specs.to_vec()
+    };
+
+    println!(
+        "{:<18} {:>8} {:>12} {:>14}",
+        "Configuration", "Ratio", "Comp MB/s", "Decomp MB/s"
+    );
+    for cell in compare(path, &data, &specs, TRIALS)? {
+        println!(
+            "{:<18} {:>7.2}x {:>12.1} {:>14.1}",
+            cell.label(),
+            cell.ratio,
+            cell.compress_mbps(),
+            cell.decompress_mbps()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_compress_comparison::harness::{generate_test_data, TestData};
+
+    #[test]
+    fn compares_each_configuration() {
+        let data = generate_test_data(&TestData::Mixed, 64 * 1024);
+        let specs = vec!["gzip:fast".to_string(), "lz4-flex".to_string()];
+        let cells = compare("mixed", &data, &specs, 2).unwrap();
+        assert_eq!(cells.len(), 2);
+        assert!(cells.iter().all(|c| c.ratio > 1.0 && c.compress_secs > 0.0));
+    }
+
+    #[test]
+    fn rejects_unknown_configuration() {
+        assert!(compare("mixed", b"data", &["brotli".to_string()], 1).is_err());
+    }
+}
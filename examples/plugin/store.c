@@ -0,0 +1,39 @@
+/*
+ * Minimal codec plugin: "compresses" by copying, as a template and a floor for memcpy speed.
+ *
+ *     cc -shared -fPIC -Iinclude examples/plugin/store.c -o libstore.so
+ *     cargo run --release -- bench --plugin ./libstore.so
+ */
+#include <stdlib.h>
+#include <string.h>
+
+#include "compress_comparison_plugin.h"
+
+static int copy(const uint8_t *input, size_t input_len, uint8_t **output, size_t *output_len) {
+    /* malloc(0) may return NULL; an empty result is fine either way */
+    *output = malloc(input_len ? input_len : 1);
+    if (*output == NULL) {
+        return 1;
+    }
+    memcpy(*output, input, input_len);
+    *output_len = input_len;
+    return 0;
+}
+
+static void free_buffer(uint8_t *buffer, size_t len) {
+    (void)len;
+    free(buffer);
+}
+
+static const CcbCodecVTable STORE = {
+    .abi_version = CCB_PLUGIN_ABI_VERSION,
+    .name = "Store",
+    .level = "None",
+    .compress = copy,
+    .decompress = copy,
+    .free_buffer = free_buffer,
+};
+
+const CcbCodecVTable *ccb_plugin_codec(void) {
+    return &STORE;
+}
@@ -0,0 +1,59 @@
+// Runs the suite with whatever backends are compiled in and prints one line per cell. Built with
+// `--no-default-features --features pure-rust` that is gzip (miniz_oxide) against lz4_flex, which
+// also builds for wasm32-wasip1 and runs under a WASI runtime such as wasmtime:
+//
+//     wasmtime target/wasm32-wasip1/release/examples/wasm_bench.wasm '{"trials": 5}'
+use rust_compress_comparison::config::SuiteConfig;
+use rust_compress_comparison::RunResults;
+use std::env;
+use std::error::Error;
+
+// The config is `SuiteConfig` JSON; wasmtime passes arguments through but not files by default
+fn run(config_json: &str) -> Result<RunResults, Box<dyn Error>> {
+    Ok(SuiteConfig::from_json(config_json)?.builder()?.run()?)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let config = env::args().nth(1).unwrap_or_else(|| "{}".to_string());
+    let results = run(&config)?;
+
+    println!(
+        "{:<12} {:<24} {:>8} {:>12} {:>14}",
+        "Data", "Configuration", "Ratio", "Comp MB/s", "Decomp MB/s"
+    );
+    for cell in &results.cells {
+        println!(
+            "{:<12} {:<24} {:>7.2}x {:>12.1} {:>14.1}",
+            cell.data_type,
+            cell.label(),
+            cell.ratio,
+            cell.compress_mbps(),
+            cell.decompress_mbps()
+        );
+    }
+    for failure in &results.failures {
+        eprintln!(
+            "{} {} on {}: {}",
+            failure.algorithm, failure.level, failure.data_type, failure.error
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_pure_rust_backends() {
+        let results = run(r#"{"data": ["mixed"], "trials": 1, "data_size": 65536}"#).unwrap();
+        assert!(results.failures.is_empty());
+        assert!(results.cells.iter().any(|c| c.algorithm == "Gzip"));
+        assert!(results.cells.iter().any(|c| c.algorithm == "LZ4-Flex"));
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        assert!(run(r#"{"trails": 1}"#).is_err());
+    }
+}
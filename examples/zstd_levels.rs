@@ -0,0 +1,58 @@
+// Sweeps zstd levels 1-19 through a suite and prints the ratio/speed trade-off:
+//
+//     cargo run --release --example zstd_levels
+use rust_compress_comparison::backends::Zstd;
+use rust_compress_comparison::error::Result;
+use rust_compress_comparison::harness::TestData;
+use rust_compress_comparison::{BenchmarkSuite, Compressor, RunResults};
+use std::error::Error;
+use std::ops::RangeInclusive;
+
+fn sweep(levels: RangeInclusive<i32>, data_size: usize, trials: usize) -> Result<RunResults> {
+    // Suites hold `'static` configurations; a one-off sweep can simply leak its handful
+    let levels =
+        levels.map(|level| -> &'static dyn Compressor { Box::leak(Box::new(Zstd::new(level))) });
+    BenchmarkSuite::builder()
+        .data([TestData::Mixed])
+        .algorithms(levels)
+        .data_size(data_size)
+        .trials(trials)
+        .verify(true)
+        .run()
+}
+
+fn main() -> std::result::Result<(), Box<dyn Error>> {
+    let results = sweep(1..=19, 1024 * 1024, 5)?;
+
+    println!(
+        "{:<8} {:>8} {:>12} {:>14}",
+        "Level", "Ratio", "Comp MB/s", "Decomp MB/s"
+    );
+    for cell in &results.cells {
+        println!(
+            "{:<8} {:>7.2}x {:>12.1} {:>14.1}",
+            cell.level,
+            cell.ratio,
+            cell.compress_mbps(),
+            cell.decompress_mbps()
+        );
+    }
+    for failure in &results.failures {
+        println!("FAILED {}: {}", failure.level, failure.error);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_every_level() {
+        let results = sweep(1..=3, 64 * 1024, 2).unwrap();
+        let levels: Vec<&str> = results.cells.iter().map(|c| c.level.as_str()).collect();
+        assert_eq!(levels, ["Fast", "2", "Default"]);
+        assert!(results.failures.is_empty());
+        assert!(results.metadata.verified);
+    }
+}
@@ -0,0 +1,47 @@
+#ifndef COMPRESS_COMPARISON_H
+#define COMPRESS_COMPARISON_H
+
+/* Generated by cbindgen from src/ffi.rs when building with --features ffi; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The run succeeded; `out_json` holds the `RunResults` JSON.
+ */
+#define CCB_OK 0
+
+/**
+ * `config_json` or `out_json` was null, or the config was not valid UTF-8.
+ */
+#define CCB_INVALID_ARGUMENT 1
+
+/**
+ * The config was rejected or the run failed; `out_json` holds `{"error": "..."}`.
+ */
+#define CCB_RUN_FAILED 2
+
+/**
+ * Runs the suite described by `config_json` (see `SuiteConfig`, `"{}"` for the defaults) and
+ * stores the `RunResults` JSON in `*out_json`. Whatever is stored in `*out_json` must be
+ * released with `ccb_free_string`.
+ *
+ * # Safety
+ *
+ * `config_json` must be a NUL-terminated string and `out_json` a valid pointer to write to.
+ */
+int ccb_run(const char *config_json, char **out_json);
+
+/**
+ * Releases a string returned through `ccb_run`; null is ignored.
+ *
+ * # Safety
+ *
+ * `json` must be null or a pointer from `ccb_run` that has not been freed yet.
+ */
+void ccb_free_string(char *json);
+
+#endif  /* COMPRESS_COMPARISON_H */
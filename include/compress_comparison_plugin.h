@@ -0,0 +1,33 @@
+#ifndef COMPRESS_COMPARISON_PLUGIN_H
+#define COMPRESS_COMPARISON_PLUGIN_H
+
+/*
+ * Contract for external codecs loaded with `bench --plugin path/to/libcodec.so`.
+ *
+ * A plugin exports `ccb_plugin_codec`, returning a vtable that stays valid for the life of
+ * the process. Output buffers are allocated by the plugin and handed back to its own
+ * `free_buffer` once copied. The functions must be thread-safe and must not unwind or
+ * longjmp out of the call.
+ */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define CCB_PLUGIN_ABI_VERSION 1
+
+typedef struct CcbCodecVTable {
+    /* Must be CCB_PLUGIN_ABI_VERSION */
+    uint32_t abi_version;
+    /* Algorithm name as shown in result tables, e.g. "Brotli" */
+    const char *name;
+    /* Level name as shown in result tables, e.g. "Default" or "11" */
+    const char *level;
+    /* Both return 0 on success and set *output / *output_len to a plugin-owned buffer */
+    int (*compress)(const uint8_t *input, size_t input_len, uint8_t **output, size_t *output_len);
+    int (*decompress)(const uint8_t *input, size_t input_len, uint8_t **output, size_t *output_len);
+    void (*free_buffer)(uint8_t *buffer, size_t len);
+} CcbCodecVTable;
+
+const CcbCodecVTable *ccb_plugin_codec(void);
+
+#endif /* COMPRESS_COMPARISON_PLUGIN_H */
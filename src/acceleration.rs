@@ -0,0 +1,102 @@
+use crate::plots::{self, PlotFormat};
+use crate::{generate_test_data, TestData};
+use lz4::block::{self, CompressionMode};
+use std::error::Error;
+use std::path::Path;
+use std::time::Instant;
+
+const SWEEP_DATA_SIZE: usize = 1024 * 1024 * 4; // 4MB
+const SWEEP_ITERATIONS: usize = 3;
+
+pub const DEFAULT_ACCELERATIONS: [i32; 11] = [1, 2, 4, 8, 16, 32, 64, 256, 1024, 8192, 65537];
+
+struct SweepPoint {
+    ratio: f64,
+    compress_mbps: f64,
+    decompress_mbps: f64,
+}
+
+// Averages SWEEP_ITERATIONS compress/decompress roundtrips of one configuration
+fn measure(
+    data: &[u8],
+    compress: impl Fn(&[u8]) -> Vec<u8>,
+    decompress: impl Fn(&[u8]) -> Vec<u8>,
+) -> SweepPoint {
+    let mut compress_secs = 0.0;
+    let mut decompress_secs = 0.0;
+    let mut compressed_size = 0;
+    for _ in 0..SWEEP_ITERATIONS {
+        let start = Instant::now();
+        let compressed = compress(data);
+        compress_secs += start.elapsed().as_secs_f64();
+
+        let start = Instant::now();
+        let restored = decompress(&compressed);
+        decompress_secs += start.elapsed().as_secs_f64();
+        assert_eq!(restored.len(), data.len());
+        compressed_size = compressed.len();
+    }
+    let mb = (data.len() * SWEEP_ITERATIONS) as f64 / (1024.0 * 1024.0);
+    SweepPoint {
+        ratio: data.len() as f64 / compressed_size as f64,
+        compress_mbps: mb / compress_secs,
+        decompress_mbps: mb / decompress_secs,
+    }
+}
+
+fn print_row(name: &str, point: &SweepPoint) {
+    println!(
+        "{:<20} {:>8.2}x {:>12.1} {:>14.1}",
+        name, point.ratio, point.compress_mbps, point.decompress_mbps
+    );
+}
+
+// lz4 "fast" is a family: acceleration trades ratio for speed from 1 up to 65537
+pub fn run(
+    accelerations: &[i32],
+    plot: Option<&Path>,
+    format: PlotFormat,
+) -> Result<(), Box<dyn Error>> {
+    let data = generate_test_data(&TestData::Mixed, SWEEP_DATA_SIZE);
+
+    println!(
+        "\nLZ4 acceleration sweep ({}MB Mixed data, {} iterations per point)\n",
+        SWEEP_DATA_SIZE / 1024 / 1024,
+        SWEEP_ITERATIONS
+    );
+    println!(
+        "{:<20} {:>9} {:>12} {:>14}",
+        "Configuration", "Ratio", "Comp MB/s", "Decomp MB/s"
+    );
+    println!("{}", "-".repeat(58));
+
+    let decompress = |compressed: &[u8]| block::decompress(compressed, None).unwrap();
+    let mut curve = Vec::with_capacity(accelerations.len());
+    for &acceleration in accelerations {
+        let point = measure(
+            &data,
+            |data| block::compress(data, Some(CompressionMode::FAST(acceleration)), true).unwrap(),
+            decompress,
+        );
+        print_row(&format!("LZ4-RS accel={}", acceleration), &point);
+        curve.push((acceleration.to_string(), point.compress_mbps, point.ratio));
+    }
+
+    // lz4_flex has no acceleration knob; it always runs the equivalent of acceleration 1
+    let flex = measure(&data, lz4_flex::compress_prepend_size, |compressed| {
+        lz4_flex::decompress_size_prepended(compressed).unwrap()
+    });
+    print_row("LZ4-Flex (fixed)", &flex);
+
+    if let Some(path) = plot {
+        plots::render_sweep(
+            path,
+            format,
+            "LZ4 acceleration: ratio vs compression speed",
+            &curve,
+            Some(("LZ4-Flex", flex.compress_mbps, flex.ratio)),
+        )?;
+        println!("\nWrote acceleration curve to {}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+#[cfg(feature = "gzip")]
+pub mod gzip;
+#[cfg(all(feature = "gzip", feature = "parallel"))]
+pub mod gzip_parallel;
+#[cfg(feature = "lz4-flex")]
+pub mod lz4_flex;
+#[cfg(feature = "lz4")]
+pub mod lz4_rs;
+#[cfg(feature = "zstd")]
+pub mod zstd;
+
+#[cfg(feature = "gzip")]
+pub use self::gzip::Gzip;
+#[cfg(all(feature = "gzip", feature = "parallel"))]
+pub use self::gzip_parallel::ParallelGzip;
+#[cfg(feature = "lz4-flex")]
+pub use self::lz4_flex::Lz4Flex;
+#[cfg(feature = "lz4")]
+pub use self::lz4_rs::Lz4Rs;
+#[cfg(feature = "zstd")]
+pub use self::zstd::Zstd;
+
+use crate::{Backend, Compressor};
+use std::io;
+use std::sync::Mutex;
+
+// Every backend compiled in, in report order; adding one takes its module, a feature and a line here
+pub const REGISTRY: &[&Backend] = &[
+    #[cfg(feature = "gzip")]
+    &gzip::BACKEND,
+    #[cfg(feature = "lz4-flex")]
+    &lz4_flex::BACKEND,
+    #[cfg(feature = "lz4")]
+    &lz4_rs::BACKEND,
+    #[cfg(feature = "zstd")]
+    &zstd::BACKEND,
+];
+
+// All registered (backend, level) configurations, in report order
+pub fn configurations() -> impl Iterator<Item = &'static dyn Compressor> {
+    REGISTRY
+        .iter()
+        .flat_map(|backend| backend.levels.iter().copied())
+}
+
+// Multithreaded configurations at `threads` threads, built on demand since the thread count
+// is a runtime choice. Each set is leaked to live for the rest of the process like the
+// registry's, so it is kept per thread count: `scaling` asks for every count it sweeps, and a
+// fresh set per call would leak another parallel gzip with its rayon pool each time
+pub fn multithreaded(threads: usize) -> io::Result<Vec<&'static dyn Compressor>> {
+    assert!(threads >= 1, "thread count must be at least 1");
+    static BUILT: Mutex<Vec<(usize, Vec<&'static dyn Compressor>)>> = Mutex::new(Vec::new());
+    let mut built = BUILT.lock().unwrap();
+    if let Some((_, codecs)) = built.iter().find(|(count, _)| *count == threads) {
+        return Ok(codecs.clone());
+    }
+    let codecs: &[&'static dyn Compressor] = &[
+        #[cfg(feature = "zstd")]
+        Box::leak(Box::new(Zstd::multithreaded(3, threads as u32))),
+        #[cfg(all(feature = "gzip", feature = "parallel"))]
+        Box::leak(Box::new(ParallelGzip::new(
+            flate2::Compression::new(6),
+            threads,
+            gzip_parallel::DEFAULT_CHUNK_SIZE,
+        )?)),
+    ];
+    built.push((threads, codecs.to_vec()));
+    Ok(codecs.to_vec())
+}
+
+// The backend whose output `compressed` starts like, judged by magic bytes
+pub fn detect(compressed: &[u8]) -> Option<&'static Backend> {
+    REGISTRY
+        .iter()
+        .copied()
+        .find(|backend| !backend.magic.is_empty() && compressed.starts_with(backend.magic))
+}
+
+// Resolves `id` or `id:level`; a bare id picks the backend's "Default" level
+pub fn find(spec: &str) -> Result<&'static dyn Compressor, String> {
+    let (id, level) = spec.split_once(':').unwrap_or((spec, "default"));
+    let backend = REGISTRY
+        .iter()
+        .find(|backend| backend.id.eq_ignore_ascii_case(id))
+        .ok_or_else(|| {
+            let ids: Vec<&str> = REGISTRY.iter().map(|backend| backend.id).collect();
+            format!(
+                "unknown backend `{}`, expected one of {}",
+                id,
+                ids.join(", ")
+            )
+        })?;
+    backend.level(level).ok_or_else(|| {
+        let levels: Vec<String> = backend
+            .levels
+            .iter()
+            .map(|codec| codec.level().to_lowercase())
+            .collect();
+        format!(
+            "unknown level `{}` for {}, expected one of {}",
+            level,
+            backend.id,
+            levels.join(", ")
+        )
+    })
+}
+
+// Inputs and round-trip assertions shared by every backend module's tests
+#[cfg(all(
+    test,
+    any(
+        feature = "gzip",
+        feature = "lz4",
+        feature = "lz4-flex",
+        feature = "zstd"
+    )
+))]
+pub(crate) mod check {
+    use crate::harness::{generate_test_data, TestData};
+    use crate::Compressor;
+    use std::io;
+
+    // Edge sizes plus a repeating pattern ending exactly on a 64KB block boundary and a large
+    // input spanning many blocks with one byte over
+    pub fn inputs() -> Vec<(&'static str, Vec<u8>)> {
+        vec![
+            ("empty", Vec::new()),
+            ("one byte", vec![0x42]),
+            (
+                "pattern",
+                b"HelloWorld"
+                    .iter()
+                    .copied()
+                    .cycle()
+                    .take(64 * 1024)
+                    .collect(),
+            ),
+            (
+                "large",
+                generate_test_data(&TestData::Mixed, 1024 * 1024 + 1),
+            ),
+        ]
+    }
+
+    // Every input restored by `decompress` and by `decompress_limited` at exactly its size, and
+    // through the streaming encoder where the backend has one
+    pub fn round_trips(codec: &dyn Compressor) {
+        let label = format!("{} {}", codec.name(), codec.level());
+        for (name, data) in inputs() {
+            let compressed = codec
+                .compress(&data)
+                .unwrap_or_else(|e| panic!("{} compressing {} input: {}", label, name, e));
+            let restored = codec
+                .decompress(&compressed)
+                .unwrap_or_else(|e| panic!("{} decompressing {} input: {}", label, name, e));
+            assert!(restored == data, "{} changed the {} input", label, name);
+            let limited = codec
+                .decompress_limited(&compressed, data.len())
+                .unwrap_or_else(|e| panic!("{} limited to {} input: {}", label, name, e));
+            assert!(
+                limited == data,
+                "{} limited changed the {} input",
+                label,
+                name
+            );
+            match codec.compress_streamed(&data, 4096) {
+                Err(e) if e.kind() == io::ErrorKind::Unsupported => {}
+                Err(e) => panic!("{} streaming {} input: {}", label, name, e),
+                Ok(streamed) => assert!(
+                    codec.decompress(&streamed).ok().as_ref() == Some(&data),
+                    "{} streamed output did not restore the {} input",
+                    label,
+                    name
+                ),
+            }
+        }
+    }
+
+    // One byte under the output size must be refused, with the limit's own error kind
+    pub fn enforces_limit(codec: &dyn Compressor) {
+        let data = generate_test_data(&TestData::Repeating, 1024 * 1024);
+        let compressed = codec.compress(&data).unwrap();
+        let error = codec
+            .decompress_limited(&compressed, data.len() - 1)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::FileTooLarge);
+    }
+
+    // Size of the input `truncated` compresses before cutting
+    pub const TRUNCATED_SOURCE: usize = 256 * 1024;
+
+    // Some prefix of a valid stream, cut short
+    pub fn truncated(codec: &dyn Compressor) -> Vec<u8> {
+        let mut compressed = codec
+            .compress(&generate_test_data(&TestData::Mixed, TRUNCATED_SOURCE))
+            .unwrap();
+        compressed.truncate(compressed.len() / 2);
+        compressed
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{read_limited, Backend, Compressor, Instant, StreamPhases};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{self, Read, Write};
+
+pub const BACKEND: Backend = Backend {
+    id: "gzip",
+    levels: &[
+        &Gzip::new(Compression::fast()),
+        // Compression::default(), which isn't const
+        &Gzip::new(Compression::new(6)),
+        &Gzip::new(Compression::best()),
+    ],
+    magic: &[0x1f, 0x8b],
+};
+
+// flate2's gzip format at a given deflate level
+#[derive(Clone, Copy)]
+pub struct Gzip {
+    level: Compression,
+}
+
+impl Gzip {
+    pub const fn new(level: Compression) -> Self {
+        Gzip { level }
+    }
+
+    // Streams the compressed output into any sink, e.g. a `Vec` or a `BytesMut` writer
+    pub fn compress_into<W: Write>(&self, data: &[u8], sink: W) -> io::Result<W> {
+        let mut encoder = GzEncoder::new(sink, self.level);
+        encoder.write_all(data)?;
+        encoder.finish()
+    }
+}
+
+impl Compressor for Gzip {
+    fn name(&self) -> &'static str {
+        "Gzip"
+    }
+
+    fn level(&self) -> String {
+        match self.level.level() {
+            1 => "Fast".to_string(),
+            6 => "Default".to_string(),
+            9 => "Best".to_string(),
+            level => level.to_string(),
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        self.compress_into(data, Vec::new())
+    }
+
+    fn compress_phased(
+        &self,
+        data: &[u8],
+        write_size: usize,
+    ) -> io::Result<(Vec<u8>, StreamPhases)> {
+        let start = Instant::now();
+        let mut encoder = GzEncoder::new(Vec::new(), self.level);
+        for chunk in data.chunks(write_size) {
+            encoder.write_all(chunk)?;
+        }
+        let finish = Instant::now();
+        let compressed = encoder.finish()?;
+        Ok((compressed, StreamPhases::since(start, finish)))
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        // Multi-member so concatenated files (pigz, `cat a.gz b.gz`) restore in full
+        let mut decoder = MultiGzDecoder::new(data);
+        let mut decompressed = Vec::new();
+        decoder.read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+
+    fn decompress_limited(&self, data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        read_limited(MultiGzDecoder::new(data), limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::check;
+
+    #[test]
+    fn round_trips_at_every_level() {
+        for codec in BACKEND.levels {
+            check::round_trips(*codec);
+        }
+    }
+
+    #[test]
+    fn restores_concatenated_members() {
+        let gzip = Gzip::new(Compression::fast());
+        let mut concatenated = gzip.compress(b"Hello").unwrap();
+        concatenated.extend(gzip.compress(b"World").unwrap());
+        assert_eq!(gzip.decompress(&concatenated).unwrap(), b"HelloWorld");
+    }
+
+    #[test]
+    fn rejects_truncated_stream() {
+        let gzip = Gzip::new(Compression::fast());
+        assert!(gzip.decompress(&check::truncated(&gzip)).is_err());
+    }
+
+    #[test]
+    fn rejects_corrupted_crc() {
+        let gzip = Gzip::new(Compression::fast());
+        let mut compressed = gzip.compress(b"HelloWorld").unwrap();
+        let crc = compressed.len() - 8;
+        compressed[crc] ^= 0xff;
+        assert!(gzip.decompress(&compressed).is_err());
+    }
+
+    #[test]
+    fn rejects_input_without_gzip_header() {
+        assert!(Gzip::new(Compression::fast())
+            .decompress(b"not gzip at all")
+            .is_err());
+    }
+
+    #[test]
+    fn enforces_output_limit() {
+        check::enforces_limit(&Gzip::new(Compression::fast()));
+    }
+
+    #[test]
+    fn names_levels() {
+        let levels: Vec<String> = BACKEND.levels.iter().map(|codec| codec.level()).collect();
+        assert_eq!(levels, ["Fast", "Default", "Best"]);
+        assert_eq!(Gzip::new(Compression::new(4)).level(), "4");
+    }
+}
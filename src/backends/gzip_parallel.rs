@@ -0,0 +1,105 @@
+use super::Gzip;
+use crate::Compressor;
+use flate2::read::MultiGzDecoder;
+use flate2::Compression;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::io::{self, Read};
+
+pub const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024; // 1MB
+
+// pigz-style gzip: fixed-size chunks compressed in parallel and concatenated as gzip members,
+// which any gzip reader accepts. Decompression stays serial, as it does for pigz
+pub struct ParallelGzip {
+    gzip: Gzip,
+    threads: usize,
+    chunk_size: usize,
+    pool: ThreadPool,
+}
+
+impl ParallelGzip {
+    pub fn new(level: Compression, threads: usize, chunk_size: usize) -> io::Result<Self> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(io::Error::other)?;
+        Ok(ParallelGzip {
+            gzip: Gzip::new(level),
+            threads,
+            chunk_size,
+            pool,
+        })
+    }
+}
+
+impl Compressor for ParallelGzip {
+    fn name(&self) -> &'static str {
+        "Gzip-Parallel"
+    }
+
+    fn level(&self) -> String {
+        format!("{} x{}", self.gzip.level(), self.threads)
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        // No chunks would mean no members, and an empty file is not a gzip stream
+        if data.is_empty() {
+            return self.gzip.compress(data);
+        }
+        let members = self.pool.install(|| {
+            data.par_chunks(self.chunk_size)
+                .map(|chunk| self.gzip.compress(chunk))
+                .collect::<io::Result<Vec<Vec<u8>>>>()
+        })?;
+        Ok(members.concat())
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut decompressed = Vec::new();
+        MultiGzDecoder::new(data).read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+
+    fn decompress_limited(&self, data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        self.gzip.decompress_limited(data, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::check;
+
+    // Small chunks, so every input but the tiny ones is split across members
+    fn parallel() -> ParallelGzip {
+        ParallelGzip::new(Compression::fast(), 2, 16 * 1024).unwrap()
+    }
+
+    #[test]
+    fn round_trips() {
+        check::round_trips(&parallel());
+    }
+
+    #[test]
+    fn output_is_readable_by_plain_gzip() {
+        let data = b"HelloWorld".repeat(10_000);
+        let compressed = parallel().compress(&data).unwrap();
+        assert_eq!(
+            Gzip::new(Compression::fast())
+                .decompress(&compressed)
+                .unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_stream() {
+        let parallel = parallel();
+        assert!(parallel.decompress(&check::truncated(&parallel)).is_err());
+    }
+
+    #[test]
+    fn enforces_output_limit() {
+        check::enforces_limit(&parallel());
+    }
+}
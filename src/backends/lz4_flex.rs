@@ -0,0 +1,92 @@
+use crate::{limit_exceeded, Backend, Compressor};
+use lz4_flex::{compress_prepend_size, decompress_size_prepended};
+use std::io;
+
+pub const BACKEND: Backend = Backend {
+    id: "lz4-flex",
+    levels: &[&Lz4Flex],
+    // A bare size-prefixed block, indistinguishable from arbitrary bytes
+    magic: &[],
+};
+
+// lz4_flex block format with the uncompressed size prepended; it has no levels, and being a
+// single block it has no streaming encoder
+#[derive(Clone, Copy)]
+pub struct Lz4Flex;
+
+impl Compressor for Lz4Flex {
+    fn name(&self) -> &'static str {
+        "LZ4-Flex"
+    }
+
+    fn level(&self) -> String {
+        "Default".to_string()
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        Ok(compress_prepend_size(data))
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        decompress_size_prepended(data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    // The prepended size is checked before lz4_flex allocates a buffer of that size
+    fn decompress_limited(&self, data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        let size = data
+            .get(..4)
+            .map(|header| u32::from_le_bytes(header.try_into().unwrap()) as usize)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "missing size header"))?;
+        if size > limit {
+            return Err(limit_exceeded(limit));
+        }
+        self.decompress(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::check;
+
+    #[test]
+    fn round_trips() {
+        check::round_trips(&Lz4Flex);
+    }
+
+    #[test]
+    fn has_no_streaming_encoder() {
+        let error = Lz4Flex.compress_streamed(b"HelloWorld", 4).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn rejects_missing_size_header() {
+        assert!(Lz4Flex.decompress(&[0x01, 0x00]).is_err());
+        assert!(Lz4Flex.decompress_limited(&[0x01, 0x00], 1024).is_err());
+    }
+
+    // A bare block has no end marker or checksum, so a cut between sequences decodes without an
+    // error; the output still falls short of the prepended size
+    #[test]
+    fn truncated_block_never_restores_declared_size() {
+        let truncated = check::truncated(&Lz4Flex);
+        let declared = u32::from_le_bytes(truncated[..4].try_into().unwrap()) as usize;
+        if let Ok(restored) = Lz4Flex.decompress(&truncated) {
+            assert!(restored.len() < declared);
+        }
+    }
+
+    #[test]
+    fn enforces_output_limit() {
+        check::enforces_limit(&Lz4Flex);
+    }
+
+    #[test]
+    fn refuses_forged_size_before_decoding() {
+        let mut forged = u32::MAX.to_le_bytes().to_vec();
+        forged.extend_from_slice(&[0x10, b'x']);
+        let error = Lz4Flex.decompress_limited(&forged, 1024).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::FileTooLarge);
+    }
+}
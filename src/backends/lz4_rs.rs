@@ -0,0 +1,130 @@
+use crate::{read_limited, Backend, Compressor, Instant, StreamPhases};
+use lz4::{Decoder, EncoderBuilder};
+use std::io::{self, Read, Write};
+
+pub const BACKEND: Backend = Backend {
+    id: "lz4-rs",
+    levels: &[&Lz4Rs::new(0), &Lz4Rs::new(4), &Lz4Rs::new(16)],
+    // LZ4 frame format, as written by the lz4 CLI
+    magic: &[0x04, 0x22, 0x4d, 0x18],
+};
+
+// The lz4 crate's frame format at a given level (0-16)
+#[derive(Clone, Copy)]
+pub struct Lz4Rs {
+    level: u32,
+}
+
+impl Lz4Rs {
+    pub const fn new(level: u32) -> Self {
+        Lz4Rs { level }
+    }
+
+    // Streams the compressed output into any sink, e.g. a `Vec` or a `BytesMut` writer
+    pub fn compress_into<W: Write>(&self, data: &[u8], sink: W) -> io::Result<W> {
+        let mut encoder = EncoderBuilder::new().level(self.level).build(sink)?;
+        encoder.write_all(data)?;
+        let (sink, result) = encoder.finish();
+        result.map(|()| sink)
+    }
+}
+
+impl Compressor for Lz4Rs {
+    fn name(&self) -> &'static str {
+        "LZ4-RS"
+    }
+
+    fn level(&self) -> String {
+        match self.level {
+            0 => "Fast".to_string(),
+            4 => "Default".to_string(),
+            16 => "Best".to_string(),
+            level => level.to_string(),
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        self.compress_into(data, Vec::new())
+    }
+
+    fn compress_phased(
+        &self,
+        data: &[u8],
+        write_size: usize,
+    ) -> io::Result<(Vec<u8>, StreamPhases)> {
+        let start = Instant::now();
+        let mut encoder = EncoderBuilder::new().level(self.level).build(Vec::new())?;
+        for chunk in data.chunks(write_size) {
+            encoder.write_all(chunk)?;
+        }
+        let finish = Instant::now();
+        let (compressed, result) = encoder.finish();
+        result.map(|()| (compressed, StreamPhases::since(start, finish)))
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut decoder = Decoder::new(data)?;
+        let mut decompressed = Vec::new();
+        decoder.read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+
+    fn decompress_limited(&self, data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        read_limited(Decoder::new(data)?, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::check;
+
+    #[test]
+    fn round_trips_at_every_level() {
+        for codec in BACKEND.levels {
+            check::round_trips(*codec);
+        }
+    }
+
+    #[test]
+    fn writes_frame_magic() {
+        let compressed = Lz4Rs::new(0).compress(b"HelloWorld").unwrap();
+        assert!(compressed.starts_with(BACKEND.magic));
+    }
+
+    #[test]
+    fn rejects_corrupted_content_checksum() {
+        let lz4 = Lz4Rs::new(0);
+        let mut compressed = lz4.compress(b"HelloWorld").unwrap();
+        let last = compressed.len() - 1;
+        compressed[last] ^= 0xff;
+        assert!(lz4.decompress(&compressed).is_err());
+    }
+
+    // liblz4's reader treats the end of input as the end of the frame, so a cut frame decodes to
+    // the blocks before the cut rather than failing
+    #[test]
+    fn truncated_frame_never_restores_full_input() {
+        let lz4 = Lz4Rs::new(0);
+        if let Ok(restored) = lz4.decompress(&check::truncated(&lz4)) {
+            assert!(restored.len() < check::TRUNCATED_SOURCE);
+        }
+    }
+
+    #[test]
+    fn rejects_input_without_frame_magic() {
+        assert!(Lz4Rs::new(0).decompress(b"not an lz4 frame").is_err());
+    }
+
+    #[test]
+    fn enforces_output_limit() {
+        check::enforces_limit(&Lz4Rs::new(0));
+    }
+
+    #[test]
+    fn names_levels() {
+        let levels: Vec<String> = BACKEND.levels.iter().map(|codec| codec.level()).collect();
+        assert_eq!(levels, ["Fast", "Default", "Best"]);
+        assert_eq!(Lz4Rs::new(9).level(), "9");
+    }
+}
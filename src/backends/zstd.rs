@@ -0,0 +1,130 @@
+use crate::{read_limited, Backend, Compressor, Instant, StreamPhases};
+use std::io::{self, Write};
+use zstd::bulk::Compressor as BulkCompressor;
+use zstd::zstd_safe::CParameter;
+
+pub const BACKEND: Backend = Backend {
+    id: "zstd",
+    levels: &[&Zstd::new(1), &Zstd::new(3), &Zstd::new(19)],
+    magic: &[0x28, 0xb5, 0x2f, 0xfd],
+};
+
+// zstd's single-shot API at a given level, optionally spread over zstd's own worker threads
+#[derive(Clone, Copy)]
+pub struct Zstd {
+    level: i32,
+    // 0 compresses on the calling thread; zstd only parallelizes compression
+    workers: u32,
+}
+
+impl Zstd {
+    pub const fn new(level: i32) -> Self {
+        Zstd { level, workers: 0 }
+    }
+
+    pub const fn multithreaded(level: i32, workers: u32) -> Self {
+        Zstd { level, workers }
+    }
+}
+
+impl Compressor for Zstd {
+    fn name(&self) -> &'static str {
+        if self.workers > 0 {
+            "Zstd-MT"
+        } else {
+            "Zstd"
+        }
+    }
+
+    fn level(&self) -> String {
+        let level = match self.level {
+            1 => "Fast".to_string(),
+            3 => "Default".to_string(),
+            19 => "Best".to_string(),
+            level => level.to_string(),
+        };
+        if self.workers > 0 {
+            format!("{} x{}", level, self.workers)
+        } else {
+            level
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        if self.workers == 0 {
+            return zstd::bulk::compress(data, self.level);
+        }
+        let mut compressor = BulkCompressor::new(self.level)?;
+        compressor.set_parameter(CParameter::NbWorkers(self.workers))?;
+        compressor.compress(data)
+    }
+
+    fn compress_phased(
+        &self,
+        data: &[u8],
+        write_size: usize,
+    ) -> io::Result<(Vec<u8>, StreamPhases)> {
+        let start = Instant::now();
+        let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), self.level)?;
+        if self.workers > 0 {
+            encoder.multithread(self.workers)?;
+        }
+        for chunk in data.chunks(write_size) {
+            encoder.write_all(chunk)?;
+        }
+        let finish = Instant::now();
+        let compressed = encoder.finish()?;
+        Ok((compressed, StreamPhases::since(start, finish)))
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        zstd::stream::decode_all(data)
+    }
+
+    fn decompress_limited(&self, data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        read_limited(zstd::stream::read::Decoder::new(data)?, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::check;
+
+    #[test]
+    fn round_trips_at_every_level() {
+        for codec in BACKEND.levels {
+            check::round_trips(*codec);
+        }
+    }
+
+    #[test]
+    fn round_trips_multithreaded() {
+        check::round_trips(&Zstd::multithreaded(3, 2));
+    }
+
+    #[test]
+    fn rejects_truncated_frame() {
+        let zstd = Zstd::new(1);
+        assert!(zstd.decompress(&check::truncated(&zstd)).is_err());
+    }
+
+    #[test]
+    fn rejects_input_without_frame_magic() {
+        assert!(Zstd::new(1).decompress(b"not a zstd frame").is_err());
+    }
+
+    #[test]
+    fn enforces_output_limit() {
+        check::enforces_limit(&Zstd::new(1));
+    }
+
+    #[test]
+    fn names_levels_and_workers() {
+        let levels: Vec<String> = BACKEND.levels.iter().map(|codec| codec.level()).collect();
+        assert_eq!(levels, ["Fast", "Default", "Best"]);
+        let multithreaded = Zstd::multithreaded(3, 4);
+        assert_eq!(multithreaded.name(), "Zstd-MT");
+        assert_eq!(multithreaded.level(), "Default x4");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::results::{CellResult, RunResults};
+use rust_compress_comparison::error::Result;
+use rust_compress_comparison::CompressionBenchError;
+use std::path::{Path, PathBuf};
+
+// Percentage changes for one cell present in both runs
+pub struct CellDelta<'a> {
+    pub cell: &'a CellResult,
+    pub ratio_pct: f64,
+    pub compress_pct: f64,
+    pub decompress_pct: Option<f64>,
+}
+
+// How much worse a cell may get before the comparison fails
+pub struct Thresholds {
+    pub time_pct: f64,
+    pub ratio_pct: f64,
+}
+
+impl CellDelta<'_> {
+    // Time going up or ratio going down beyond the thresholds
+    pub fn is_regression(&self, thresholds: &Thresholds) -> bool {
+        self.compress_pct > thresholds.time_pct
+            || self
+                .decompress_pct
+                .is_some_and(|pct| pct > thresholds.time_pct)
+            || -self.ratio_pct > thresholds.ratio_pct
+    }
+}
+
+pub fn baseline_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.json", name))
+}
+
+pub fn save(results: &RunResults, dir: &Path, name: &str) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).map_err(|source| CompressionBenchError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+    let path = baseline_path(dir, name);
+    results.save(&path)?;
+    Ok(path)
+}
+
+pub fn load(dir: &Path, name: &str) -> Result<RunResults> {
+    RunResults::load(&baseline_path(dir, name))
+}
+
+fn pct_change(old: f64, new: f64) -> f64 {
+    (new - old) / old * 100.0
+}
+
+// Pairs cells by data type, algorithm and level; unmatched cells are skipped
+pub fn compare<'a>(old: &RunResults, new: &'a RunResults) -> Vec<CellDelta<'a>> {
+    new.cells
+        .iter()
+        .filter_map(|cell| {
+            let previous = old.matching(cell)?;
+            let decompress_pct = (previous.decompress_secs > 0.0 && cell.decompress_secs > 0.0)
+                .then(|| pct_change(previous.decompress_secs, cell.decompress_secs));
+            Some(CellDelta {
+                cell,
+                ratio_pct: pct_change(previous.ratio, cell.ratio),
+                compress_pct: pct_change(previous.compress_secs, cell.compress_secs),
+                decompress_pct,
+            })
+        })
+        .collect()
+}
+
+// Prints the delta table and returns how many cells regressed
+pub fn print_comparison(deltas: &[CellDelta], thresholds: &Thresholds) -> usize {
+    println!(
+        "\n{:<10} {:<20} {:>9} {:>11} {:>11}",
+        "Data", "Algorithm", "Ratio", "Comp time", "Decomp time"
+    );
+    println!("{}", "-".repeat(65));
+    let mut regressions = 0;
+    for delta in deltas {
+        let regressed = delta.is_regression(thresholds);
+        if regressed {
+            regressions += 1;
+        }
+        println!(
+            "{:<10} {:<20} {:>+8.1}% {:>+10.1}% {:>11}{}",
+            delta.cell.data_type,
+            delta.cell.label(),
+            delta.ratio_pct,
+            delta.compress_pct,
+            delta
+                .decompress_pct
+                .map_or("n/a".to_string(), |pct| format!("{:+.1}%", pct)),
+            if regressed { "  REGRESSION" } else { "" }
+        );
+    }
+    regressions
+}
@@ -0,0 +1,93 @@
+use rust_compress_comparison::memory;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::io;
+use std::time::Instant;
+
+const MB: usize = 1024 * 1024;
+
+// Time and peak memory of one decompression, and whether it was stopped at the limit
+struct Decode {
+    secs: f64,
+    peak_rss: Option<u64>,
+    outcome: io::Result<usize>,
+}
+
+fn decode(decompress: impl FnOnce() -> io::Result<Vec<u8>>) -> Decode {
+    let baseline = memory::reset_peak();
+    let start = Instant::now();
+    let outcome = decompress().map(|decompressed| decompressed.len());
+    let secs = start.elapsed().as_secs_f64();
+    Decode {
+        secs,
+        peak_rss: memory::peak_since(baseline),
+        outcome,
+    }
+}
+
+fn describe(decode: &Decode) -> String {
+    let rss = decode
+        .peak_rss
+        .map(|bytes| format!("{:.1}MB", bytes as f64 / MB as f64))
+        .unwrap_or_else(|| "-".to_string());
+    let outcome = match &decode.outcome {
+        Ok(len) => format!("{}MB out", len / MB),
+        Err(e) if e.kind() == io::ErrorKind::FileTooLarge => "stopped".to_string(),
+        Err(e) => format!("FAILED: {}", e),
+    };
+    format!("{:>9.3}s {:>10} {:>10}", decode.secs, rss, outcome)
+}
+
+// Small inputs that expand enormously, decoded with no bound and then through
+// `decompress_limited`: how much time and memory a caller spends before a bomb is refused
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    size_mb: usize,
+    limit_mb: usize,
+) -> Result<(), Box<dyn Error>> {
+    let zeros = vec![0u8; size_mb * MB];
+    let limit = limit_mb * MB;
+    println!(
+        "\nDecompression bombs ({}MB of zeros, output limit {}MB)\n",
+        size_mb, limit_mb
+    );
+    println!(
+        "{:<18} {:>10} {:>11} | {:>10} {:>10} {:>10} | {:>10} {:>10} {:>10}",
+        "Configuration",
+        "Bomb",
+        "Expansion",
+        "Unbounded",
+        "Peak RSS",
+        "Result",
+        "Limited",
+        "Peak RSS",
+        "Result"
+    );
+    println!("{}", "-".repeat(113));
+    for codec in algorithms {
+        let label = format!("{} {}", codec.name(), codec.level());
+        let bomb = match codec.compress(&zeros) {
+            Ok(bomb) => bomb,
+            Err(e) => {
+                println!("{:<18} FAILED: {}", label, e);
+                continue;
+            }
+        };
+        let unbounded = decode(|| codec.decompress(&bomb));
+        let limited = decode(|| codec.decompress_limited(&bomb, limit));
+        println!(
+            "{:<18} {:>8.1}KB {:>10.0}x | {} | {}",
+            label,
+            bomb.len() as f64 / 1024.0,
+            zeros.len() as f64 / bomb.len() as f64,
+            describe(&unbounded),
+            describe(&limited)
+        );
+    }
+    println!(
+        "\nStopped: refused once output passed the limit. Gzip, LZ4-RS and Zstd stream into a \
+         buffer capped at the limit; LZ4-Flex checks its prepended size before allocating. A \
+         bare read_to_end (GzDecoder) or decode_all has no cap: memory grows with the bomb"
+    );
+    Ok(())
+}
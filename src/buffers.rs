@@ -0,0 +1,209 @@
+use crate::{generate_test_data, TestData};
+use rust_compress_comparison::backends::{Gzip, Lz4Rs};
+use rust_compress_comparison::memory::{self, AllocationCount};
+use std::io;
+use std::time::Instant;
+
+const PAYLOAD_SIZES: [usize; 3] = [4 * 1024, 64 * 1024, 1024 * 1024];
+// Input compressed per path and payload size; small payloads make many calls
+const BYTES_PER_PATH: usize = 1024 * 1024 * 32; // 32MB
+
+// Compresses one payload, returning the compressed length
+type CompressCall = Box<dyn FnMut(&[u8]) -> io::Result<usize>>;
+
+// One codec compressed three ways: into a fresh Vec per call grown as output arrives, into a
+// fresh Vec sized up front to the worst-case compressed size, and into one buffer reused
+// throughout. All keep their codec context, so the differences are the output allocation alone
+struct BufferCase {
+    name: &'static str,
+    allocating: CompressCall,
+    sized: CompressCall,
+    preallocated: CompressCall,
+}
+
+fn invalid(e: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+// zlib's compressBound plus the 12 bytes a gzip header and trailer add over zlib's
+fn gzip_bound(len: usize) -> usize {
+    len + (len >> 12) + (len >> 14) + (len >> 25) + 13 + 12
+}
+
+// LZ4F_compressBound covers the blocks and end mark at default preferences, not the header
+fn lz4_frame_bound(len: usize) -> usize {
+    const MAX_HEADER: usize = 19;
+    // SAFETY: a null preferences pointer is documented to mean the defaults
+    MAX_HEADER + unsafe { lz4::liblz4::LZ4F_compressBound(len, std::ptr::null()) }
+}
+
+fn cases(max_payload: usize) -> Vec<BufferCase> {
+    let gzip = Gzip::new(flate2::Compression::new(6));
+    let mut gzip_buffer = Vec::with_capacity(max_payload + 1024);
+    let lz4 = Lz4Rs::new(4);
+    let mut lz4_buffer = Vec::with_capacity(max_payload + 1024);
+    let mut lz4_flex_buffer = vec![0u8; lz4_flex::block::get_maximum_output_size(max_payload)];
+    let mut zstd_allocating = zstd::bulk::Compressor::new(3).expect("zstd context");
+    let mut zstd_sized = zstd::bulk::Compressor::new(3).expect("zstd context");
+    let mut zstd_preallocated = zstd::bulk::Compressor::new(3).expect("zstd context");
+    let mut zstd_buffer = Vec::with_capacity(zstd::zstd_safe::compress_bound(max_payload));
+
+    #[allow(unused_mut)]
+    let mut cases = vec![
+        BufferCase {
+            name: "Gzip compress_into",
+            allocating: Box::new(move |data| Ok(gzip.compress_into(data, Vec::new())?.len())),
+            sized: Box::new(move |data| {
+                let output = Vec::with_capacity(gzip_bound(data.len()));
+                Ok(gzip.compress_into(data, output)?.len())
+            }),
+            preallocated: Box::new(move |data| {
+                gzip_buffer.clear();
+                Ok(gzip.compress_into(data, &mut gzip_buffer)?.len())
+            }),
+        },
+        BufferCase {
+            name: "LZ4-RS compress_into",
+            allocating: Box::new(move |data| Ok(lz4.compress_into(data, Vec::new())?.len())),
+            sized: Box::new(move |data| {
+                let output = Vec::with_capacity(lz4_frame_bound(data.len()));
+                Ok(lz4.compress_into(data, output)?.len())
+            }),
+            preallocated: Box::new(move |data| {
+                lz4_buffer.clear();
+                Ok(lz4.compress_into(data, &mut lz4_buffer)?.len())
+            }),
+        },
+        BufferCase {
+            name: "LZ4-Flex compress_into",
+            // block::compress already sizes its output to the bound, so these two should match
+            allocating: Box::new(|data| Ok(lz4_flex::block::compress(data).len())),
+            sized: Box::new(|data| {
+                let mut output = vec![0u8; lz4_flex::block::get_maximum_output_size(data.len())];
+                let len = lz4_flex::block::compress_into(data, &mut output).map_err(invalid)?;
+                output.truncate(len);
+                Ok(output.len())
+            }),
+            preallocated: Box::new(move |data| {
+                lz4_flex::block::compress_into(data, &mut lz4_flex_buffer).map_err(invalid)
+            }),
+        },
+        BufferCase {
+            name: "Zstd compress_to_buffer",
+            // As does bulk::Compressor::compress
+            allocating: Box::new(move |data| Ok(zstd_allocating.compress(data)?.len())),
+            sized: Box::new(move |data| {
+                let mut output = Vec::with_capacity(zstd::zstd_safe::compress_bound(data.len()));
+                zstd_sized.compress_to_buffer(data, &mut output)?;
+                Ok(output.len())
+            }),
+            preallocated: Box::new(move |data| {
+                zstd_buffer.clear();
+                zstd_preallocated.compress_to_buffer(data, &mut zstd_buffer)
+            }),
+        },
+    ];
+
+    // libdeflate only compresses into a caller-sized slice; its Vec path sizes a fresh one per call
+    #[cfg(feature = "libdeflate")]
+    {
+        use libdeflater::{CompressionLvl, Compressor};
+        let mut allocating = Compressor::new(CompressionLvl::default());
+        let mut sized = Compressor::new(CompressionLvl::default());
+        let mut preallocated = Compressor::new(CompressionLvl::default());
+        let mut buffer = vec![0u8; preallocated.gzip_compress_bound(max_payload)];
+        cases.push(BufferCase {
+            name: "Libdeflate gzip_compress",
+            // There is no growing path, so the allocating side is the sized one
+            allocating: Box::new(move |data| {
+                let mut output = vec![0u8; allocating.gzip_compress_bound(data.len())];
+                let len = allocating
+                    .gzip_compress(data, &mut output)
+                    .map_err(invalid)?;
+                output.truncate(len);
+                Ok(output.len())
+            }),
+            sized: Box::new(move |data| {
+                let mut output = vec![0u8; sized.gzip_compress_bound(data.len())];
+                let len = sized.gzip_compress(data, &mut output).map_err(invalid)?;
+                output.truncate(len);
+                Ok(output.len())
+            }),
+            preallocated: Box::new(move |data| {
+                preallocated
+                    .gzip_compress(data, &mut buffer)
+                    .map_err(invalid)
+            }),
+        });
+    }
+    cases
+}
+
+// Input MB/s over every payload, and allocations per call where they are counted
+fn measure(
+    compress: &mut dyn FnMut(&[u8]) -> io::Result<usize>,
+    payloads: &[&[u8]],
+) -> io::Result<(f64, Option<AllocationCount>)> {
+    let before = memory::allocation_count();
+    let start = Instant::now();
+    for payload in payloads {
+        compress(payload)?;
+    }
+    let secs = start.elapsed().as_secs_f64();
+    let allocations = memory::allocation_count()
+        .zip(before)
+        .map(|(after, before)| {
+            let total = after.since(before);
+            AllocationCount {
+                count: total.count / payloads.len() as u64,
+                bytes: total.bytes / payloads.len() as u64,
+            }
+        });
+    let mb = payloads.iter().map(|p| p.len()).sum::<usize>() as f64 / (1024.0 * 1024.0);
+    Ok((mb / secs, allocations))
+}
+
+pub fn run() -> io::Result<()> {
+    let max_payload = PAYLOAD_SIZES[PAYLOAD_SIZES.len() - 1];
+    let data = generate_test_data(&TestData::Mixed, BYTES_PER_PATH);
+    let counted = memory::allocation_count().is_some();
+
+    println!("\n=== Vec-Allocating vs Preallocated Output Buffers (mixed data) ===\n");
+    println!(
+        "{:<26} {:>8} {:>10} {:>10} {:>8} {:>12} {:>8} {:>16}",
+        "API", "Payload", "New MB/s", "Sized MB/s", "Growth", "Reused MB/s", "Gain", "Allocs/call"
+    );
+    println!("{}", "-".repeat(105));
+    for mut case in cases(max_payload) {
+        for size in PAYLOAD_SIZES {
+            let payloads: Vec<&[u8]> = data.chunks(size).collect();
+            // One untimed pass each so neither side pays for first-touch page faults
+            (case.allocating)(payloads[0])?;
+            (case.sized)(payloads[0])?;
+            (case.preallocated)(payloads[0])?;
+            let (vec_mbps, vec_allocs) = measure(&mut case.allocating, &payloads)?;
+            let (sized_mbps, sized_allocs) = measure(&mut case.sized, &payloads)?;
+            let (prealloc_mbps, prealloc_allocs) = measure(&mut case.preallocated, &payloads)?;
+            let allocs = match (vec_allocs, sized_allocs, prealloc_allocs) {
+                (Some(v), Some(s), Some(p)) => format!("{} / {} / {}", v.count, s.count, p.count),
+                _ => "-".to_string(),
+            };
+            println!(
+                "{:<26} {:>7}K {:>10.1} {:>10.1} {:>7.1}% {:>12.1} {:>7.1}% {:>16}",
+                case.name,
+                size / 1024,
+                vec_mbps,
+                sized_mbps,
+                (sized_mbps - vec_mbps) / vec_mbps * 100.0,
+                prealloc_mbps,
+                (prealloc_mbps - vec_mbps) / vec_mbps * 100.0,
+                allocs
+            );
+        }
+    }
+    println!("\nGrowth: Vec::with_capacity(worst case) against Vec::new(); Gain: one reused buffer against Vec::new()");
+    if !counted {
+        println!("\nBuild with --features count-allocations to count allocations per call");
+    }
+    Ok(())
+}
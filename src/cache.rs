@@ -0,0 +1,67 @@
+// Whether a trial's inputs start out in the CPU caches, as in a tight in-memory loop, or have to
+// come from DRAM, as when a batch job compresses data it has just read off disk
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+// Bytes swept to push other data out where cache lines can't be flushed directly; comfortably
+// larger than the last-level caches of current desktop and server parts
+#[cfg(not(target_arch = "x86_64"))]
+const SWEEP_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheState {
+    /// Inputs are left wherever the previous step put them, usually cached
+    #[default]
+    Warm,
+    /// Inputs are evicted from every cache level before each timed call
+    Cold,
+}
+
+impl CacheState {
+    pub fn name(self) -> &'static str {
+        match self {
+            CacheState::Warm => "warm",
+            CacheState::Cold => "cold",
+        }
+    }
+
+    // Evicts `data` when cold; a no-op when warm
+    pub fn prepare(self, data: &[u8]) {
+        if self == CacheState::Cold {
+            evict(data);
+        }
+    }
+}
+
+// Flushes every line of `data` from all cache levels and waits for the flushes to complete
+#[cfg(target_arch = "x86_64")]
+pub fn evict(data: &[u8]) {
+    use std::arch::x86_64::{_mm_clflush, _mm_mfence};
+    const LINE: usize = 64;
+    // SAFETY: every address flushed lies inside `data`; clflush is baseline on x86_64
+    unsafe {
+        for offset in (0..data.len()).step_by(LINE) {
+            _mm_clflush(data.as_ptr().add(offset));
+        }
+        _mm_mfence();
+    }
+}
+
+// Without a portable flush instruction, writes over a buffer larger than the caches instead
+#[cfg(not(target_arch = "x86_64"))]
+pub fn evict(data: &[u8]) {
+    use std::cell::RefCell;
+    use std::hint::black_box;
+    thread_local! {
+        static SWEEP: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+    black_box(data);
+    SWEEP.with_borrow_mut(|sweep| {
+        sweep.resize(SWEEP_BYTES, 0);
+        for line in sweep.chunks_mut(64) {
+            line[0] = line[0].wrapping_add(1);
+        }
+        black_box(sweep);
+    });
+}
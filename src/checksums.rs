@@ -0,0 +1,330 @@
+use crate::results::percentile;
+use crate::{generate_test_data, TestData};
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use lz4::liblz4::BlockChecksum;
+use lz4::{ContentChecksum, Decoder, EncoderBuilder};
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::time::Instant;
+use zstd::bulk::Compressor as BulkCompressor;
+use zstd::zstd_safe::CParameter;
+
+type Codec = fn(&[u8]) -> io::Result<Vec<u8>>;
+
+// A hash run only for its cost
+type Hash = fn(&[u8]);
+
+// One format with its integrity check set one way; the first variant of each group has none
+// and is the baseline the others are measured against
+struct Variant {
+    group: &'static str,
+    check: &'static str,
+    compress: Codec,
+    decompress: Codec,
+}
+
+const VARIANTS: [Variant; 13] = [
+    Variant {
+        group: "Deflate 6",
+        check: "none (raw)",
+        compress: |data| deflate_into(DeflateEncoder::new(Vec::new(), Compression::new(6)), data),
+        decompress: |data| read_all(DeflateDecoder::new(data)),
+    },
+    Variant {
+        group: "Deflate 6",
+        check: "Adler-32 (zlib)",
+        compress: |data| deflate_into(ZlibEncoder::new(Vec::new(), Compression::new(6)), data),
+        decompress: |data| read_all(ZlibDecoder::new(data)),
+    },
+    Variant {
+        group: "Deflate 6",
+        check: "CRC32 (gzip)",
+        compress: |data| deflate_into(GzEncoder::new(Vec::new(), Compression::new(6)), data),
+        decompress: |data| read_all(GzDecoder::new(data)),
+    },
+    Variant {
+        group: "LZ4 frame",
+        check: "none",
+        compress: |data| lz4_frame(data, false, false),
+        decompress: |data| read_all(Decoder::new(data)?),
+    },
+    Variant {
+        group: "LZ4 frame",
+        check: "xxHash32 content",
+        compress: |data| lz4_frame(data, true, false),
+        decompress: |data| read_all(Decoder::new(data)?),
+    },
+    Variant {
+        group: "LZ4 frame",
+        check: "xxHash32 blocks",
+        compress: |data| lz4_frame(data, false, true),
+        decompress: |data| read_all(Decoder::new(data)?),
+    },
+    Variant {
+        group: "LZ4 frame",
+        check: "xxHash32 both",
+        compress: |data| lz4_frame(data, true, true),
+        decompress: |data| read_all(Decoder::new(data)?),
+    },
+    Variant {
+        group: "Zstd 3",
+        check: "none",
+        compress: |data| zstd_frame(data, false),
+        decompress: |data| zstd::stream::decode_all(data),
+    },
+    Variant {
+        group: "Zstd 3",
+        check: "XXH64 content",
+        compress: |data| zstd_frame(data, true),
+        decompress: |data| zstd::stream::decode_all(data),
+    },
+    Variant {
+        group: "LZ4 block",
+        check: "none",
+        compress: |data| Ok(lz4_flex::compress_prepend_size(data)),
+        decompress: |data| lz4_block(data),
+    },
+    Variant {
+        group: "LZ4 block",
+        check: "CRC32 (crc32fast)",
+        compress: |data| Ok(sealed(data, &crc32fast::hash(data).to_le_bytes())),
+        decompress: |data| unsealed(data, 4, |raw| crc32fast::hash(raw).to_le_bytes().to_vec()),
+    },
+    Variant {
+        group: "LZ4 block",
+        check: "XXH3-64",
+        compress: |data| {
+            Ok(sealed(
+                data,
+                &xxhash_rust::xxh3::xxh3_64(data).to_le_bytes(),
+            ))
+        },
+        decompress: |data| {
+            unsealed(data, 8, |raw| {
+                xxhash_rust::xxh3::xxh3_64(raw).to_le_bytes().to_vec()
+            })
+        },
+    },
+    Variant {
+        group: "LZ4 block",
+        check: "BLAKE3",
+        compress: |data| Ok(sealed(data, blake3::hash(data).as_bytes())),
+        decompress: |data| unsealed(data, 32, |raw| blake3::hash(raw).as_bytes().to_vec()),
+    },
+];
+
+fn deflate_into<W: Write + Finish>(mut encoder: W, data: &[u8]) -> io::Result<Vec<u8>> {
+    encoder.write_all(data)?;
+    encoder.finish_vec()
+}
+
+// flate2's encoders share no trait for finish()
+trait Finish {
+    fn finish_vec(self) -> io::Result<Vec<u8>>;
+}
+
+impl Finish for DeflateEncoder<Vec<u8>> {
+    fn finish_vec(self) -> io::Result<Vec<u8>> {
+        self.finish()
+    }
+}
+
+impl Finish for ZlibEncoder<Vec<u8>> {
+    fn finish_vec(self) -> io::Result<Vec<u8>> {
+        self.finish()
+    }
+}
+
+impl Finish for GzEncoder<Vec<u8>> {
+    fn finish_vec(self) -> io::Result<Vec<u8>> {
+        self.finish()
+    }
+}
+
+fn read_all(mut decoder: impl Read) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    decoder.read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+// liblz4 at its fast level, the frame's content and per-block checksums set independently
+fn lz4_frame(data: &[u8], content: bool, blocks: bool) -> io::Result<Vec<u8>> {
+    let mut encoder = EncoderBuilder::new()
+        .level(0)
+        .checksum(if content {
+            ContentChecksum::ChecksumEnabled
+        } else {
+            ContentChecksum::NoChecksum
+        })
+        .block_checksum(if blocks {
+            BlockChecksum::BlockChecksumEnabled
+        } else {
+            BlockChecksum::NoBlockChecksum
+        })
+        .build(Vec::new())?;
+    encoder.write_all(data)?;
+    let (compressed, result) = encoder.finish();
+    result.map(|()| compressed)
+}
+
+fn zstd_frame(data: &[u8], checksum: bool) -> io::Result<Vec<u8>> {
+    let mut compressor = BulkCompressor::new(3)?;
+    compressor.set_parameter(CParameter::ChecksumFlag(checksum))?;
+    compressor.compress(data)
+}
+
+fn lz4_block(data: &[u8]) -> io::Result<Vec<u8>> {
+    lz4_flex::decompress_size_prepended(data)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// A raw lz4 block followed by a digest of the uncompressed input, the way an application adds
+// its own check around a format that has none
+fn sealed(data: &[u8], digest: &[u8]) -> Vec<u8> {
+    let mut compressed = lz4_flex::compress_prepend_size(data);
+    compressed.extend_from_slice(digest);
+    compressed
+}
+
+fn unsealed(data: &[u8], digest_len: usize, hash: fn(&[u8]) -> Vec<u8>) -> io::Result<Vec<u8>> {
+    let split = data
+        .len()
+        .checked_sub(digest_len)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "missing digest"))?;
+    let decompressed = lz4_block(&data[..split])?;
+    if hash(&decompressed) != data[split..] {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "digest mismatch",
+        ));
+    }
+    Ok(decompressed)
+}
+
+struct Measured {
+    size: usize,
+    compress: f64,
+    decompress: f64,
+}
+
+// A format's variants measured in turn within each trial, so drift in clock speed or load
+// during the run lands on all of them rather than skewing the comparison
+fn measure(variants: &[Variant], data: &[u8], trials: usize) -> io::Result<Vec<Measured>> {
+    let mut sizes = vec![0; variants.len()];
+    let mut compress = vec![Vec::with_capacity(trials); variants.len()];
+    let mut decompress = vec![Vec::with_capacity(trials); variants.len()];
+    for _ in 0..trials {
+        for (i, variant) in variants.iter().enumerate() {
+            let start = Instant::now();
+            let compressed = (variant.compress)(data)?;
+            compress[i].push(start.elapsed().as_secs_f64());
+            let start = Instant::now();
+            let restored = (variant.decompress)(&compressed)?;
+            decompress[i].push(start.elapsed().as_secs_f64());
+            if restored != data {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} round trip mismatch", variant.check),
+                ));
+            }
+            sizes[i] = compressed.len();
+        }
+    }
+    Ok(sizes
+        .into_iter()
+        .zip(compress.iter_mut().zip(&mut decompress))
+        .map(|(size, (compress, decompress))| {
+            compress.sort_by(f64::total_cmp);
+            decompress.sort_by(f64::total_cmp);
+            Measured {
+                size,
+                compress: percentile(compress, 0.5),
+                decompress: percentile(decompress, 0.5),
+            }
+        })
+        .collect())
+}
+
+// What an integrity check costs: each format with its checksum off and on, and raw lz4 blocks
+// with an external digest, against the same format without one
+pub fn run(size_mb: usize, trials: usize) -> Result<(), Box<dyn Error>> {
+    let data = generate_test_data(&TestData::Mixed, size_mb * 1024 * 1024);
+    let mb = data.len() as f64 / (1024.0 * 1024.0);
+    println!(
+        "\nChecksum overhead ({}MB mixed data, median of {} trials)\n",
+        size_mb, trials
+    );
+    println!(
+        "{:<10} {:<18} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "Format", "Check", "Comp MB/s", "Decomp", "Comp +%", "Decomp +%", "Bytes +"
+    );
+    println!("{}", "-".repeat(84));
+    for group in VARIANTS.chunk_by(|a, b| a.group == b.group) {
+        let measured = match measure(group, &data, trials) {
+            Ok(measured) => measured,
+            Err(e) => {
+                println!("{:<10} FAILED: {}", group[0].group, e);
+                continue;
+            }
+        };
+        let base = &measured[0];
+        println!(
+            "{:<10} {:<18} {:>10.1} {:>10.1} {:>10} {:>10} {:>10}",
+            group[0].group,
+            group[0].check,
+            mb / base.compress,
+            mb / base.decompress,
+            "-",
+            "-",
+            "-"
+        );
+        for (variant, measured) in group.iter().zip(&measured).skip(1) {
+            println!(
+                "{:<10} {:<18} {:>10.1} {:>10.1} {:>+9.1}% {:>+9.1}% {:>10}",
+                variant.group,
+                variant.check,
+                mb / measured.compress,
+                mb / measured.decompress,
+                (measured.compress / base.compress - 1.0) * 100.0,
+                (measured.decompress / base.decompress - 1.0) * 100.0,
+                measured.size as i64 - base.size as i64
+            );
+        }
+    }
+
+    // The hashes alone, for scale against the codecs above
+    println!();
+    let hashes: [(&str, Hash); 3] = [
+        ("CRC32 (crc32fast)", |data| {
+            crc32fast::hash(data);
+        }),
+        ("XXH3-64", |data| {
+            xxhash_rust::xxh3::xxh3_64(data);
+        }),
+        ("BLAKE3", |data| {
+            blake3::hash(data);
+        }),
+    ];
+    for (name, hash) in hashes {
+        let mut times: Vec<f64> = (0..trials)
+            .map(|_| {
+                let start = Instant::now();
+                hash(std::hint::black_box(&data));
+                start.elapsed().as_secs_f64()
+            })
+            .collect();
+        times.sort_by(f64::total_cmp);
+        println!(
+            "{:<18} alone: {:>8.0} MB/s",
+            name,
+            mb / percentile(&times, 0.5)
+        );
+    }
+    println!(
+        "\n+%: time over the first row of the same format; LZ4 block digests cover the \
+         uncompressed input and are checked after decoding"
+    );
+    Ok(())
+}
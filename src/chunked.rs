@@ -0,0 +1,101 @@
+// Chunked-parallel compression around any backend: the input is split into fixed-size chunks
+// compressed independently on a rayon pool, trading some ratio for throughput
+use crate::Compressor;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::io;
+use std::sync::Arc;
+
+// Each chunk is framed as a little-endian u32 compressed length followed by its payload
+const FRAME_HEADER: usize = 4;
+
+pub struct Chunked {
+    inner: &'static dyn Compressor,
+    chunk_size: usize,
+    pool: Arc<ThreadPool>,
+}
+
+impl Chunked {
+    pub fn new(inner: &'static dyn Compressor, chunk_size: usize, pool: Arc<ThreadPool>) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        Chunked {
+            inner,
+            chunk_size,
+            pool,
+        }
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+}
+
+// A pool shared by every chunked configuration in a run, so they compete for the same threads.
+// rayon would read 0 threads as "pick for me", so it is rejected rather than passed through
+pub fn pool(threads: usize) -> io::Result<Arc<ThreadPool>> {
+    assert!(threads >= 1, "thread count must be at least 1");
+    ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map(Arc::new)
+        .map_err(io::Error::other)
+}
+
+// Splits framed output back into the compressed chunks
+fn frames(mut data: &[u8]) -> io::Result<Vec<&[u8]>> {
+    let truncated = || io::Error::new(io::ErrorKind::UnexpectedEof, "truncated chunk frame");
+    let mut frames = Vec::new();
+    while !data.is_empty() {
+        let header = data.get(..FRAME_HEADER).ok_or_else(truncated)?;
+        let len = u32::from_le_bytes(header.try_into().unwrap()) as usize;
+        let payload = data
+            .get(FRAME_HEADER..FRAME_HEADER + len)
+            .ok_or_else(truncated)?;
+        frames.push(payload);
+        data = &data[FRAME_HEADER + len..];
+    }
+    Ok(frames)
+}
+
+impl Compressor for Chunked {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn level(&self) -> String {
+        format!(
+            "{} /{}KB x{}",
+            self.inner.level(),
+            self.chunk_size / 1024,
+            self.pool.current_num_threads()
+        )
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let chunks = self.pool.install(|| {
+            data.par_chunks(self.chunk_size)
+                .map(|chunk| self.inner.compress(chunk))
+                .collect::<io::Result<Vec<Vec<u8>>>>()
+        })?;
+        let mut framed =
+            Vec::with_capacity(chunks.iter().map(|c| c.len() + FRAME_HEADER).sum::<usize>());
+        for chunk in chunks {
+            let len = u32::try_from(chunk.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "chunk too large"))?;
+            framed.extend_from_slice(&len.to_le_bytes());
+            framed.extend_from_slice(&chunk);
+        }
+        Ok(framed)
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let frames = frames(data)?;
+        let chunks = self.pool.install(|| {
+            frames
+                .par_iter()
+                .map(|frame| self.inner.decompress(frame))
+                .collect::<io::Result<Vec<Vec<u8>>>>()
+        })?;
+        Ok(chunks.concat())
+    }
+}
@@ -0,0 +1,102 @@
+use rust_compress_comparison::backends;
+use rust_compress_comparison::chunked::{self, Chunked};
+use rust_compress_comparison::harness::TestData;
+use rust_compress_comparison::{BenchmarkSuite, Compressor};
+use std::error::Error;
+
+const CHUNKING_DATA_SIZE: usize = 1024 * 1024 * 10; // 10MB
+const CHUNKING_TRIALS: usize = 3;
+
+pub const DEFAULT_CHUNK_KB: [usize; 3] = [64, 256, 1024];
+
+// Each configuration single-stream and at every chunk size on `threads` threads, reporting the
+// throughput gained and the ratio lost against the single stream
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    chunk_kb: &[usize],
+    threads: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::configurations().collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let pool = chunked::pool(threads)?;
+
+    // (single-stream label, chunk size) for every configuration the suite runs, keyed by label
+    let mut dimensions: Vec<(String, String, Option<usize>)> = Vec::new();
+    let mut configurations: Vec<&'static dyn Compressor> = Vec::new();
+    for codec in algorithms {
+        let base = format!("{} {}", codec.name(), codec.level());
+        dimensions.push((base.clone(), base.clone(), None));
+        configurations.push(codec);
+        for kb in chunk_kb {
+            let chunked: &'static Chunked =
+                Box::leak(Box::new(Chunked::new(codec, kb * 1024, pool.clone())));
+            dimensions.push((
+                format!("{} {}", chunked.name(), chunked.level()),
+                base.clone(),
+                Some(chunked.chunk_size()),
+            ));
+            configurations.push(chunked);
+        }
+    }
+
+    println!(
+        "\nChunked-parallel compression ({}MB mixed data, {} trials, {} threads)\n",
+        CHUNKING_DATA_SIZE / 1024 / 1024,
+        CHUNKING_TRIALS,
+        threads
+    );
+    let results = BenchmarkSuite::builder()
+        .data([TestData::Mixed])
+        .algorithms(configurations)
+        .data_size(CHUNKING_DATA_SIZE)
+        .trials(CHUNKING_TRIALS)
+        .verify(true)
+        .run()?;
+    for failure in &results.failures {
+        println!(
+            "FAILED {} {}: {}",
+            failure.algorithm, failure.level, failure.error
+        );
+    }
+
+    println!(
+        "{:<22} {:>8} {:>8} {:>11} {:>11} {:>13} {:>9}",
+        "Configuration", "Chunk", "Ratio", "Ratio loss", "Comp MB/s", "Decomp MB/s", "Speedup"
+    );
+    println!("{}", "-".repeat(88));
+    let single = |base: &str| {
+        results
+            .cells
+            .iter()
+            .find(|cell| cell.label() == base)
+            .map(|cell| (cell.ratio, cell.compress_mbps()))
+    };
+    for cell in &results.cells {
+        let Some((_, base, chunk_size)) = dimensions.iter().find(|(l, _, _)| *l == cell.label())
+        else {
+            continue;
+        };
+        let chunk = chunk_size.map_or("single".to_string(), |size| format!("{}KB", size / 1024));
+        let (ratio_loss, speedup) = match single(base) {
+            Some((ratio, mbps)) if ratio > 0.0 && mbps > 0.0 => (
+                format!("{:.1}%", (ratio - cell.ratio) / ratio * 100.0),
+                format!("{:.2}x", cell.compress_mbps() / mbps),
+            ),
+            _ => ("-".to_string(), "-".to_string()),
+        };
+        println!(
+            "{:<22} {:>8} {:>7.2}x {:>11} {:>11.1} {:>13.1} {:>9}",
+            base,
+            chunk,
+            cell.ratio,
+            ratio_loss,
+            cell.compress_mbps(),
+            cell.decompress_mbps(),
+            speedup
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use rust_compress_comparison::backends;
+use rust_compress_comparison::cache::CacheState;
+use rust_compress_comparison::harness::TestData;
+use rust_compress_comparison::{BenchmarkSuite, Compressor, RunResults};
+use std::error::Error;
+
+pub const DEFAULT_SIZE_KB: [usize; 3] = [64, 1024, 16 * 1024];
+
+fn run_suite(
+    algorithms: &[&'static dyn Compressor],
+    size: usize,
+    trials: usize,
+    cache: CacheState,
+) -> Result<RunResults, Box<dyn Error>> {
+    let results = BenchmarkSuite::builder()
+        .data([TestData::Mixed])
+        .algorithms(algorithms.iter().copied())
+        .data_size(size)
+        .trials(trials)
+        .verify(true)
+        .cache(cache)
+        .run()?;
+    for failure in &results.failures {
+        println!(
+            "FAILED {} {} ({}): {}",
+            failure.algorithm,
+            failure.level,
+            cache.name(),
+            failure.error
+        );
+    }
+    Ok(results)
+}
+
+fn change(warm: f64, cold: f64) -> String {
+    if warm > 0.0 {
+        format!("{:+.1}%", (cold - warm) / warm * 100.0)
+    } else {
+        "-".to_string()
+    }
+}
+
+// Each configuration with its inputs cached and evicted before every call, side by side per size
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    size_kb: &[usize],
+    trials: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::REGISTRY
+            .iter()
+            .filter_map(|backend| backend.level("Default"))
+            .collect()
+    } else {
+        algorithms.to_vec()
+    };
+    println!(
+        "\nWarm vs cold CPU caches (mixed data, {} trials; cold evicts the input before each call)",
+        trials
+    );
+
+    for &kb in size_kb {
+        let warm = run_suite(&algorithms, kb * 1024, trials, CacheState::Warm)?;
+        let cold = run_suite(&algorithms, kb * 1024, trials, CacheState::Cold)?;
+        println!("\n=== {}KB input ===\n", kb);
+        println!(
+            "{:<18} {:>11} {:>11} {:>8} {:>13} {:>13} {:>8}",
+            "Configuration",
+            "Warm comp",
+            "Cold comp",
+            "Change",
+            "Warm decomp",
+            "Cold decomp",
+            "Change"
+        );
+        println!("{}", "-".repeat(88));
+        for cell in &warm.cells {
+            let Some(cold) = cold.matching(cell) else {
+                continue;
+            };
+            println!(
+                "{:<18} {:>11.1} {:>11.1} {:>8} {:>13.1} {:>13.1} {:>8}",
+                cell.label(),
+                cell.compress_mbps(),
+                cold.compress_mbps(),
+                change(cell.compress_mbps(), cold.compress_mbps()),
+                cell.decompress_mbps(),
+                cold.decompress_mbps(),
+                change(cell.decompress_mbps(), cold.decompress_mbps())
+            );
+        }
+    }
+    println!("\nThroughput in MB/s of input");
+    Ok(())
+}
@@ -0,0 +1,284 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rand::{thread_rng, Rng};
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::time::Instant;
+
+// parquet-rs defaults: gzip at zlib's 6, zstd at 1
+const GZIP_LEVEL: u32 = 6;
+const ZSTD_LEVEL: i32 = 1;
+const CITIES: [&str; 16] = [
+    "Amsterdam",
+    "Berlin",
+    "Chicago",
+    "Dublin",
+    "Edinburgh",
+    "Frankfurt",
+    "Geneva",
+    "Helsinki",
+    "Istanbul",
+    "Jakarta",
+    "Kyoto",
+    "Lisbon",
+    "Madrid",
+    "Nairobi",
+    "Oslo",
+    "Paris",
+];
+
+// A Parquet page compression codec; the page header records the uncompressed size, which the
+// raw formats rely on
+struct Codec {
+    name: &'static str,
+    compress: fn(&[u8]) -> io::Result<Vec<u8>>,
+    decompress: fn(&[u8], usize) -> io::Result<Vec<u8>>,
+}
+
+fn gzip(page: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(GZIP_LEVEL));
+    encoder.write_all(page)?;
+    encoder.finish()
+}
+
+fn gunzip(compressed: &[u8], size: usize) -> io::Result<Vec<u8>> {
+    let mut restored = Vec::with_capacity(size);
+    GzDecoder::new(compressed).read_to_end(&mut restored)?;
+    Ok(restored)
+}
+
+fn snappy_compress(page: &[u8]) -> io::Result<Vec<u8>> {
+    snap::raw::Encoder::new()
+        .compress_vec(page)
+        .map_err(io::Error::other)
+}
+
+fn snappy_decompress(compressed: &[u8], _: usize) -> io::Result<Vec<u8>> {
+    snap::raw::Decoder::new()
+        .decompress_vec(compressed)
+        .map_err(io::Error::other)
+}
+
+// LZ4_RAW: a bare LZ4 block, as the deprecated Hadoop-framed LZ4 is not written any more
+fn lz4_raw_compress(page: &[u8]) -> io::Result<Vec<u8>> {
+    Ok(lz4_flex::block::compress(page))
+}
+
+fn lz4_raw_decompress(compressed: &[u8], size: usize) -> io::Result<Vec<u8>> {
+    lz4_flex::block::decompress(compressed, size).map_err(io::Error::other)
+}
+
+fn zstd_compress(page: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::bulk::compress(page, ZSTD_LEVEL)
+}
+
+fn zstd_decompress(compressed: &[u8], size: usize) -> io::Result<Vec<u8>> {
+    zstd::bulk::decompress(compressed, size)
+}
+
+const CODECS: [Codec; 4] = [
+    Codec {
+        name: "SNAPPY",
+        compress: snappy_compress,
+        decompress: snappy_decompress,
+    },
+    Codec {
+        name: "GZIP",
+        compress: gzip,
+        decompress: gunzip,
+    },
+    Codec {
+        name: "LZ4_RAW",
+        compress: lz4_raw_compress,
+        decompress: lz4_raw_decompress,
+    },
+    Codec {
+        name: "ZSTD",
+        compress: zstd_compress,
+        decompress: zstd_decompress,
+    },
+];
+
+// A column's encoded values, filling one page of `size` bytes
+struct Column {
+    name: &'static str,
+    page: fn(usize) -> Vec<u8>,
+}
+
+const COLUMNS: [Column; 7] = [
+    Column {
+        name: "Dictionary indices (RLE_DICTIONARY)",
+        page: dictionary_indices,
+    },
+    Column {
+        name: "Dictionary page (PLAIN strings)",
+        page: dictionary_page,
+    },
+    Column {
+        name: "Timestamps (PLAIN int64)",
+        page: timestamps,
+    },
+    Column {
+        name: "Quantities (PLAIN int32)",
+        page: quantities,
+    },
+    Column {
+        name: "Prices (PLAIN double)",
+        page: prices,
+    },
+    Column {
+        name: "Sensor readings (PLAIN double)",
+        page: readings,
+    },
+    Column {
+        name: "Validity bitmap (5% null)",
+        page: validity,
+    },
+];
+
+// Skewed indices into a 16-entry dictionary, bit-packed 4 to a value as the hybrid encoding's
+// bit-packed runs store them
+fn dictionary_indices(size: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let mut index = || {
+        let roll: f64 = rng.gen();
+        (roll * roll * CITIES.len() as f64) as u8
+    };
+    (0..size).map(|_| index() | index() << 4).collect()
+}
+
+// PLAIN byte arrays, each distinct value once, as in the dictionary of a high-cardinality column
+fn dictionary_page(size: usize) -> Vec<u8> {
+    let mut page = Vec::with_capacity(size + 64);
+    let mut i = 0;
+    while page.len() < size {
+        let value = format!("{}-{:05}", CITIES[i % CITIES.len()], i / CITIES.len());
+        page.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        page.extend_from_slice(value.as_bytes());
+        i += 1;
+    }
+    page.truncate(size);
+    page
+}
+
+// Event times in milliseconds, increasing with small jitter
+fn timestamps(size: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let mut ts: i64 = 1_700_000_000_000;
+    let mut page = Vec::with_capacity(size);
+    while page.len() < size {
+        ts += rng.gen_range(0..250);
+        page.extend_from_slice(&ts.to_le_bytes());
+    }
+    page.truncate(size);
+    page
+}
+
+fn quantities(size: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let mut page = Vec::with_capacity(size);
+    while page.len() < size {
+        page.extend_from_slice(&rng.gen_range(1i32..100).to_le_bytes());
+    }
+    page.truncate(size);
+    page
+}
+
+// Two-decimal amounts, which still use most of a double's mantissa
+fn prices(size: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let mut page = Vec::with_capacity(size);
+    while page.len() < size {
+        let cents = rng.gen_range(100..100_000);
+        page.extend_from_slice(&(cents as f64 / 100.0).to_le_bytes());
+    }
+    page.truncate(size);
+    page
+}
+
+// A slowly drifting noisy signal
+fn readings(size: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let mut value = 20.0f64;
+    let mut page = Vec::with_capacity(size);
+    while page.len() < size {
+        value += rng.gen_range(-0.05..0.05);
+        page.extend_from_slice(&value.to_le_bytes());
+    }
+    page.truncate(size);
+    page
+}
+
+// Arrow-style validity bits, one per row
+fn validity(size: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    (0..size)
+        .map(|_| (0..8).fold(0u8, |bits, bit| bits | (rng.gen_bool(0.95) as u8) << bit))
+        .collect()
+}
+
+struct Measured {
+    ratio: f64,
+    compress_mbps: f64,
+    decompress_mbps: f64,
+}
+
+fn measure(codec: &Codec, pages: &[Vec<u8>]) -> Result<Measured, Box<dyn Error>> {
+    let mut original = 0;
+    let mut compressed_total = 0;
+    let mut compress_secs = 0.0;
+    let mut decompress_secs = 0.0;
+    for page in pages {
+        let start = Instant::now();
+        let compressed = (codec.compress)(page)?;
+        compress_secs += start.elapsed().as_secs_f64();
+        let start = Instant::now();
+        let restored = (codec.decompress)(&compressed, page.len())?;
+        decompress_secs += start.elapsed().as_secs_f64();
+        if restored != *page {
+            return Err(format!("{} round trip mismatch", codec.name).into());
+        }
+        original += page.len();
+        compressed_total += compressed.len();
+    }
+    let mb = original as f64 / (1024.0 * 1024.0);
+    Ok(Measured {
+        ratio: original as f64 / compressed_total as f64,
+        compress_mbps: mb / compress_secs,
+        decompress_mbps: mb / decompress_secs,
+    })
+}
+
+// Per-column-type page compression with Parquet's codecs
+pub fn run(page_kb: usize, page_count: usize) -> Result<(), Box<dyn Error>> {
+    let page_size = page_kb * 1024;
+    println!(
+        "\nColumnar page compression ({} pages of {}KB per column type)",
+        page_count, page_kb
+    );
+    for column in &COLUMNS {
+        let pages: Vec<Vec<u8>> = (0..page_count).map(|_| (column.page)(page_size)).collect();
+        println!("\n=== {} ===\n", column.name);
+        println!(
+            "{:<8} {:>8} {:>11} {:>13}",
+            "Codec", "Ratio", "Comp MB/s", "Decomp MB/s"
+        );
+        println!("{}", "-".repeat(43));
+        let mut best: Option<(&str, f64)> = None;
+        for codec in &CODECS {
+            let measured = measure(codec, &pages)?;
+            println!(
+                "{:<8} {:>7.2}x {:>11.1} {:>13.1}",
+                codec.name, measured.ratio, measured.compress_mbps, measured.decompress_mbps
+            );
+            if best.is_none_or(|(_, ratio)| measured.ratio > ratio) {
+                best = Some((codec.name, measured.ratio));
+            }
+        }
+        if let Some((name, _)) = best {
+            println!("\nBest ratio: {}", name);
+        }
+    }
+    Ok(())
+}
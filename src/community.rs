@@ -0,0 +1,77 @@
+use crate::metadata::RunMetadata;
+use crate::results::RunResults;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+// Copy of a run that is safe to publish: no hostname, labels, notes or exact CPU SKU
+pub fn anonymize(results: &RunResults) -> RunResults {
+    RunResults {
+        metadata: RunMetadata {
+            machine: results.metadata.machine.anonymized(),
+            cpu: results.metadata.cpu.clone(),
+            verified: results.metadata.verified,
+            cache: results.metadata.cache,
+            allocator: results.metadata.allocator.clone(),
+            ..RunMetadata::default()
+        },
+        cells: results.cells.clone(),
+        failures: results.failures.clone(),
+    }
+}
+
+#[derive(Default)]
+struct Aggregate {
+    contributions: usize,
+    ratio_sum: f64,
+    compress_mbps_sum: f64,
+    decompress_mbps_sum: f64,
+    saved_mb_per_cpu_sec_sum: f64,
+}
+
+// Averages contributed runs into one markdown table per CPU class
+pub fn aggregate_markdown(runs: &[RunResults]) -> String {
+    let mut groups: BTreeMap<(String, String, String), Aggregate> = BTreeMap::new();
+    for run in runs {
+        let machine = run.metadata.machine.anonymized();
+        for cell in &run.cells {
+            let key = (
+                machine.cpu_model.clone(),
+                cell.data_type.clone(),
+                cell.label(),
+            );
+            let aggregate = groups.entry(key).or_default();
+            aggregate.contributions += 1;
+            aggregate.ratio_sum += cell.ratio;
+            aggregate.compress_mbps_sum += cell.compress_mbps();
+            aggregate.decompress_mbps_sum += cell.decompress_mbps();
+            aggregate.saved_mb_per_cpu_sec_sum += cell.saved_mb_per_cpu_sec(1.0);
+        }
+    }
+
+    let mut markdown = String::from("# Community Compression Results\n");
+    let mut current_class = None;
+    for ((cpu_class, data_type, label), aggregate) in &groups {
+        if current_class != Some(cpu_class) {
+            current_class = Some(cpu_class);
+            writeln!(markdown, "\n## {}\n", cpu_class).unwrap();
+            markdown.push_str(
+                "| Data | Algorithm | Ratio | Compress MB/s | Decompress MB/s | Saved MB/CPU-s | Runs |\n",
+            );
+            markdown.push_str("|---|---|---:|---:|---:|---:|---:|\n");
+        }
+        let n = aggregate.contributions as f64;
+        writeln!(
+            markdown,
+            "| {} | {} | {:.2}x | {:.1} | {:.1} | {:.1} | {} |",
+            data_type,
+            label,
+            aggregate.ratio_sum / n,
+            aggregate.compress_mbps_sum / n,
+            aggregate.decompress_mbps_sum / n,
+            aggregate.saved_mb_per_cpu_sec_sum / n,
+            aggregate.contributions
+        )
+        .unwrap();
+    }
+    markdown
+}
@@ -0,0 +1,268 @@
+use crate::results::mean_and_stddev;
+use crate::{generate_test_data, timed, TestData};
+use clap::ValueEnum;
+use rust_compress_comparison::backends;
+use rust_compress_comparison::Compressor;
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+
+// Parses a registry configuration such as `gzip:best` or `lz4-flex`
+pub fn parse_config(spec: &str) -> Result<&'static dyn Compressor, String> {
+    backends::find(spec)
+}
+
+// Parses a significance level, which must lie strictly between 0 and 1
+pub fn parse_alpha(s: &str) -> Result<f64, String> {
+    let alpha: f64 = s.parse().map_err(|e| format!("{}", e))?;
+    if alpha > 0.0 && alpha < 1.0 {
+        Ok(alpha)
+    } else {
+        Err(format!("{} is not between 0 and 1", s))
+    }
+}
+
+fn label(codec: &dyn Compressor) -> String {
+    format!("{} {}", codec.name(), codec.level())
+}
+
+// Which throughput the test is run on
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Metric {
+    Compress,
+    Decompress,
+}
+
+pub struct CompareOptions {
+    pub a: &'static dyn Compressor,
+    pub b: &'static dyn Compressor,
+    pub data_type: TestData,
+    pub size_mb: usize,
+    pub trials: usize,
+    pub metric: Metric,
+    pub alpha: f64,
+}
+
+// Two-sided Welch's t-test; returns (t, degrees of freedom, p)
+fn welch(a: &[f64], b: &[f64]) -> (f64, f64, f64) {
+    let (mean_a, sd_a) = mean_and_stddev(a);
+    let (mean_b, sd_b) = mean_and_stddev(b);
+    let va = sd_a.powi(2) / a.len() as f64;
+    let vb = sd_b.powi(2) / b.len() as f64;
+    // Identical trials on both sides: either no difference at all or an exact one
+    if va + vb == 0.0 {
+        return if mean_a == mean_b {
+            (0.0, f64::INFINITY, 1.0)
+        } else {
+            (
+                (mean_a - mean_b).signum() * f64::INFINITY,
+                f64::INFINITY,
+                0.0,
+            )
+        };
+    }
+    let t = (mean_a - mean_b) / (va + vb).sqrt();
+    let df =
+        (va + vb).powi(2) / (va.powi(2) / (a.len() - 1) as f64 + vb.powi(2) / (b.len() - 1) as f64);
+    let dist = StudentsT::new(0.0, 1.0, df).expect("degrees of freedom are positive");
+    (t, df, 2.0 * (1.0 - dist.cdf(t.abs())))
+}
+
+// Two-sided Mann-Whitney U test with the tie-corrected normal approximation; returns (U of a, p)
+fn mann_whitney(a: &[f64], b: &[f64]) -> (f64, f64) {
+    let mut pooled: Vec<(f64, bool)> = a
+        .iter()
+        .map(|v| (*v, true))
+        .chain(b.iter().map(|v| (*v, false)))
+        .collect();
+    pooled.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Average ranks across ties, accumulating the tie correction term as we go
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut start = 0;
+    while start < pooled.len() {
+        let end = start
+            + pooled[start..]
+                .iter()
+                .take_while(|(v, _)| *v == pooled[start].0)
+                .count();
+        let rank = (start + end + 1) as f64 / 2.0;
+        rank_sum_a += rank * pooled[start..end].iter().filter(|(_, in_a)| *in_a).count() as f64;
+        let tied = (end - start) as f64;
+        tie_term += tied.powi(3) - tied;
+        start = end;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let sd = (n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)))).sqrt();
+    if sd == 0.0 {
+        return (u, 1.0);
+    }
+    let z = (u - n1 * n2 / 2.0) / sd;
+    let normal = Normal::new(0.0, 1.0).expect("standard normal");
+    (u, 2.0 * (1.0 - normal.cdf(z.abs())))
+}
+
+// Runs both configurations in alternating trials on the same buffers and tests the difference
+pub fn run(options: &CompareOptions) {
+    let size = options.size_mb * 1024 * 1024;
+    let mb = size as f64 / (1024.0 * 1024.0);
+    let metric = match options.metric {
+        Metric::Compress => "compression",
+        Metric::Decompress => "decompression",
+    };
+
+    println!(
+        "\nComparing {} vs {} {} throughput ({} trials of {}MB, alpha = {})",
+        label(options.a),
+        label(options.b),
+        metric,
+        options.trials,
+        options.size_mb,
+        options.alpha
+    );
+
+    let mut samples_a = Vec::with_capacity(options.trials);
+    let mut samples_b = Vec::with_capacity(options.trials);
+    for trial in 0..options.trials {
+        let data = generate_test_data(&options.data_type, size);
+        let measure = |codec: &dyn Compressor| {
+            let (compressed, compress_secs) = timed(|| codec.compress(&data));
+            let (_, decompress_secs) = timed(|| codec.decompress(&compressed));
+            match options.metric {
+                Metric::Compress => mb / compress_secs,
+                Metric::Decompress => mb / decompress_secs,
+            }
+        };
+        // Alternate which side goes first so neither always runs on a warmer cache
+        if trial % 2 == 0 {
+            samples_a.push(measure(options.a));
+            samples_b.push(measure(options.b));
+        } else {
+            samples_b.push(measure(options.b));
+            samples_a.push(measure(options.a));
+        }
+    }
+
+    println!(
+        "\n{:<18} {:>12} {:>10} {:>12} {:>12}",
+        "Configuration", "Mean MB/s", "Stddev", "Min MB/s", "Max MB/s"
+    );
+    println!("{}", "-".repeat(68));
+    for (codec, samples) in [(options.a, &samples_a), (options.b, &samples_b)] {
+        let (mean, stddev) = mean_and_stddev(samples);
+        println!(
+            "{:<18} {:>12.1} {:>10.1} {:>12.1} {:>12.1}",
+            label(codec),
+            mean,
+            stddev,
+            samples.iter().copied().fold(f64::INFINITY, f64::min),
+            samples.iter().copied().fold(0.0, f64::max)
+        );
+    }
+
+    let (mean_a, _) = mean_and_stddev(&samples_a);
+    let (mean_b, _) = mean_and_stddev(&samples_b);
+    let (t, df, welch_p) = welch(&samples_a, &samples_b);
+    let (u, mann_whitney_p) = mann_whitney(&samples_a, &samples_b);
+    let verdict = |p: f64| {
+        if p < options.alpha {
+            "significant"
+        } else {
+            "not significant"
+        }
+    };
+
+    println!(
+        "\nDifference: {} is {:+.1}% vs {}",
+        label(options.a),
+        (mean_a - mean_b) / mean_b * 100.0,
+        label(options.b)
+    );
+    println!(
+        "Welch's t-test:  t = {:.3}, df = {:.1}, p = {:.4} -> {}",
+        t,
+        df,
+        welch_p,
+        verdict(welch_p)
+    );
+    println!(
+        "Mann-Whitney U:  U = {:.1}, p = {:.4} -> {}",
+        u,
+        mann_whitney_p,
+        verdict(mann_whitney_p)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    // The worked examples on Wikipedia's "Welch's t-test" page
+    #[test]
+    fn welch_matches_published_examples() {
+        let a1 = [
+            27.5, 21.0, 19.0, 23.6, 17.0, 17.9, 16.9, 20.1, 21.9, 22.6, 23.1, 19.6, 19.0, 21.7,
+            21.4,
+        ];
+        let a2 = [
+            27.1, 22.0, 20.8, 23.4, 23.4, 23.5, 25.8, 22.0, 24.8, 20.2, 21.9, 22.1, 22.9, 20.5,
+            24.4,
+        ];
+        let (t, df, p) = welch(&a1, &a2);
+        close(t, -2.46, 0.005);
+        close(df, 25.0, 0.05);
+        close(p, 0.021, 0.0005);
+
+        let b1 = [17.2, 20.9, 22.6, 18.1, 21.7, 21.4, 23.5, 24.2, 14.7, 21.8];
+        let b2 = [
+            21.5, 22.8, 21.0, 23.0, 21.6, 23.6, 22.5, 20.7, 23.4, 21.8, 20.7, 21.7, 21.5, 22.5,
+            23.6, 21.5, 22.5, 23.5, 21.5, 21.8,
+        ];
+        let (t, df, p) = welch(&b1, &b2);
+        close(t, -1.57, 0.005);
+        close(df, 9.9, 0.05);
+        close(p, 0.149, 0.0005);
+    }
+
+    #[test]
+    fn welch_on_constant_samples() {
+        assert_eq!(welch(&[2.0, 2.0], &[2.0, 2.0]).2, 1.0);
+        assert_eq!(welch(&[2.0, 2.0], &[3.0, 3.0]).2, 0.0);
+    }
+
+    // R: wilcox.test(1:5, 6:10, exact = FALSE, correct = FALSE) gives W = 0, p = 0.009023
+    #[test]
+    fn mann_whitney_matches_normal_approximation() {
+        let (u, p) = mann_whitney(&[1.0, 2.0, 3.0, 4.0, 5.0], &[6.0, 7.0, 8.0, 9.0, 10.0]);
+        assert_eq!(u, 0.0);
+        close(p, 0.009023, 1e-6);
+    }
+
+    // Tied groups of 2, 3, 2 and 2 values shrink the variance of U from 22.92 to 21.94, giving
+    // p = 0.01409 where the uncorrected statistic would give 0.01629
+    #[test]
+    fn mann_whitney_corrects_for_ties() {
+        let (u, p) = mann_whitney(&[1.0, 2.0, 2.0, 3.0, 3.0], &[3.0, 4.0, 4.0, 5.0, 5.0]);
+        assert_eq!(u, 1.0);
+        close(p, 0.014092, 1e-6);
+    }
+
+    #[test]
+    fn mann_whitney_on_all_ties_finds_no_difference() {
+        let (u, p) = mann_whitney(&[1.0, 1.0, 1.0], &[1.0, 1.0, 1.0]);
+        assert_eq!(u, 4.5);
+        assert_eq!(p, 1.0);
+    }
+}
@@ -0,0 +1,65 @@
+// Serializable description of a suite, for callers that can't use the builder directly
+use crate::backends;
+use crate::cache::CacheState;
+use crate::error::{CompressionBenchError, Result};
+use crate::harness::TestData;
+use crate::suite::{BenchmarkSuite, BenchmarkSuiteBuilder, Schedule};
+use serde::Deserialize;
+
+// Every field is optional and falls back to the `bench` subcommand's default, e.g.
+// `{"data": ["mixed"], "algorithms": ["gzip:fast", "zstd"], "trials": 5}`
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SuiteConfig {
+    pub data: Option<Vec<TestData>>,
+    // Registry specs such as `gzip:best` or `lz4-flex`
+    pub algorithms: Option<Vec<String>>,
+    pub trials: Option<usize>,
+    pub data_size: Option<usize>,
+    pub warmup: Option<usize>,
+    pub exclude_outliers: bool,
+    pub schedule: Option<Schedule>,
+    pub noisy_cv: Option<f64>,
+    pub verify: bool,
+    pub cache: CacheState,
+}
+
+impl SuiteConfig {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| CompressionBenchError::Config(e.to_string()))
+    }
+
+    pub fn builder(&self) -> Result<BenchmarkSuiteBuilder> {
+        let mut builder = BenchmarkSuite::builder()
+            .exclude_outliers(self.exclude_outliers)
+            .verify(self.verify)
+            .cache(self.cache);
+        if let Some(data) = &self.data {
+            builder = builder.data(data.iter().copied());
+        }
+        if let Some(specs) = &self.algorithms {
+            let algorithms = specs
+                .iter()
+                .map(|spec| backends::find(spec))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(CompressionBenchError::Config)?;
+            builder = builder.algorithms(algorithms);
+        }
+        if let Some(trials) = self.trials {
+            builder = builder.trials(trials);
+        }
+        if let Some(data_size) = self.data_size {
+            builder = builder.data_size(data_size);
+        }
+        if let Some(warmup) = self.warmup {
+            builder = builder.warmup(warmup);
+        }
+        if let Some(schedule) = self.schedule {
+            builder = builder.schedule(schedule);
+        }
+        if let Some(noisy_cv) = self.noisy_cv {
+            builder = builder.noisy_cv(noisy_cv);
+        }
+        Ok(builder)
+    }
+}
@@ -0,0 +1,340 @@
+use crate::records::generate_records;
+use crate::results::percentile;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rand::{thread_rng, Rng};
+use std::error::Error;
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::time::Instant;
+
+// Distinct bodies cycled through per content type, so no single buffer stays hot in cache
+const DISTINCT_BODIES: usize = 16;
+const BROTLI_BUFFER: usize = 4096;
+const BROTLI_WINDOW: u32 = 22;
+
+const WORDS: [&str; 12] = [
+    "account", "billing", "cart", "delivery", "order", "product", "review", "search", "settings",
+    "shipping", "support", "wishlist",
+];
+
+// A response body kind, its typical uncompressed size and its share of requests
+struct ContentType {
+    name: &'static str,
+    size: usize,
+    weight: usize,
+    generate: fn(usize) -> Vec<u8>,
+}
+
+const CONTENT_TYPES: [ContentType; 3] = [
+    ContentType {
+        name: "HTML",
+        size: 48 * 1024,
+        weight: 3,
+        generate: html,
+    },
+    ContentType {
+        name: "JSON",
+        size: 16 * 1024,
+        weight: 5,
+        generate: json,
+    },
+    ContentType {
+        name: "CSS",
+        size: 32 * 1024,
+        weight: 2,
+        generate: css,
+    },
+];
+
+fn word(rng: &mut impl Rng) -> &'static str {
+    WORDS[rng.gen_range(0..WORDS.len())]
+}
+
+// A product listing page: boilerplate head and nav, then cards until `size`
+fn html(size: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let mut page = String::from(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>Shop</title>\n<link rel=\"stylesheet\" href=\"/static/site.css\">\n\
+         </head>\n<body>\n<nav class=\"site-nav\"><ul>\n",
+    );
+    for name in WORDS {
+        let _ = writeln!(page, "<li><a href=\"/{0}\">{0}</a></li>", name);
+    }
+    page.push_str("</ul></nav>\n<main class=\"listing\">\n");
+    let mut id = 1000;
+    while page.len() < size {
+        let _ = writeln!(
+            page,
+            "<article class=\"card\" data-id=\"{id}\">\n<a href=\"/product/{id}\">\
+             <img src=\"/img/{id}.jpg\" alt=\"{a} {b}\" loading=\"lazy\"></a>\n\
+             <h2 class=\"card-title\">{a} {b} {id}</h2>\n\
+             <p class=\"card-price\">${price:.2}</p>\n\
+             <button class=\"btn btn-primary\" data-action=\"add-to-cart\">Add to cart</button>\n\
+             </article>",
+            id = id,
+            a = word(&mut rng),
+            b = word(&mut rng),
+            price = rng.gen_range(1.0..500.0)
+        );
+        id += rng.gen_range(1..20);
+    }
+    page.truncate(size);
+    page.into_bytes()
+}
+
+// An API response: a JSON array of records
+fn json(size: usize) -> Vec<u8> {
+    let mut body = b"[".to_vec();
+    while body.len() < size {
+        for record in generate_records(32) {
+            if body.len() > 1 {
+                body.push(b',');
+            }
+            body.extend_from_slice(&record);
+        }
+    }
+    body.truncate(size);
+    body
+}
+
+// A stylesheet of component rules
+fn css(size: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let mut sheet = String::new();
+    let mut rule = 0;
+    while sheet.len() < size {
+        let _ = writeln!(
+            sheet,
+            ".{}-{} > .item:hover, .{}-{}.is-active {{\n  color: #{:06x};\n  \
+             margin: {}px {}px;\n  padding: {}rem;\n  border-radius: {}px;\n  \
+             transition: opacity 0.{}s ease-in-out;\n}}",
+            word(&mut rng),
+            rule,
+            word(&mut rng),
+            rule,
+            rng.gen_range(0..0x1000000),
+            rng.gen_range(0..32),
+            rng.gen_range(0..32),
+            rng.gen_range(1..4),
+            rng.gen_range(0..12),
+            rng.gen_range(1..9)
+        );
+        rule += 1;
+    }
+    sheet.truncate(size);
+    sheet.into_bytes()
+}
+
+// A streaming encoder a server writes each chunk of the response into and flushes
+trait ResponseEncoder: Write {
+    fn finish(self: Box<Self>) -> io::Result<Vec<u8>>;
+}
+
+impl ResponseEncoder for GzEncoder<Vec<u8>> {
+    fn finish(self: Box<Self>) -> io::Result<Vec<u8>> {
+        GzEncoder::finish(*self)
+    }
+}
+
+impl ResponseEncoder for brotli::CompressorWriter<Vec<u8>> {
+    fn finish(self: Box<Self>) -> io::Result<Vec<u8>> {
+        Ok(self.into_inner())
+    }
+}
+
+impl ResponseEncoder for zstd::stream::write::Encoder<'static, Vec<u8>> {
+    fn finish(self: Box<Self>) -> io::Result<Vec<u8>> {
+        zstd::stream::write::Encoder::finish(*self)
+    }
+}
+
+// Content-Encoding at a level servers commonly compress dynamic responses with
+struct Encoding {
+    name: &'static str,
+    level: u32,
+    encoder: fn(u32) -> io::Result<Box<dyn ResponseEncoder>>,
+    decoder: fn(&[u8]) -> io::Result<Box<dyn Read + '_>>,
+}
+
+fn gzip(level: u32) -> Encoding {
+    Encoding {
+        name: "gzip",
+        level,
+        encoder: |level| {
+            Ok(Box::new(GzEncoder::new(
+                Vec::new(),
+                Compression::new(level),
+            )))
+        },
+        decoder: |data| Ok(Box::new(GzDecoder::new(data))),
+    }
+}
+
+fn br(level: u32) -> Encoding {
+    Encoding {
+        name: "br",
+        level,
+        encoder: |level| {
+            Ok(Box::new(brotli::CompressorWriter::new(
+                Vec::new(),
+                BROTLI_BUFFER,
+                level,
+                BROTLI_WINDOW,
+            )))
+        },
+        decoder: |data| Ok(Box::new(brotli::Decompressor::new(data, BROTLI_BUFFER))),
+    }
+}
+
+fn zstd(level: u32) -> Encoding {
+    Encoding {
+        name: "zstd",
+        level,
+        encoder: |level| {
+            Ok(Box::new(zstd::stream::write::Encoder::new(
+                Vec::new(),
+                level as i32,
+            )?))
+        },
+        decoder: |data| Ok(Box::new(zstd::stream::read::Decoder::new(data)?)),
+    }
+}
+
+// nginx's gzip_comp_level default and the common tuned value, brotli's dynamic-content range,
+// and zstd's default and a step up
+fn encodings() -> [Encoding; 6] {
+    [gzip(1), gzip(6), br(4), br(6), zstd(3), zstd(6)]
+}
+
+// One response: written `chunk` bytes at a time with a flush after each, as a server streaming a
+// chunked body does, then finished
+fn respond(encoding: &Encoding, body: &[u8], chunk: usize) -> io::Result<(Vec<u8>, f64)> {
+    let start = Instant::now();
+    let mut encoder = (encoding.encoder)(encoding.level)?;
+    for piece in body.chunks(chunk) {
+        encoder.write_all(piece)?;
+        encoder.flush()?;
+    }
+    let compressed = encoder.finish()?;
+    Ok((compressed, start.elapsed().as_secs_f64()))
+}
+
+struct Measured {
+    bytes_out: f64,
+    // Sorted per-request CPU time
+    secs: Vec<f64>,
+}
+
+impl Measured {
+    fn mean_secs(&self) -> f64 {
+        self.secs.iter().sum::<f64>() / self.secs.len() as f64
+    }
+}
+
+fn measure(
+    encoding: &Encoding,
+    bodies: &[Vec<u8>],
+    requests: usize,
+    chunk: usize,
+) -> Result<Measured, Box<dyn Error>> {
+    let mut bytes_out = 0;
+    let mut secs = Vec::with_capacity(requests);
+    for request in 0..requests {
+        let body = &bodies[request % bodies.len()];
+        let (compressed, elapsed) = respond(encoding, body, chunk)?;
+        if request < bodies.len() {
+            let mut restored = Vec::new();
+            (encoding.decoder)(&compressed)?.read_to_end(&mut restored)?;
+            if restored != *body {
+                return Err(
+                    format!("{} {} round trip mismatch", encoding.name, encoding.level).into(),
+                );
+            }
+        }
+        bytes_out += compressed.len();
+        secs.push(elapsed);
+    }
+    secs.sort_by(f64::total_cmp);
+    Ok(Measured {
+        bytes_out: bytes_out as f64 / requests as f64,
+        secs,
+    })
+}
+
+// Bytes saved per microsecond of CPU added, the trade a server makes per request
+fn saved_per_us(saved: f64, secs: f64) -> f64 {
+    if secs > 0.0 {
+        saved / (secs * 1e6)
+    } else {
+        0.0
+    }
+}
+
+// On-the-fly Content-Encoding of typical HTML, JSON and CSS responses: bytes saved against CPU
+pub fn run(requests: usize, chunk_kb: usize) -> Result<(), Box<dyn Error>> {
+    let chunk = chunk_kb * 1024;
+    let encodings = encodings();
+    println!(
+        "\nHTTP Content-Encoding ({} requests per content type, {}KB chunks flushed as written)",
+        requests, chunk_kb
+    );
+
+    // Per encoding: weighted sums of bytes saved and seconds over the request mix
+    let mut mix = vec![(0.0, 0.0); encodings.len()];
+    let total_weight: usize = CONTENT_TYPES.iter().map(|content| content.weight).sum();
+    for content in &CONTENT_TYPES {
+        let bodies: Vec<Vec<u8>> = (0..DISTINCT_BODIES.min(requests))
+            .map(|_| (content.generate)(content.size))
+            .collect();
+        println!(
+            "\n=== {} ({}KB, {}% of requests) ===\n",
+            content.name,
+            content.size / 1024,
+            content.weight * 100 / total_weight
+        );
+        println!(
+            "{:<10} {:>10} {:>7} {:>11} {:>10} {:>10} {:>12}",
+            "Encoding", "Bytes out", "Ratio", "Saved/req", "CPU p50", "CPU p99", "Saved B/us"
+        );
+        println!("{}", "-".repeat(76));
+        for (encoding, totals) in encodings.iter().zip(&mut mix) {
+            let measured = measure(encoding, &bodies, requests, chunk)?;
+            let saved = content.size as f64 - measured.bytes_out;
+            let weight = content.weight as f64 / total_weight as f64;
+            totals.0 += saved * weight;
+            totals.1 += measured.mean_secs() * weight;
+            println!(
+                "{:<10} {:>10.0} {:>6.2}x {:>11.0} {:>8.1}us {:>8.1}us {:>12.1}",
+                format!("{}:{}", encoding.name, encoding.level),
+                measured.bytes_out,
+                content.size as f64 / measured.bytes_out,
+                saved,
+                percentile(&measured.secs, 0.5) * 1e6,
+                percentile(&measured.secs, 0.99) * 1e6,
+                saved_per_us(saved, measured.mean_secs())
+            );
+        }
+    }
+
+    println!("\n=== Request mix ===\n");
+    println!(
+        "{:<10} {:>11} {:>13} {:>12}",
+        "Encoding", "Saved/req", "CPU us/req", "Saved B/us"
+    );
+    println!("{}", "-".repeat(49));
+    for (encoding, (saved, secs)) in encodings.iter().zip(mix) {
+        println!(
+            "{:<10} {:>11.0} {:>13.1} {:>12.1}",
+            format!("{}:{}", encoding.name, encoding.level),
+            saved,
+            secs * 1e6,
+            saved_per_us(saved, secs)
+        );
+    }
+    println!("\nSaved B/us: response bytes saved per microsecond of compression CPU");
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use crate::results::percentile;
+use crate::scaling::thread_counts;
+use crate::{generate_test_data, TestData};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::harness;
+use rust_compress_comparison::{CompressionBenchError, Compressor};
+use std::error::Error;
+use std::sync::Barrier;
+use std::thread;
+use std::time::Instant;
+
+const STREAM_BUFFER_SIZE: usize = 1024 * 1024 * 4; // 4MB
+const CALLS_PER_STREAM: usize = 8;
+
+// One sweep point: `streams` threads each compressing their own buffer at once
+#[derive(Clone, Copy)]
+struct ContentionRun {
+    streams: usize,
+    aggregate_mbps: f64,
+    // Median and p99 of individual compress calls across all streams, in seconds
+    p50_secs: f64,
+    p99_secs: f64,
+}
+
+fn run_streams(
+    codec: &dyn Compressor,
+    buffers: &[Vec<u8>],
+) -> Result<ContentionRun, Box<dyn Error>> {
+    let barrier = Barrier::new(buffers.len());
+    let start = Instant::now();
+    let per_stream = thread::scope(|scope| {
+        let workers: Vec<_> = buffers
+            .iter()
+            .map(|buffer| {
+                let barrier = &barrier;
+                scope.spawn(move || {
+                    // Start together so every stream overlaps the others for its whole run
+                    barrier.wait();
+                    (0..CALLS_PER_STREAM)
+                        .map(|_| {
+                            let call = Instant::now();
+                            harness::compress(codec, buffer)?;
+                            Ok(call.elapsed().as_secs_f64())
+                        })
+                        .collect::<Result<Vec<f64>, CompressionBenchError>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("stream thread panicked"))
+            .collect::<Result<Vec<Vec<f64>>, CompressionBenchError>>()
+    })?;
+    let wall_secs = start.elapsed().as_secs_f64();
+
+    let mut calls: Vec<f64> = per_stream.into_iter().flatten().collect();
+    calls.sort_by(f64::total_cmp);
+    let total_mb =
+        (buffers.len() * CALLS_PER_STREAM * STREAM_BUFFER_SIZE) as f64 / (1024.0 * 1024.0);
+    Ok(ContentionRun {
+        streams: buffers.len(),
+        aggregate_mbps: total_mb / wall_secs,
+        p50_secs: percentile(&calls, 0.5),
+        p99_secs: percentile(&calls, 0.99),
+    })
+}
+
+// Independent compression streams per backend at 1, 2, 4, ... threads: linear aggregate scaling
+// means the codec is compute-bound, flattening aggregate with growing per-call latency means the
+// streams are fighting over memory bandwidth or cache
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    max_streams: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::REGISTRY
+            .iter()
+            .filter_map(|backend| backend.level("Default"))
+            .collect()
+    } else {
+        algorithms.to_vec()
+    };
+    // Distinct buffers so streams don't share input cache lines
+    let buffers: Vec<Vec<u8>> = (0..max_streams)
+        .map(|_| generate_test_data(&TestData::Mixed, STREAM_BUFFER_SIZE))
+        .collect();
+
+    println!(
+        "\nContention scaling ({}MB mixed buffer per stream, {} compress calls each, up to {} streams)",
+        STREAM_BUFFER_SIZE / 1024 / 1024,
+        CALLS_PER_STREAM,
+        max_streams
+    );
+    for codec in algorithms {
+        println!("\n=== {} {} ===\n", codec.name(), codec.level());
+        println!(
+            "{:>7} {:>14} {:>9} {:>12} {:>12} {:>13}",
+            "Streams", "Aggregate MB/s", "Scaling", "p50 call ms", "p99 call ms", "p50 slowdown"
+        );
+        println!("{}", "-".repeat(72));
+        let mut single: Option<ContentionRun> = None;
+        for streams in thread_counts(max_streams) {
+            let run = match run_streams(codec, &buffers[..streams]) {
+                Ok(run) => run,
+                Err(e) => {
+                    println!("{:>7} FAILED: {}", streams, e);
+                    break;
+                }
+            };
+            let baseline = *single.get_or_insert(run);
+            println!(
+                "{:>7} {:>14.1} {:>8.2}x {:>12.2} {:>12.2} {:>12.1}%",
+                run.streams,
+                run.aggregate_mbps,
+                run.aggregate_mbps / baseline.aggregate_mbps,
+                run.p50_secs * 1e3,
+                run.p99_secs * 1e3,
+                (run.p50_secs / baseline.p50_secs - 1.0) * 100.0
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use crate::{generate_test_data, TestData};
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress};
+use rust_compress_comparison::pool::{CodecPool, Pool};
+use std::marker::PhantomData;
+use std::thread;
+use std::time::Instant;
+
+const PAYLOAD_SIZE: usize = 4 * 1024; // 4KB
+const PAYLOAD_COUNT: usize = 10_000;
+const MESSAGES_PER_THREAD: usize = 2_000;
+const ZSTD_LEVEL: i32 = 3;
+
+// Autoref trick: the inherent const wins when the bound holds, the trait default otherwise
+struct Probe<T: ?Sized>(PhantomData<T>);
+
+trait NotSend {
+    const IS_SEND: bool = false;
+}
+impl<T: ?Sized> NotSend for Probe<T> {}
+impl<T: ?Sized + Send> Probe<T> {
+    const IS_SEND: bool = true;
+}
+
+trait NotSync {
+    const IS_SYNC: bool = false;
+}
+impl<T: ?Sized> NotSync for Probe<T> {}
+impl<T: ?Sized + Sync> Probe<T> {
+    const IS_SYNC: bool = true;
+}
+
+struct SupportRow {
+    name: &'static str,
+    send: bool,
+    sync: bool,
+    // Whether the type can be reset and reused rather than consumed by finish()
+    reusable: bool,
+}
+
+impl SupportRow {
+    fn strategy(&self) -> &'static str {
+        match (self.send, self.reusable) {
+            (false, _) => "per-thread",
+            (true, true) => "pool",
+            (true, false) => "per-call",
+        }
+    }
+}
+
+macro_rules! support_row {
+    ($ty:ty, reusable: $reusable:expr) => {
+        SupportRow {
+            name: stringify!($ty),
+            send: <Probe<$ty>>::IS_SEND,
+            sync: <Probe<$ty>>::IS_SYNC,
+            reusable: $reusable,
+        }
+    };
+}
+
+// Fails the build if a type we hand across threads loses its Send impl
+const _: () = {
+    // Sanity check that the probe can actually report a negative
+    assert!(!<Probe<std::rc::Rc<u8>>>::IS_SEND);
+    assert!(<Probe<Compress>>::IS_SEND);
+    assert!(<Probe<flate2::write::GzEncoder<Vec<u8>>>>::IS_SEND);
+    assert!(<Probe<lz4::Encoder<Vec<u8>>>>::IS_SEND);
+    assert!(<Probe<lz4_flex::frame::FrameEncoder<Vec<u8>>>>::IS_SEND);
+};
+
+fn support_matrix() -> Vec<SupportRow> {
+    vec![
+        support_row!(flate2::Compress, reusable: true),
+        support_row!(flate2::Decompress, reusable: true),
+        support_row!(flate2::write::GzEncoder<Vec<u8>>, reusable: false),
+        support_row!(flate2::read::GzDecoder<&'static [u8]>, reusable: false),
+        support_row!(lz4::Encoder<Vec<u8>>, reusable: false),
+        support_row!(lz4::Decoder<&'static [u8]>, reusable: false),
+        support_row!(lz4_flex::frame::FrameEncoder<Vec<u8>>, reusable: false),
+        support_row!(lz4_flex::frame::FrameDecoder<&'static [u8]>, reusable: false),
+        support_row!(zstd::bulk::Compressor<'static>, reusable: true),
+        support_row!(zstd::bulk::Decompressor<'static>, reusable: true),
+    ]
+}
+
+fn deflate_into(context: &mut Compress, payload: &[u8]) -> usize {
+    let mut output = Vec::with_capacity(payload.len() + 64);
+    context
+        .compress_vec(payload, &mut output, FlushCompress::Finish)
+        .unwrap();
+    output.len()
+}
+
+fn inflate_into(context: &mut Decompress, compressed: &[u8], original_len: usize) -> usize {
+    let mut output = Vec::with_capacity(original_len);
+    context
+        .decompress_vec(compressed, &mut output, FlushDecompress::Finish)
+        .unwrap();
+    output.len()
+}
+
+// Compress then decompress one message, the unit of work for the concurrency runs
+fn roundtrip(compress: &mut Compress, decompress: &mut Decompress, payload: &[u8]) -> usize {
+    let mut compressed = Vec::with_capacity(payload.len() + 64);
+    compress
+        .compress_vec(payload, &mut compressed, FlushCompress::Finish)
+        .unwrap();
+    inflate_into(decompress, &compressed, payload.len())
+}
+
+#[derive(Clone, Copy)]
+enum Strategy {
+    Fresh,
+    MutexPool,
+    ThreadLocal,
+}
+
+// Aggregate messages per second with `threads` workers each handling MESSAGES_PER_THREAD
+fn concurrent_throughput(payloads: &[&[u8]], threads: usize, strategy: Strategy) -> f64 {
+    let compress_pool = Pool::new(
+        || Compress::new(Compression::default(), false),
+        Compress::reset,
+    );
+    let decompress_pool = Pool::new(|| Decompress::new(false), |d| d.reset(false));
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for worker in 0..threads {
+            let (compress_pool, decompress_pool) = (&compress_pool, &decompress_pool);
+            scope.spawn(move || {
+                for i in 0..MESSAGES_PER_THREAD {
+                    let payload = payloads[(worker * MESSAGES_PER_THREAD + i) % payloads.len()];
+                    let restored = match strategy {
+                        Strategy::Fresh => roundtrip(
+                            &mut Compress::new(Compression::default(), false),
+                            &mut Decompress::new(false),
+                            payload,
+                        ),
+                        Strategy::MutexPool => roundtrip(
+                            &mut compress_pool.get(),
+                            &mut decompress_pool.get(),
+                            payload,
+                        ),
+                        Strategy::ThreadLocal => {
+                            CodecPool::with_compress(Compression::default(), |compress| {
+                                CodecPool::with_decompress(|decompress| {
+                                    roundtrip(compress, decompress, payload)
+                                })
+                            })
+                        }
+                    };
+                    assert_eq!(restored, payload.len());
+                }
+            });
+        }
+    });
+    (threads * MESSAGES_PER_THREAD) as f64 / start.elapsed().as_secs_f64()
+}
+
+pub fn run(max_threads: usize) {
+    println!("\n=== Codec Type Support Matrix ===\n");
+    println!(
+        "{:<46} {:>5} {:>5} {:>9} {:>11}",
+        "Type", "Send", "Sync", "Reusable", "Strategy"
+    );
+    println!("{}", "-".repeat(80));
+    for row in support_matrix() {
+        println!(
+            "{:<46} {:>5} {:>5} {:>9} {:>11}",
+            row.name,
+            row.send,
+            row.sync,
+            row.reusable,
+            row.strategy()
+        );
+    }
+
+    let data = generate_test_data(&TestData::Mixed, PAYLOAD_SIZE * PAYLOAD_COUNT);
+    let payloads: Vec<&[u8]> = data.chunks(PAYLOAD_SIZE).collect();
+
+    let start = Instant::now();
+    let mut fresh_bytes = 0;
+    for payload in &payloads {
+        let mut context = Compress::new(Compression::default(), false);
+        fresh_bytes += deflate_into(&mut context, payload);
+    }
+    let fresh_secs = start.elapsed().as_secs_f64();
+
+    let pool = Pool::new(
+        || Compress::new(Compression::default(), false),
+        Compress::reset,
+    );
+    let start = Instant::now();
+    let mut pooled_bytes = 0;
+    for payload in &payloads {
+        pooled_bytes += deflate_into(&mut pool.get(), payload);
+    }
+    let pooled_secs = start.elapsed().as_secs_f64();
+    assert_eq!(fresh_bytes, pooled_bytes);
+
+    println!(
+        "\n=== Pooled vs Fresh Deflate Contexts ({} x {}KB payloads) ===\n",
+        PAYLOAD_COUNT,
+        PAYLOAD_SIZE / 1024
+    );
+    println!(
+        "Fresh:  {:.2}us/msg\nPooled: {:.2}us/msg ({:.1}% faster)",
+        fresh_secs / PAYLOAD_COUNT as f64 * 1e6,
+        pooled_secs / PAYLOAD_COUNT as f64 * 1e6,
+        (fresh_secs - pooled_secs) / fresh_secs * 100.0
+    );
+
+    let start = Instant::now();
+    let mut fresh_bytes = 0;
+    for payload in &payloads {
+        fresh_bytes += zstd::bulk::compress(payload, ZSTD_LEVEL).unwrap().len();
+    }
+    let fresh_secs = start.elapsed().as_secs_f64();
+    let start = Instant::now();
+    let mut pooled_bytes = 0;
+    for payload in &payloads {
+        pooled_bytes +=
+            CodecPool::with_zstd_compress(ZSTD_LEVEL, |context| context.compress(payload))
+                .and_then(|compressed| compressed)
+                .unwrap()
+                .len();
+    }
+    let pooled_secs = start.elapsed().as_secs_f64();
+    assert_eq!(fresh_bytes, pooled_bytes);
+    println!(
+        "\n=== Pooled vs Fresh Zstd Contexts (thread-local CodecPool, level {}) ===\n",
+        ZSTD_LEVEL
+    );
+    println!(
+        "Fresh:  {:.2}us/msg\nPooled: {:.2}us/msg ({:.1}% faster)",
+        fresh_secs / PAYLOAD_COUNT as f64 * 1e6,
+        pooled_secs / PAYLOAD_COUNT as f64 * 1e6,
+        (fresh_secs - pooled_secs) / fresh_secs * 100.0
+    );
+
+    println!("\n=== Concurrent Roundtrips: Fresh vs Mutex Pool vs Thread-Local CodecPool ===\n");
+    println!(
+        "{:>7} {:>14} {:>14} {:>14}",
+        "Threads", "Fresh msg/s", "Mutex msg/s", "TLS msg/s"
+    );
+    println!("{}", "-".repeat(52));
+    let mut threads = 1;
+    while threads <= max_threads {
+        println!(
+            "{:>7} {:>14.0} {:>14.0} {:>14.0}",
+            threads,
+            concurrent_throughput(&payloads, threads, Strategy::Fresh),
+            concurrent_throughput(&payloads, threads, Strategy::MutexPool),
+            concurrent_throughput(&payloads, threads, Strategy::ThreadLocal)
+        );
+        threads *= 2;
+    }
+}
@@ -0,0 +1,163 @@
+use crate::results::percentile;
+use crate::{generate_test_data, TestData};
+use rand::{thread_rng, Rng};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+
+// A way of damaging a compressed buffer
+struct Mutation {
+    name: &'static str,
+    apply: fn(&mut Vec<u8>, &mut rand::rngs::ThreadRng),
+}
+
+const MUTATIONS: [Mutation; 3] = [
+    Mutation {
+        name: "bit flip",
+        apply: |data, rng| {
+            let bit = rng.gen_range(0..data.len() * 8);
+            data[bit / 8] ^= 1 << (bit % 8);
+        },
+    },
+    Mutation {
+        name: "byte burst",
+        apply: |data, rng| {
+            let start = rng.gen_range(0..data.len());
+            let end = (start + rng.gen_range(1..=16)).min(data.len());
+            rng.fill(&mut data[start..end]);
+        },
+    },
+    Mutation {
+        name: "truncation",
+        apply: |data, rng| {
+            let len = rng.gen_range(0..data.len());
+            data.truncate(len);
+        },
+    },
+];
+
+// How a decoder answered one damaged input
+#[derive(Default)]
+struct Outcomes {
+    errors: usize,
+    panics: usize,
+    // Decoded without complaint into something other than the input
+    garbage: usize,
+    // The damage hit bytes the decoder ignores, so the input came back intact
+    harmless: usize,
+    // Time to return an error, sorted
+    detect: Vec<f64>,
+}
+
+fn damage(
+    codec: &dyn Compressor,
+    mutation: &Mutation,
+    original: &[u8],
+    compressed: &[u8],
+    trials: usize,
+) -> Outcomes {
+    let mut rng = thread_rng();
+    let mut outcomes = Outcomes::default();
+    for _ in 0..trials {
+        let mut damaged = compressed.to_vec();
+        (mutation.apply)(&mut damaged, &mut rng);
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| codec.decompress(&damaged)));
+        let secs = start.elapsed().as_secs_f64();
+        match result {
+            Err(_) => outcomes.panics += 1,
+            Ok(Err(_)) => {
+                outcomes.errors += 1;
+                outcomes.detect.push(secs);
+            }
+            Ok(Ok(restored)) if restored == original => outcomes.harmless += 1,
+            Ok(Ok(_)) => outcomes.garbage += 1,
+        }
+    }
+    outcomes.detect.sort_by(f64::total_cmp);
+    outcomes
+}
+
+// How each decoder reacts to damaged input: a clean error, a panic, or wrong output passed off
+// as good, and how soon it notices
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    size_kb: usize,
+    trials: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::configurations().collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let original = generate_test_data(&TestData::Mixed, size_kb * 1024);
+    println!(
+        "\nCorrupted input ({}KB mixed data, {} damaged copies per mutation)\n",
+        size_kb, trials
+    );
+    println!(
+        "{:<18} {:<11} {:>7} {:>7} {:>8} {:>9} {:>12} {:>12}",
+        "Configuration",
+        "Mutation",
+        "Errors",
+        "Panics",
+        "Garbage",
+        "Harmless",
+        "Detect p50",
+        "Full decode"
+    );
+    println!("{}", "-".repeat(93));
+
+    // Panics are counted, not printed
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut failed = None;
+    for codec in &algorithms {
+        let label = format!("{} {}", codec.name(), codec.level());
+        let compressed = match codec.compress(&original) {
+            Ok(compressed) if !compressed.is_empty() => compressed,
+            Ok(_) => continue,
+            Err(e) => {
+                failed = Some(format!("{} could not compress: {}", label, e));
+                break;
+            }
+        };
+        let start = Instant::now();
+        let decoded = codec.decompress(&compressed);
+        let full = start.elapsed().as_secs_f64();
+        if !decoded.is_ok_and(|restored| restored == original) {
+            failed = Some(format!("{} does not round-trip undamaged input", label));
+            break;
+        }
+        for mutation in &MUTATIONS {
+            let outcomes = damage(*codec, mutation, &original, &compressed, trials);
+            let detect = if outcomes.detect.is_empty() {
+                "-".to_string()
+            } else {
+                format!("{:.1}us", percentile(&outcomes.detect, 0.5) * 1e6)
+            };
+            println!(
+                "{:<18} {:<11} {:>7} {:>7} {:>8} {:>9} {:>12} {:>10.1}us",
+                label,
+                mutation.name,
+                outcomes.errors,
+                outcomes.panics,
+                outcomes.garbage,
+                outcomes.harmless,
+                detect,
+                full * 1e6
+            );
+        }
+    }
+    panic::set_hook(hook);
+    if let Some(message) = failed {
+        return Err(message.into());
+    }
+    println!(
+        "\nGarbage: decoded without error into the wrong bytes; formats without a content \
+         checksum, or with it turned off, cannot tell"
+    );
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use crate::results::{CellResult, RunResults};
+use std::error::Error;
+use std::path::Path;
+
+pub const DEFAULT_BANDWIDTH_MBIT: [f64; 3] = [10.0, 100.0, 1000.0];
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+// Prices the model charges: compute time on either end and bytes leaving the sender
+pub struct Prices {
+    pub cpu_per_hour: f64,
+    pub egress_per_gb: f64,
+}
+
+// Moving 1GB of original data across the link, compressed with one cell's configuration
+struct Transfer {
+    compress: f64,
+    wire: f64,
+    decompress: f64,
+    dollars: f64,
+}
+
+impl Transfer {
+    fn uncompressed(bandwidth_mbit: f64, prices: &Prices) -> Self {
+        Transfer {
+            compress: 0.0,
+            wire: GB * 8.0 / (bandwidth_mbit * 1e6),
+            decompress: 0.0,
+            dollars: prices.egress_per_gb,
+        }
+    }
+
+    fn compressed(cell: &CellResult, bandwidth_mbit: f64, prices: &Prices) -> Self {
+        let per_gb = |secs: f64| secs * GB / cell.original_size as f64;
+        let compress = per_gb(cell.compress_secs);
+        let decompress = per_gb(cell.decompress_secs);
+        Transfer {
+            compress,
+            wire: GB / cell.ratio * 8.0 / (bandwidth_mbit * 1e6),
+            decompress,
+            dollars: prices.egress_per_gb / cell.ratio
+                + prices.cpu_per_hour * (compress + decompress) / 3600.0,
+        }
+    }
+
+    // Each step waits for the one before it to finish
+    fn serial(&self) -> f64 {
+        self.compress + self.wire + self.decompress
+    }
+
+    // Compression, transfer and decompression overlapped as a stream, so the slowest step sets
+    // the pace
+    fn streamed(&self) -> f64 {
+        self.compress.max(self.wire).max(self.decompress)
+    }
+}
+
+fn print_row(label: &str, ratio: f64, transfer: &Transfer, raw: &Transfer) {
+    println!(
+        "{:<20} {:>7.2}x {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>8.4} {:>7.2}x",
+        label,
+        ratio,
+        transfer.compress,
+        transfer.wire,
+        transfer.decompress,
+        transfer.serial(),
+        transfer.streamed(),
+        transfer.dollars,
+        raw.serial() / transfer.serial()
+    );
+}
+
+// End-to-end time and dollar cost of sending 1GB through each measured configuration, per link
+// bandwidth, against sending it uncompressed
+pub fn run(input: &Path, bandwidths: &[f64], prices: &Prices) -> Result<(), Box<dyn Error>> {
+    let results = RunResults::load(input)?;
+    println!(
+        "\nTransfer cost model for 1GB of original data (CPU ${}/hour, egress ${}/GB)",
+        prices.cpu_per_hour, prices.egress_per_gb
+    );
+    for data_type in results.data_types() {
+        let cells: Vec<&CellResult> = results
+            .cells
+            .iter()
+            .filter(|cell| cell.data_type == data_type && cell.ratio > 0.0)
+            .collect();
+        for &bandwidth in bandwidths {
+            let raw = Transfer::uncompressed(bandwidth, prices);
+            println!("\n=== {} over {} Mbit/s ===\n", data_type, bandwidth);
+            println!(
+                "{:<20} {:>8} {:>9} {:>9} {:>9} {:>9} {:>9} {:>8} {:>8}",
+                "Configuration",
+                "Ratio",
+                "Comp s",
+                "Wire s",
+                "Decomp s",
+                "Serial s",
+                "Stream s",
+                "$/GB",
+                "Speedup"
+            );
+            println!("{}", "-".repeat(99));
+            print_row("uncompressed", 1.0, &raw, &raw);
+            let mut fastest: Option<(String, f64)> = None;
+            let mut cheapest: Option<(String, f64)> = None;
+            for cell in &cells {
+                let transfer = Transfer::compressed(cell, bandwidth, prices);
+                print_row(&cell.label(), cell.ratio, &transfer, &raw);
+                if fastest
+                    .as_ref()
+                    .is_none_or(|(_, secs)| transfer.serial() < *secs)
+                {
+                    fastest = Some((cell.label(), transfer.serial()));
+                }
+                if cheapest
+                    .as_ref()
+                    .is_none_or(|(_, dollars)| transfer.dollars < *dollars)
+                {
+                    cheapest = Some((cell.label(), transfer.dollars));
+                }
+            }
+            match fastest {
+                Some((label, secs)) if secs < raw.serial() => {
+                    println!("\nFastest end to end: {}", label)
+                }
+                _ => println!(
+                    "\nFastest end to end: uncompressed; no codec pays off at this bandwidth"
+                ),
+            }
+            if let Some((label, _)) = cheapest.filter(|(_, dollars)| *dollars < raw.dollars) {
+                println!("Cheapest: {}", label);
+            }
+        }
+    }
+    println!(
+        "\nSerial: compress, send, then decompress; Stream: the three overlapped. Speedup is \
+         serial time against sending uncompressed"
+    );
+    Ok(())
+}
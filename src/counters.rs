@@ -0,0 +1,175 @@
+// Hardware performance counters per cell: instructions and cycles separate algorithmic cost from
+// stalls, and last-level cache misses show when a codec is bound by memory bandwidth
+use serde::{Deserialize, Serialize};
+
+// Mean counter readings per trial on the benchmark thread; `None` without `perf-counters` or
+// where the kernel refuses perf events (containers, perf_event_paranoid > 2)
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct HardwareCounters {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compress: Option<CounterValues>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decompress: Option<CounterValues>,
+}
+
+impl HardwareCounters {
+    // "instructions M (IPC, LLC misses K)" for compression / decompression, or "-" where unmeasured
+    pub fn describe(&self) -> String {
+        let describe = |values: Option<CounterValues>| match values {
+            Some(values) => format!(
+                "{:.1}M ({:.2}, {:.1}K)",
+                values.instructions as f64 / 1e6,
+                values.ipc(),
+                values.llc_misses as f64 / 1e3
+            ),
+            None => "-".to_string(),
+        };
+        format!(
+            "{} / {}",
+            describe(self.compress),
+            describe(self.decompress)
+        )
+    }
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CounterValues {
+    pub instructions: u64,
+    pub cycles: u64,
+    pub llc_misses: u64,
+}
+
+impl CounterValues {
+    pub fn since(self, earlier: CounterValues) -> CounterValues {
+        CounterValues {
+            instructions: self.instructions.saturating_sub(earlier.instructions),
+            cycles: self.cycles.saturating_sub(earlier.cycles),
+            llc_misses: self.llc_misses.saturating_sub(earlier.llc_misses),
+        }
+    }
+
+    pub fn plus(self, other: CounterValues) -> CounterValues {
+        CounterValues {
+            instructions: self.instructions + other.instructions,
+            cycles: self.cycles + other.cycles,
+            llc_misses: self.llc_misses + other.llc_misses,
+        }
+    }
+
+    pub fn divide(self, trials: u64) -> CounterValues {
+        CounterValues {
+            instructions: self.instructions / trials,
+            cycles: self.cycles / trials,
+            llc_misses: self.llc_misses / trials,
+        }
+    }
+
+    // Instructions per cycle
+    pub fn ipc(&self) -> f64 {
+        if self.cycles == 0 {
+            0.0
+        } else {
+            self.instructions as f64 / self.cycles as f64
+        }
+    }
+}
+
+#[cfg(all(feature = "perf-counters", target_os = "linux"))]
+mod perf {
+    use super::CounterValues;
+    use perf_event_open_sys::bindings::{
+        perf_event_attr, perf_event_read_format_PERF_FORMAT_GROUP,
+        perf_hw_id_PERF_COUNT_HW_CACHE_MISSES, perf_hw_id_PERF_COUNT_HW_CPU_CYCLES,
+        perf_hw_id_PERF_COUNT_HW_INSTRUCTIONS, perf_type_id_PERF_TYPE_HARDWARE,
+    };
+    use perf_event_open_sys::{ioctls, perf_event_open};
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::fd::{AsRawFd, FromRawFd};
+
+    // Counted together so all three cover exactly the same instructions
+    const EVENTS: [u32; 3] = [
+        perf_hw_id_PERF_COUNT_HW_INSTRUCTIONS,
+        perf_hw_id_PERF_COUNT_HW_CPU_CYCLES,
+        perf_hw_id_PERF_COUNT_HW_CACHE_MISSES,
+    ];
+
+    // A perf event group on the calling thread, counting user-space work from creation onwards
+    pub struct Group {
+        leader: File,
+        // Kept open so the members keep counting
+        _members: Vec<File>,
+    }
+
+    fn open(event: u32, group_fd: i32) -> io::Result<File> {
+        let mut attrs = perf_event_attr {
+            type_: perf_type_id_PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<perf_event_attr>() as u32,
+            config: event as u64,
+            read_format: perf_event_read_format_PERF_FORMAT_GROUP as u64,
+            ..Default::default()
+        };
+        attrs.set_disabled(u64::from(group_fd == -1));
+        attrs.set_exclude_kernel(1);
+        attrs.set_exclude_hv(1);
+        // SAFETY: `attrs` is a fully initialized attribute block of the size it declares
+        let fd = unsafe { perf_event_open(&mut attrs, 0, -1, group_fd, 0) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the kernel just handed us this descriptor and nothing else owns it
+        Ok(unsafe { File::from_raw_fd(fd) })
+    }
+
+    impl Group {
+        pub fn open() -> io::Result<Group> {
+            let leader = open(EVENTS[0], -1)?;
+            let members = EVENTS[1..]
+                .iter()
+                .map(|event| open(*event, leader.as_raw_fd()))
+                .collect::<io::Result<Vec<File>>>()?;
+            // SAFETY: ENABLE on a perf event descriptor we own, with no pointer argument
+            if unsafe { ioctls::ENABLE(leader.as_raw_fd(), 0) } < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Group {
+                leader,
+                _members: members,
+            })
+        }
+
+        // Group read layout: the event count, then one value per event in creation order
+        pub fn read(&self) -> io::Result<CounterValues> {
+            let mut buffer = [0u8; 8 * (1 + EVENTS.len())];
+            (&self.leader).read_exact(&mut buffer)?;
+            let value = |index: usize| {
+                u64::from_ne_bytes(buffer[8 * index..8 * (index + 1)].try_into().unwrap())
+            };
+            Ok(CounterValues {
+                instructions: value(1),
+                cycles: value(2),
+                llc_misses: value(3),
+            })
+        }
+    }
+
+    thread_local! {
+        // Opened on first use; `None` once the kernel has refused, so we only ask once per thread
+        static GROUP: Option<Group> = Group::open().ok();
+    }
+
+    pub fn read() -> Option<CounterValues> {
+        GROUP
+            .try_with(|group| group.as_ref()?.read().ok())
+            .ok()
+            .flatten()
+    }
+}
+
+// This thread's running counter totals, when `perf-counters` is compiled in and permitted
+pub fn read() -> Option<CounterValues> {
+    #[cfg(all(feature = "perf-counters", target_os = "linux"))]
+    return perf::read();
+    #[cfg(not(all(feature = "perf-counters", target_os = "linux")))]
+    None
+}
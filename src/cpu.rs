@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+
+// Scheduling and frequency-scaling state the run was measured under
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CpuSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_core: Option<usize>,
+    // Distinct cpufreq governors across cores; empty where cpufreq isn't exposed
+    #[serde(default)]
+    pub governors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turbo: Option<bool>,
+}
+
+impl CpuSettings {
+    pub fn detect() -> Self {
+        CpuSettings {
+            pinned_core: None,
+            governors: detect_governors(),
+            turbo: detect_turbo(),
+        }
+    }
+
+    // Conditions that make timings drift between or within runs
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let relaxed: Vec<&str> = self
+            .governors
+            .iter()
+            .filter(|g| *g != "performance")
+            .map(String::as_str)
+            .collect();
+        if !relaxed.is_empty() {
+            warnings.push(format!(
+                "CPU governor is {} rather than performance; clocks may ramp during the run",
+                relaxed.join(", ")
+            ));
+        }
+        if self.turbo == Some(true) {
+            warnings.push(
+                "turbo boost is enabled; clocks will vary with temperature and load".to_string(),
+            );
+        }
+        if self.pinned_core.is_none() {
+            warnings.push(
+                "benchmark thread is not pinned (see --pin-core); migrations add noise".to_string(),
+            );
+        }
+        warnings
+    }
+
+    pub fn describe(&self) -> String {
+        let pinned = self.pinned_core.map_or("unpinned".to_string(), |core| {
+            format!("pinned to core {}", core)
+        });
+        let governor = if self.governors.is_empty() {
+            "unknown".to_string()
+        } else {
+            self.governors.join("/")
+        };
+        let turbo = match self.turbo {
+            Some(true) => "on",
+            Some(false) => "off",
+            None => "unknown",
+        };
+        format!("{}, governor {}, turbo {}", pinned, governor, turbo)
+    }
+}
+
+fn detect_governors() -> Vec<String> {
+    let mut governors: Vec<String> = fs::read_dir("/sys/devices/system/cpu")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path().join("cpufreq/scaling_governor")).ok())
+        .map(|governor| governor.trim().to_string())
+        .collect();
+    governors.sort();
+    governors.dedup();
+    governors
+}
+
+// intel_pstate reports turbo inverted as no_turbo; acpi-cpufreq and amd-pstate expose boost
+fn detect_turbo() -> Option<bool> {
+    let read = |path: &str| fs::read_to_string(path).ok().map(|v| v.trim() == "1");
+    read("/sys/devices/system/cpu/intel_pstate/no_turbo")
+        .map(|no_turbo| !no_turbo)
+        .or_else(|| read("/sys/devices/system/cpu/cpufreq/boost"))
+}
+
+// Restricts the calling thread, and threads it spawns afterwards, to one core
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(core: usize) -> io::Result<()> {
+    if core >= libc::CPU_SETSIZE as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("core {} is out of range", core),
+        ));
+    }
+    // SAFETY: `set` is a plain bitmask owned by this frame, sized as the syscall expects
+    let result = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(_core: usize) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "thread pinning is only supported on Linux",
+    ))
+}
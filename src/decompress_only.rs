@@ -0,0 +1,100 @@
+use crate::results::{mean_and_stddev, percentile};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::harness::try_timed;
+use rust_compress_comparison::CompressionBenchError;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct FileResult {
+    path: PathBuf,
+    format: &'static str,
+    compressed_size: usize,
+    original_size: usize,
+    // Decompression MB/s of output produced, one per trial
+    mbps: Vec<f64>,
+    secs: Vec<f64>,
+}
+
+fn measure(path: &Path, trials: usize) -> Result<Option<FileResult>, Box<dyn Error>> {
+    let compressed = fs::read(path).map_err(|source| CompressionBenchError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let Some(backend) = backends::detect(&compressed) else {
+        return Ok(None);
+    };
+    // Any level decodes the format; levels only matter when compressing
+    let codec = backend.levels[0];
+
+    let mut mbps = Vec::with_capacity(trials);
+    let mut secs = Vec::with_capacity(trials);
+    let mut original_size = 0;
+    for _ in 0..trials {
+        let (restored, elapsed) =
+            try_timed(|| codec.decompress(&compressed)).map_err(|source| {
+                CompressionBenchError::Decompress {
+                    backend: codec.name(),
+                    level: codec.level(),
+                    source,
+                }
+            })?;
+        original_size = restored.len();
+        mbps.push(restored.len() as f64 / (1024.0 * 1024.0) / elapsed);
+        secs.push(elapsed);
+    }
+    secs.sort_by(f64::total_cmp);
+    Ok(Some(FileResult {
+        path: path.to_path_buf(),
+        format: backend.id,
+        compressed_size: compressed.len(),
+        original_size,
+        mbps,
+        secs,
+    }))
+}
+
+// Decompression throughput of existing compressed files, for readers that never compress
+pub fn run(paths: &[PathBuf], trials: usize) -> Result<(), Box<dyn Error>> {
+    let mut results = Vec::new();
+    for path in paths {
+        match measure(path, trials)? {
+            Some(result) => results.push(result),
+            None => println!(
+                "Skipping {}: not a gzip, lz4 frame or zstd file",
+                path.display()
+            ),
+        }
+    }
+    if results.is_empty() {
+        return Err("no recognized compressed files to benchmark".into());
+    }
+
+    println!(
+        "\n=== Decompression Only ({} trials per file) ===\n",
+        trials
+    );
+    println!(
+        "{:<32} {:<8} {:>10} {:>10} {:>8} {:>18} {:>9}",
+        "File", "Format", "Comp MB", "Orig MB", "Ratio", "Decomp MB/s ± sd", "p50 ms"
+    );
+    println!("{}", "-".repeat(101));
+    for result in &results {
+        let (mean, stddev) = mean_and_stddev(&result.mbps);
+        let name = result.path.file_name().map_or_else(
+            || result.path.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        println!(
+            "{:<32} {:<8} {:>10.2} {:>10.2} {:>7.2}x {:>18} {:>9.2}",
+            name,
+            result.format,
+            result.compressed_size as f64 / (1024.0 * 1024.0),
+            result.original_size as f64 / (1024.0 * 1024.0),
+            result.original_size as f64 / result.compressed_size as f64,
+            format!("{:.1} ± {:.1}", mean, stddev),
+            percentile(&result.secs, 0.5) * 1e3
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use crate::records::generate_records;
+use crate::results::percentile;
+use std::time::Instant;
+#[cfg(feature = "deflate-dict")]
+use zlib_rs::{Deflate, DeflateFlush, Inflate, InflateFlush};
+
+// Records held back to train dictionaries, never measured
+const TRAINING_RECORDS: usize = 1_000;
+const ZSTD_LEVEL: i32 = 3;
+#[cfg(feature = "deflate-dict")]
+const DEFLATE_LEVEL: i32 = 6;
+// Deflate's window; a larger preset dictionary would be ignored past this
+#[cfg(feature = "deflate-dict")]
+const DEFLATE_MAX_DICT: usize = 32 * 1024;
+
+// Compresses one unit: a record, or a batch of records for the batching strategies
+type CompressCall = Box<dyn FnMut(&[u8]) -> Vec<u8>>;
+// Restores one unit given its original length, which a real store keeps alongside it
+type DecompressCall = Box<dyn FnMut(&[u8], usize) -> Vec<u8>>;
+
+struct Strategy {
+    name: String,
+    // Records per compressed unit; reading one record means decompressing its whole unit
+    batch: usize,
+    compress: CompressCall,
+    decompress: DecompressCall,
+}
+
+fn zstd_strategy(name: String, batch: usize, dictionary: &[u8]) -> Strategy {
+    let mut compressor =
+        zstd::bulk::Compressor::with_dictionary(ZSTD_LEVEL, dictionary).expect("zstd context");
+    let mut decompressor =
+        zstd::bulk::Decompressor::with_dictionary(dictionary).expect("zstd context");
+    Strategy {
+        name,
+        batch,
+        compress: Box::new(move |unit| compressor.compress(unit).expect("zstd compress")),
+        decompress: Box::new(move |compressed, len| {
+            decompressor
+                .decompress(compressed, len)
+                .expect("zstd decompress")
+        }),
+    }
+}
+
+// Raw deflate (no gzip/zlib framing, which would swamp tiny records) via zlib-rs, which can
+// preset a dictionary on both ends; an empty dictionary means none
+#[cfg(feature = "deflate-dict")]
+fn deflate_strategy(name: String, batch: usize, dictionary: Vec<u8>) -> Strategy {
+    let mut deflate = Deflate::new(DEFLATE_LEVEL, false, 15);
+    let mut inflate = Inflate::new(false, 15);
+    let inflate_dictionary = dictionary.clone();
+    Strategy {
+        name,
+        batch,
+        compress: Box::new(move |unit| {
+            deflate.reset();
+            if !dictionary.is_empty() {
+                deflate
+                    .set_dictionary(&dictionary)
+                    .expect("deflate dictionary");
+            }
+            let mut output = vec![0u8; zlib_rs::compress_bound(unit.len())];
+            deflate
+                .compress(unit, &mut output, DeflateFlush::Finish)
+                .expect("deflate compress");
+            output.truncate(deflate.total_out() as usize);
+            output
+        }),
+        decompress: Box::new(move |compressed, len| {
+            inflate.reset(false);
+            if !inflate_dictionary.is_empty() {
+                inflate
+                    .set_dictionary(&inflate_dictionary)
+                    .expect("inflate dictionary");
+            }
+            let mut output = vec![0u8; len];
+            inflate
+                .decompress(compressed, &mut output, InflateFlush::Finish)
+                .expect("inflate decompress");
+            output.truncate(inflate.total_out() as usize);
+            output
+        }),
+    }
+}
+
+fn strategies(training: &[Vec<u8>], dict_size: usize, batch: usize) -> Vec<Strategy> {
+    let zstd_dictionary =
+        zstd::dict::from_samples(training, dict_size).expect("zstd dictionary training");
+    #[allow(unused_mut)]
+    let mut strategies = vec![
+        zstd_strategy("Zstd per record".to_string(), 1, &[]),
+        zstd_strategy(
+            format!("Zstd per record + {}KB dict", zstd_dictionary.len() / 1024),
+            1,
+            &zstd_dictionary,
+        ),
+        zstd_strategy(format!("Zstd batch of {}", batch), batch, &[]),
+    ];
+
+    // A preset dictionary is just bytes likely to recur; the most recent training records are
+    // what a live pipeline would have on hand, placed last since deflate favours near matches
+    #[cfg(feature = "deflate-dict")]
+    {
+        let mut preset: Vec<u8> = training.concat();
+        let keep = dict_size.min(DEFLATE_MAX_DICT).min(preset.len());
+        preset.drain(..preset.len() - keep);
+        strategies.push(deflate_strategy(
+            "Deflate per record".to_string(),
+            1,
+            Vec::new(),
+        ));
+        strategies.push(deflate_strategy(
+            format!("Deflate per record + {}KB dict", preset.len() / 1024),
+            1,
+            preset,
+        ));
+        strategies.push(deflate_strategy(
+            format!("Deflate batch of {}", batch),
+            batch,
+            Vec::new(),
+        ));
+    }
+    strategies
+}
+
+// Compresses every unit once, timing each call, then times reading back each unit
+fn measure(strategy: &mut Strategy, units: &[Vec<u8>]) -> (f64, Vec<f64>, Vec<f64>) {
+    let mut compressed = Vec::with_capacity(units.len());
+    let mut compress_secs = Vec::with_capacity(units.len());
+    for unit in units {
+        let start = Instant::now();
+        compressed.push((strategy.compress)(unit));
+        compress_secs.push(start.elapsed().as_secs_f64());
+    }
+    let mut decompress_secs = Vec::with_capacity(units.len());
+    for (unit, compressed) in units.iter().zip(&compressed) {
+        let start = Instant::now();
+        let restored = (strategy.decompress)(compressed, unit.len());
+        decompress_secs.push(start.elapsed().as_secs_f64());
+        assert!(restored == *unit, "{} failed to round-trip", strategy.name);
+    }
+    compress_secs.sort_by(f64::total_cmp);
+    decompress_secs.sort_by(f64::total_cmp);
+    let original: usize = units.iter().map(Vec::len).sum();
+    let packed: usize = compressed.iter().map(Vec::len).sum();
+    (
+        original as f64 / packed as f64,
+        compress_secs,
+        decompress_secs,
+    )
+}
+
+// Many small similar records compressed one by one, with and without a trained or preset
+// dictionary, against batching them first: the ratio/latency trade-off behind event pipelines
+pub fn run(records: usize, batch: usize, dict_kb: usize) {
+    let all = generate_records(TRAINING_RECORDS + records);
+    let (training, measured) = all.split_at(TRAINING_RECORDS);
+    let mean_len = measured.iter().map(Vec::len).sum::<usize>() as f64 / records as f64;
+
+    println!(
+        "\n=== Small-Record Dictionaries ({} records of ~{:.0}B, dictionaries trained on {} others) ===\n",
+        records, mean_len, TRAINING_RECORDS
+    );
+    println!(
+        "{:<32} {:>7} {:>13} {:>13} {:>15} {:>15}",
+        "Strategy", "Ratio", "Comp p50 us", "Comp p99 us", "Read 1 p50 us", "Comp us/record"
+    );
+    println!("{}", "-".repeat(100));
+    for mut strategy in strategies(training, dict_kb * 1024, batch) {
+        let units: Vec<Vec<u8>> = measured
+            .chunks(strategy.batch)
+            .map(|c| c.concat())
+            .collect();
+        let (ratio, compress_secs, decompress_secs) = measure(&mut strategy, &units);
+        let total_secs: f64 = compress_secs.iter().sum();
+        println!(
+            "{:<32} {:>6.2}x {:>13.1} {:>13.1} {:>15.1} {:>15.2}",
+            strategy.name,
+            ratio,
+            percentile(&compress_secs, 0.5) * 1e6,
+            percentile(&compress_secs, 0.99) * 1e6,
+            percentile(&decompress_secs, 0.5) * 1e6,
+            total_secs / records as f64 * 1e6
+        );
+    }
+    println!(
+        "\nBatch rows time a whole batch per call; reading one record means decompressing its batch."
+    );
+}
@@ -0,0 +1,131 @@
+use crate::results::{CellResult, RunResults};
+use comfy_table::presets::UTF8_FULL_CONDENSED;
+use comfy_table::{Cell, CellAlignment, Color, Table};
+
+// Percentage changes between two runs of the same cell; positive is always better
+struct CellDiff<'a> {
+    cell: &'a CellResult,
+    ratio_pct: f64,
+    compress_pct: Option<f64>,
+    decompress_pct: Option<f64>,
+    efficiency_pct: Option<f64>,
+}
+
+fn pct_change(old: f64, new: f64) -> Option<f64> {
+    (old > 0.0 && new > 0.0).then(|| (new - old) / old * 100.0)
+}
+
+fn diff_cells<'a>(old: &RunResults, new: &'a RunResults) -> Vec<CellDiff<'a>> {
+    new.cells
+        .iter()
+        .filter_map(|cell| {
+            let previous = old.matching(cell)?;
+            Some(CellDiff {
+                cell,
+                ratio_pct: pct_change(previous.ratio, cell.ratio).unwrap_or(0.0),
+                compress_pct: pct_change(previous.compress_mbps(), cell.compress_mbps()),
+                decompress_pct: pct_change(previous.decompress_mbps(), cell.decompress_mbps()),
+                efficiency_pct: pct_change(
+                    previous.efficiency.saved_mb_per_cpu_sec,
+                    cell.efficiency.saved_mb_per_cpu_sec,
+                ),
+            })
+        })
+        .collect()
+}
+
+// Changes beyond the threshold are colored (or flagged when color is off)
+fn pct_cell(pct: Option<f64>, threshold_pct: f64, color: bool) -> Cell {
+    let Some(pct) = pct else {
+        return Cell::new("n/a");
+    };
+    let significant = pct.abs() > threshold_pct;
+    let text = if significant && !color {
+        format!("{:+.1}% *", pct)
+    } else {
+        format!("{:+.1}%", pct)
+    };
+    let cell = Cell::new(text);
+    match (significant && color, pct > 0.0) {
+        (true, true) => cell.fg(Color::Green),
+        (true, false) => cell.fg(Color::Red),
+        (false, _) => cell,
+    }
+}
+
+fn print_unmatched(title: &str, from: &RunResults, other: &RunResults) {
+    let missing: Vec<&CellResult> = from
+        .cells
+        .iter()
+        .filter(|c| other.matching(c).is_none())
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+    println!("\n{}:", title);
+    for cell in missing {
+        println!("  {} {}", cell.data_type, cell.label());
+    }
+}
+
+// Prints ratio and throughput deltas for every cell present in both runs
+pub fn print(old: &RunResults, new: &RunResults, threshold_pct: f64, color: bool) {
+    let diffs = diff_cells(old, new);
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED).set_header(vec![
+        "Data",
+        "Algorithm",
+        "Level",
+        "Ratio",
+        "Comp MB/s",
+        "Decomp MB/s",
+        "Saved MB/CPU-s",
+    ]);
+    if !color {
+        table.force_no_tty();
+    }
+
+    let mut significant = 0;
+    for diff in &diffs {
+        let changes = [
+            Some(diff.ratio_pct),
+            diff.compress_pct,
+            diff.decompress_pct,
+            diff.efficiency_pct,
+        ];
+        if changes
+            .iter()
+            .flatten()
+            .any(|pct| pct.abs() > threshold_pct)
+        {
+            significant += 1;
+        }
+        table.add_row(vec![
+            Cell::new(&diff.cell.data_type),
+            Cell::new(&diff.cell.algorithm),
+            Cell::new(&diff.cell.level),
+            pct_cell(Some(diff.ratio_pct), threshold_pct, color),
+            pct_cell(diff.compress_pct, threshold_pct, color),
+            pct_cell(diff.decompress_pct, threshold_pct, color),
+            pct_cell(diff.efficiency_pct, threshold_pct, color),
+        ]);
+    }
+    for index in 3..7 {
+        if let Some(column) = table.column_mut(index) {
+            column.set_cell_alignment(CellAlignment::Right);
+        }
+    }
+
+    println!(
+        "\n=== Changes from old to new (significant beyond ±{}%) ===",
+        threshold_pct
+    );
+    println!("{}", table);
+    println!(
+        "{} of {} matched cell(s) changed significantly",
+        significant,
+        diffs.len()
+    );
+    print_unmatched("Only in old", old, new);
+    print_unmatched("Only in new", new, old);
+}
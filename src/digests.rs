@@ -0,0 +1,163 @@
+use rand::distributions::Alphanumeric;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::harness::{TestData, TEST_CASES};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// Digests recorded by one run, with what produced them
+#[derive(Serialize, Deserialize)]
+struct Recorded {
+    seed: u64,
+    size_kb: usize,
+    // Codec crate versions the outputs came from, as `crate=version,...`
+    crate_versions: String,
+    outputs: Vec<Output>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Output {
+    data_type: String,
+    configuration: String,
+    input_sha256: String,
+    output_sha256: String,
+    compressed_size: usize,
+}
+
+fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+// The benchmark's data types drawn from a seeded generator, so every run sees the same bytes
+fn seeded_input(data_type: TestData, size: usize, seed: u64) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    match data_type {
+        TestData::Random => (&mut rng).sample_iter(&Alphanumeric).take(size).collect(),
+        TestData::Repeating => b"HelloWorld".iter().copied().cycle().take(size).collect(),
+        TestData::Mixed => {
+            let mut data = Vec::with_capacity(size + 10);
+            while data.len() < size {
+                if rng.gen_bool(0.3) {
+                    data.extend_from_slice(b"HelloWorld");
+                } else {
+                    data.push(rng.sample(Alphanumeric));
+                }
+            }
+            data.truncate(size);
+            data
+        }
+    }
+}
+
+// Digests of every configuration's compressed output for fixed inputs, saved for later runs or
+// checked against a saved set: a codec whose bytes change under a dependency upgrade breaks
+// content-addressed storage even though it still round-trips
+pub fn run(
+    size_kb: usize,
+    seed: u64,
+    save: Option<&Path>,
+    check: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    let recorded: Option<Recorded> = match check {
+        Some(path) => Some(serde_json::from_str(&fs::read_to_string(path)?)?),
+        None => None,
+    };
+    // A check regenerates exactly the inputs that were recorded
+    let (size_kb, seed) = recorded.as_ref().map_or((size_kb, seed), |recorded| {
+        (recorded.size_kb, recorded.seed)
+    });
+    println!(
+        "\nCompressed output digests ({}KB inputs, seed {})\n",
+        size_kb, seed
+    );
+    println!(
+        "{:<10} {:<18} {:>12} {:<18} Status",
+        "Data type", "Configuration", "Compressed", "SHA-256"
+    );
+    println!("{}", "-".repeat(76));
+
+    let mut outputs = Vec::new();
+    let mut changed = 0;
+    let mut unstable = 0;
+    for (name, data_type) in TEST_CASES {
+        let input = seeded_input(data_type, size_kb * 1024, seed);
+        let input_sha256 = sha256(&input);
+        for codec in backends::configurations() {
+            let configuration = format!("{} {}", codec.name(), codec.level());
+            let compressed = codec.compress(&input)?;
+            let output_sha256 = sha256(&compressed);
+            let previous = recorded.as_ref().and_then(|recorded| {
+                recorded
+                    .outputs
+                    .iter()
+                    .find(|o| o.data_type == name && o.configuration == configuration)
+            });
+            // Compressing twice catches output that varies within one build, e.g. with threads
+            let status = if codec.compress(&input)? != compressed {
+                unstable += 1;
+                "UNSTABLE within run"
+            } else {
+                match previous {
+                    None if recorded.is_some() => "new",
+                    None => "",
+                    Some(previous) if previous.input_sha256 != input_sha256 => {
+                        "input differs, not compared"
+                    }
+                    Some(previous) if previous.output_sha256 != output_sha256 => {
+                        changed += 1;
+                        "CHANGED"
+                    }
+                    Some(_) => "same",
+                }
+            };
+            println!(
+                "{:<10} {:<18} {:>12} {:<18} {}",
+                name,
+                configuration,
+                compressed.len(),
+                &output_sha256[..16],
+                status
+            );
+            outputs.push(Output {
+                data_type: name.to_string(),
+                configuration,
+                input_sha256: input_sha256.clone(),
+                output_sha256,
+                compressed_size: compressed.len(),
+            });
+        }
+    }
+
+    let current = Recorded {
+        seed,
+        size_kb,
+        crate_versions: env!("CODEC_CRATE_VERSIONS").to_string(),
+        outputs,
+    };
+    if let Some(path) = save {
+        fs::write(path, serde_json::to_string_pretty(&current)?)?;
+        println!(
+            "\nSaved {} digests to {}",
+            current.outputs.len(),
+            path.display()
+        );
+    }
+    if let Some(recorded) = &recorded {
+        println!(
+            "\nRecorded with {}\nNow built with {}",
+            recorded.crate_versions, current.crate_versions
+        );
+    }
+    if changed + unstable > 0 {
+        return Err(format!(
+            "{} outputs changed from the recorded digests, {} vary between identical calls",
+            changed, unstable
+        )
+        .into());
+    }
+    Ok(())
+}
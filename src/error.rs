@@ -0,0 +1,42 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+// Everything that can go wrong in a run, with enough context to say which cell or file failed
+#[derive(Debug, Error)]
+pub enum CompressionBenchError {
+    #[error("{backend} {level}: compression failed: {source}")]
+    Compress {
+        backend: &'static str,
+        level: String,
+        source: io::Error,
+    },
+    #[error("{backend} {level}: decompression failed: {source}")]
+    Decompress {
+        backend: &'static str,
+        level: String,
+        source: io::Error,
+    },
+    #[error("{backend} {level}: round trip changed the data ({original} bytes in, {restored} bytes out)")]
+    RoundTrip {
+        backend: &'static str,
+        level: String,
+        original: usize,
+        restored: usize,
+    },
+    #[error("invalid benchmark configuration: {0}")]
+    Config(String),
+    #[error("noise calibration failed: {0}")]
+    Calibration(String),
+    #[error("{}: not a usable codec plugin: {message}", path.display())]
+    Plugin { path: PathBuf, message: String },
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{}: not a valid results file: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, CompressionBenchError>;
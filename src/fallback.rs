@@ -0,0 +1,224 @@
+use crate::{generate_test_data, TestData};
+use lz4_flex::frame::FrameEncoder;
+use rust_compress_comparison::CompressionBenchError;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MESSAGE_SIZE: usize = 64 * 1024; // 64KB
+const MESSAGE_COUNT: usize = 2_000;
+const ZSTD_LEVEL: i32 = 3;
+// Input fed to an encoder between deadline checks
+const CHECK_CHUNK: usize = 8 * 1024;
+
+#[derive(Clone, Copy)]
+enum Tier {
+    Zstd,
+    Lz4,
+    Store,
+}
+
+// zstd while it stays inside the budget, then lz4, then the raw bytes
+struct FallbackCodec {
+    budget: Duration,
+}
+
+// Feeds `data` in chunks, giving up once `deadline` has passed
+fn write_until<W: Write>(encoder: &mut W, data: &[u8], deadline: Instant) -> io::Result<bool> {
+    for chunk in data.chunks(CHECK_CHUNK) {
+        if Instant::now() > deadline {
+            return Ok(false);
+        }
+        encoder.write_all(chunk)?;
+    }
+    Ok(true)
+}
+
+impl FallbackCodec {
+    fn new(budget: Duration) -> Self {
+        FallbackCodec { budget }
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<(Tier, Vec<u8>)> {
+        let start = Instant::now();
+
+        let mut zstd = zstd::Encoder::new(Vec::new(), ZSTD_LEVEL)?;
+        if write_until(&mut zstd, data, start + self.budget)? {
+            let compressed = zstd.finish()?;
+            if start.elapsed() <= self.budget {
+                return Ok((Tier::Zstd, compressed));
+            }
+        }
+
+        // lz4 gets whatever is left of a second budget before we stop trying
+        let mut lz4 = FrameEncoder::new(Vec::new());
+        if write_until(&mut lz4, data, start + self.budget * 2)? {
+            let compressed = lz4.finish()?;
+            if compressed.len() < data.len() {
+                return Ok((Tier::Lz4, compressed));
+            }
+        }
+
+        Ok((Tier::Store, data.to_vec()))
+    }
+}
+
+type SingleCodec = (&'static str, fn(&[u8]) -> io::Result<Vec<u8>>);
+
+const SINGLE_CODECS: [SingleCodec; 3] = [
+    ("zstd", |data| zstd::bulk::compress(data, ZSTD_LEVEL)),
+    ("lz4", |data| {
+        let mut encoder = FrameEncoder::new(Vec::new());
+        encoder.write_all(data)?;
+        Ok(encoder.finish()?)
+    }),
+    ("store", |data| Ok(data.to_vec())),
+];
+
+struct LatencyReport {
+    latencies: Vec<f64>,
+    compressed: usize,
+    tiers: [usize; 3],
+}
+
+impl LatencyReport {
+    fn percentile(&self, p: f64) -> f64 {
+        let index = ((self.latencies.len() - 1) as f64 * p).round() as usize;
+        self.latencies[index]
+    }
+}
+
+// Latencies of `compress` over every message; a failure is labelled with the cell's codec
+// and busy-thread count
+fn measure(
+    name: &'static str,
+    pressure: usize,
+    messages: &[&[u8]],
+    mut compress: impl FnMut(&[u8]) -> io::Result<(Option<Tier>, Vec<u8>)>,
+) -> Result<LatencyReport, CompressionBenchError> {
+    let mut report = LatencyReport {
+        latencies: Vec::with_capacity(messages.len()),
+        compressed: 0,
+        tiers: [0; 3],
+    };
+    for message in messages {
+        let start = Instant::now();
+        let (tier, compressed) =
+            compress(message).map_err(|source| CompressionBenchError::Compress {
+                backend: name,
+                level: format!("({} busy threads)", pressure),
+                source,
+            })?;
+        report.latencies.push(start.elapsed().as_secs_f64());
+        report.compressed += compressed.len();
+        if let Some(tier) = tier {
+            report.tiers[tier as usize] += 1;
+        }
+    }
+    report.latencies.sort_by(f64::total_cmp);
+    Ok(report)
+}
+
+// Busy threads competing with the benchmark for cores until dropped
+struct CpuPressure {
+    stop: Arc<AtomicBool>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl CpuPressure {
+    fn start(threads: usize) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let workers = (0..threads)
+            .map(|_| {
+                let stop = Arc::clone(&stop);
+                thread::spawn(move || {
+                    let mut x = 0u64;
+                    while !stop.load(Ordering::Relaxed) {
+                        x = std::hint::black_box(
+                            x.wrapping_mul(6364136223846793005).wrapping_add(1),
+                        );
+                    }
+                })
+            })
+            .collect();
+        CpuPressure { stop, workers }
+    }
+}
+
+impl Drop for CpuPressure {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for worker in self.workers.drain(..) {
+            worker.join().unwrap();
+        }
+    }
+}
+
+fn print_report(name: &str, report: Result<LatencyReport, CompressionBenchError>, total: usize) {
+    let report = match report {
+        Ok(report) => report,
+        Err(error) => {
+            println!("FAILED {}: {}", name, error);
+            return;
+        }
+    };
+    let mix = if report.tiers.iter().sum::<usize>() > 0 {
+        let share =
+            |tier: Tier| report.tiers[tier as usize] as f64 / report.latencies.len() as f64 * 100.0;
+        format!(
+            "{:.0}/{:.0}/{:.0}",
+            share(Tier::Zstd),
+            share(Tier::Lz4),
+            share(Tier::Store)
+        )
+    } else {
+        "-".to_string()
+    };
+    println!(
+        "{:<10} {:>7.2}x {:>9.1} {:>9.1} {:>10.1} {:>9.1} {:>16}",
+        name,
+        total as f64 / report.compressed as f64,
+        report.percentile(0.5) * 1e6,
+        report.percentile(0.99) * 1e6,
+        report.percentile(0.999) * 1e6,
+        report.latencies.last().unwrap() * 1e6,
+        mix
+    );
+}
+
+pub fn run(budget_us: u64, pressure_threads: usize) {
+    let data = generate_test_data(&TestData::Mixed, MESSAGE_SIZE * MESSAGE_COUNT);
+    let messages: Vec<&[u8]> = data.chunks(MESSAGE_SIZE).collect();
+    let codec = FallbackCodec::new(Duration::from_micros(budget_us));
+
+    for pressure in [0, pressure_threads] {
+        println!(
+            "\nFallback chain: zstd -{} within {}us -> lz4 -> store ({} x {}KB Mixed messages, {} busy threads)\n",
+            ZSTD_LEVEL,
+            budget_us,
+            MESSAGE_COUNT,
+            MESSAGE_SIZE / 1024,
+            pressure
+        );
+        println!(
+            "{:<10} {:>8} {:>9} {:>9} {:>10} {:>9} {:>16}",
+            "Codec", "Ratio", "p50 (us)", "p99 (us)", "p99.9 (us)", "Max (us)", "zstd/lz4/store %"
+        );
+        println!("{}", "-".repeat(77));
+
+        let _pressure = CpuPressure::start(pressure);
+        for (name, compress) in &SINGLE_CODECS {
+            let report = measure(name, pressure, &messages, |message| {
+                Ok((None, compress(message)?))
+            });
+            print_report(name, report, data.len());
+        }
+        let report = measure("fallback", pressure, &messages, |message| {
+            let (tier, compressed) = codec.compress(message)?;
+            Ok((Some(tier), compressed))
+        });
+        print_report("fallback", report, data.len());
+    }
+}
@@ -0,0 +1,70 @@
+// C ABI for running a suite from non-Rust harnesses; the header is include/compress_comparison.h
+use crate::config::SuiteConfig;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// The run succeeded; `out_json` holds the `RunResults` JSON.
+pub const CCB_OK: c_int = 0;
+/// `config_json` or `out_json` was null, or the config was not valid UTF-8.
+pub const CCB_INVALID_ARGUMENT: c_int = 1;
+/// The config was rejected or the run failed; `out_json` holds `{"error": "..."}`.
+pub const CCB_RUN_FAILED: c_int = 2;
+
+fn run(config_json: &str) -> Result<String, String> {
+    let results = SuiteConfig::from_json(config_json)
+        .and_then(|config| config.builder())
+        .and_then(|builder| builder.run())
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&results).map_err(|e| e.to_string())
+}
+
+fn into_c_string(text: String) -> *mut c_char {
+    // JSON escapes control characters, so the only possible NUL would be a bug upstream
+    CString::new(text)
+        .expect("JSON output contains no NUL bytes")
+        .into_raw()
+}
+
+/// Runs the suite described by `config_json` (see `SuiteConfig`, `"{}"` for the defaults) and
+/// stores the `RunResults` JSON in `*out_json`. Whatever is stored in `*out_json` must be
+/// released with `ccb_free_string`.
+///
+/// # Safety
+///
+/// `config_json` must be a NUL-terminated string and `out_json` a valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn ccb_run(config_json: *const c_char, out_json: *mut *mut c_char) -> c_int {
+    if config_json.is_null() || out_json.is_null() {
+        return CCB_INVALID_ARGUMENT;
+    }
+    *out_json = ptr::null_mut();
+    let Ok(config) = CStr::from_ptr(config_json).to_str() else {
+        return CCB_INVALID_ARGUMENT;
+    };
+    // Unwinding across the C boundary is undefined behaviour, so panics become errors too
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| run(config)))
+        .unwrap_or_else(|_| Err("benchmark panicked".to_string()));
+    match outcome {
+        Ok(json) => {
+            *out_json = into_c_string(json);
+            CCB_OK
+        }
+        Err(message) => {
+            *out_json = into_c_string(serde_json::json!({ "error": message }).to_string());
+            CCB_RUN_FAILED
+        }
+    }
+}
+
+/// Releases a string returned through `ccb_run`; null is ignored.
+///
+/// # Safety
+///
+/// `json` must be null or a pointer from `ccb_run` that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn ccb_free_string(json: *mut c_char) {
+    if !json.is_null() {
+        drop(CString::from_raw(json));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::records::generate_records;
+use crate::results::percentile;
+use crate::timer::{ClockChoice, Timer};
+#[cfg(feature = "gzip")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "gzip")]
+use flate2::write::GzEncoder;
+#[cfg(feature = "gzip")]
+use flate2::Compression;
+#[cfg(feature = "lz4-flex")]
+use lz4_flex::frame::{FrameDecoder, FrameEncoder};
+use std::error::Error;
+use std::io::{self, Read, Write};
+
+pub const DEFAULT_EVERY: [usize; 4] = [1, 4, 16, 64];
+#[cfg(feature = "lz4")]
+const LZ4_LEVEL: u32 = 4;
+#[cfg(feature = "zstd")]
+const ZSTD_LEVEL: i32 = 3;
+// Flush points whose output prefix is decoded to check it holds every message written so far
+const VERIFIED_FLUSHES: usize = 32;
+
+// A streaming encoder whose output so far can be inspected between flushes
+pub trait FlushingEncoder: Write {
+    fn written(&self) -> &[u8];
+    fn finish(self: Box<Self>) -> io::Result<Vec<u8>>;
+}
+
+#[cfg(feature = "gzip")]
+impl FlushingEncoder for GzEncoder<Vec<u8>> {
+    fn written(&self) -> &[u8] {
+        self.get_ref()
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<Vec<u8>> {
+        GzEncoder::finish(*self)
+    }
+}
+
+#[cfg(feature = "lz4")]
+impl FlushingEncoder for lz4::Encoder<Vec<u8>> {
+    fn written(&self) -> &[u8] {
+        self.writer()
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<Vec<u8>> {
+        let (output, result) = lz4::Encoder::finish(*self);
+        result.map(|()| output)
+    }
+}
+
+#[cfg(feature = "lz4-flex")]
+impl FlushingEncoder for FrameEncoder<Vec<u8>> {
+    fn written(&self) -> &[u8] {
+        self.get_ref()
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<Vec<u8>> {
+        FrameEncoder::finish(*self).map_err(io::Error::other)
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl FlushingEncoder for zstd::stream::write::Encoder<'static, Vec<u8>> {
+    fn written(&self) -> &[u8] {
+        self.get_ref()
+    }
+
+    fn finish(self: Box<Self>) -> io::Result<Vec<u8>> {
+        zstd::stream::write::Encoder::finish(*self)
+    }
+}
+
+pub struct Backend {
+    pub name: &'static str,
+    pub encoder: fn() -> io::Result<Box<dyn FlushingEncoder>>,
+    pub decoder: fn(&[u8]) -> io::Result<Box<dyn Read + '_>>,
+}
+
+// One per streaming backend built in
+pub const BACKENDS: &[Backend] = &[
+    #[cfg(feature = "gzip")]
+    Backend {
+        name: "Gzip (sync flush)",
+        encoder: || Ok(Box::new(GzEncoder::new(Vec::new(), Compression::default()))),
+        decoder: |data| Ok(Box::new(GzDecoder::new(data))),
+    },
+    #[cfg(feature = "lz4")]
+    Backend {
+        name: "LZ4-RS",
+        encoder: || {
+            Ok(Box::new(
+                lz4::EncoderBuilder::new()
+                    .level(LZ4_LEVEL)
+                    .build(Vec::new())?,
+            ))
+        },
+        decoder: |data| Ok(Box::new(lz4::Decoder::new(data)?)),
+    },
+    #[cfg(feature = "lz4-flex")]
+    Backend {
+        name: "LZ4-Flex frame",
+        encoder: || Ok(Box::new(FrameEncoder::new(Vec::new()))),
+        decoder: |data| Ok(Box::new(FrameDecoder::new(data))),
+    },
+    #[cfg(feature = "zstd")]
+    Backend {
+        name: "Zstd",
+        encoder: || {
+            Ok(Box::new(zstd::stream::write::Encoder::new(
+                Vec::new(),
+                ZSTD_LEVEL,
+            )?))
+        },
+        decoder: |data| Ok(Box::new(zstd::stream::read::Decoder::new(data)?)),
+    },
+];
+
+// Bytes a decoder gets out of a stream cut off after a flush, before it runs out of input
+fn decodable_len(backend: &Backend, prefix: &[u8]) -> io::Result<usize> {
+    let mut decoder = (backend.decoder)(prefix)?;
+    let mut buffer = [0u8; 16 * 1024];
+    let mut total = 0;
+    loop {
+        match decoder.read(&mut buffer) {
+            Ok(0) | Err(_) => return Ok(total),
+            Ok(n) => total += n,
+        }
+    }
+}
+
+struct FlushRun {
+    // Per-flush latencies of the write that completes a group plus its flush, sorted
+    latencies: Vec<f64>,
+    compressed: usize,
+}
+
+// Streams `messages`, flushing after every `every`-th, and checks flushed prefixes decode fully
+fn stream(
+    timer: &Timer,
+    backend: &Backend,
+    messages: &[Vec<u8>],
+    every: usize,
+) -> Result<FlushRun, Box<dyn Error>> {
+    let mut encoder = (backend.encoder)()?;
+    let mut latencies = Vec::with_capacity(messages.len() / every + 1);
+    let mut checkpoints = Vec::new();
+    let mut written = 0;
+    for (i, message) in messages.iter().enumerate() {
+        written += message.len();
+        if (i + 1) % every != 0 {
+            encoder.write_all(message)?;
+            continue;
+        }
+        let (result, secs) = timer.time(|| {
+            encoder.write_all(message)?;
+            encoder.flush()
+        });
+        result?;
+        latencies.push(timer.corrected(secs));
+        if checkpoints.len() < VERIFIED_FLUSHES {
+            checkpoints.push((encoder.written().len(), written));
+        }
+    }
+    let compressed = encoder.finish()?;
+    for (prefix, expected) in checkpoints {
+        let decoded = decodable_len(backend, &compressed[..prefix])?;
+        if decoded != expected {
+            return Err(format!(
+                "{}: flushed output decodes to {} of {} bytes written",
+                backend.name, decoded, expected
+            )
+            .into());
+        }
+    }
+    latencies.sort_by(f64::total_cmp);
+    Ok(FlushRun {
+        latencies,
+        compressed: compressed.len(),
+    })
+}
+
+// Latency of producing decodable output after a flush, and what flushing that often costs in ratio
+pub fn run(every: &[usize], message_count: usize) -> Result<(), Box<dyn Error>> {
+    let timer = Timer::calibrate(ClockChoice::Auto)?;
+    let messages = generate_records(message_count);
+    let original: usize = messages.iter().map(Vec::len).sum();
+    println!(
+        "\nFlush latency ({} JSON records averaging {} bytes, {}; overhead {:.1} ns subtracted)",
+        message_count,
+        original / message_count,
+        timer.name(),
+        timer.overhead_secs() * 1e9
+    );
+
+    for backend in BACKENDS {
+        // Flushing once at the very end is the same stream as never flushing
+        let unflushed = stream(&timer, backend, &messages, message_count)?.compressed;
+        println!(
+            "\n=== {} (unflushed ratio {:.2}x) ===\n",
+            backend.name,
+            original as f64 / unflushed as f64
+        );
+        println!(
+            "{:>11} {:>10} {:>10} {:>10} {:>8} {:>14}",
+            "Flush every", "p50 us", "p99 us", "max us", "Ratio", "Ratio penalty"
+        );
+        println!("{}", "-".repeat(68));
+        for &every in every {
+            let flushed = stream(&timer, backend, &messages, every.min(message_count))?;
+            let us = |p: f64| percentile(&flushed.latencies, p) * 1e6;
+            println!(
+                "{:>11} {:>10.2} {:>10.2} {:>10.2} {:>7.2}x {:>13.1}%",
+                every,
+                us(0.5),
+                us(0.99),
+                flushed.latencies[flushed.latencies.len() - 1] * 1e6,
+                original as f64 / flushed.compressed as f64,
+                (flushed.compressed as f64 / unflushed as f64 - 1.0) * 100.0
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use rust_compress_comparison::backends;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+// A throwaway binary that compresses stdin with one backend, so its size and build time are the
+// backend's cost on top of the std-only baseline
+struct Probe {
+    id: &'static str,
+    // Cargo.toml dependency lines, matching this crate's own versions
+    dependencies: &'static str,
+    main: &'static str,
+}
+
+const BASELINE: Probe = Probe {
+    id: "baseline",
+    dependencies: "",
+    main: r#"use std::io::{Read, Write};
+
+fn main() {
+    let mut data = Vec::new();
+    std::io::stdin().read_to_end(&mut data).unwrap();
+    std::io::stdout().write_all(&data).unwrap();
+}
+"#,
+};
+
+const PROBES: [Probe; 4] = [
+    Probe {
+        id: "gzip",
+        dependencies: r#"flate2 = "1.0""#,
+        main: r#"use std::io::{Read, Write};
+
+fn main() {
+    let mut data = Vec::new();
+    std::io::stdin().read_to_end(&mut data).unwrap();
+    let mut encoder = flate2::write::GzEncoder::new(std::io::stdout(), flate2::Compression::default());
+    encoder.write_all(&data).unwrap();
+    encoder.finish().unwrap();
+}
+"#,
+    },
+    Probe {
+        id: "lz4-flex",
+        dependencies: r#"lz4_flex = "0.11""#,
+        main: r#"use std::io::{Read, Write};
+
+fn main() {
+    let mut data = Vec::new();
+    std::io::stdin().read_to_end(&mut data).unwrap();
+    std::io::stdout().write_all(&lz4_flex::compress_prepend_size(&data)).unwrap();
+}
+"#,
+    },
+    Probe {
+        id: "lz4-rs",
+        dependencies: r#"lz4 = "1.24""#,
+        main: r#"use std::io::{Read, Write};
+
+fn main() {
+    let mut data = Vec::new();
+    std::io::stdin().read_to_end(&mut data).unwrap();
+    let mut encoder = lz4::EncoderBuilder::new().build(std::io::stdout()).unwrap();
+    encoder.write_all(&data).unwrap();
+    encoder.finish().1.unwrap();
+}
+"#,
+    },
+    Probe {
+        id: "zstd",
+        dependencies: r#"zstd = "0.13""#,
+        main: r#"use std::io::{Read, Write};
+
+fn main() {
+    let mut data = Vec::new();
+    std::io::stdin().read_to_end(&mut data).unwrap();
+    std::io::stdout().write_all(&zstd::bulk::compress(&data, 3).unwrap()).unwrap();
+}
+"#,
+    },
+];
+
+struct Footprint {
+    id: &'static str,
+    binary_bytes: u64,
+    build_secs: f64,
+}
+
+// Writes a standalone binary crate into `crate_dir` and builds it in release mode from a clean
+// target directory, returning the binary and how long the build took
+pub fn build_crate(
+    crate_dir: &Path,
+    name: &str,
+    dependencies: &str,
+    main: &str,
+    offline: bool,
+) -> Result<(PathBuf, f64), Box<dyn Error>> {
+    fs::create_dir_all(crate_dir.join("src"))?;
+    // An empty [workspace] keeps cargo from adopting the probe into an enclosing workspace
+    fs::write(
+        crate_dir.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"{}\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n[dependencies]\n{}\n\n\
+             [profile.release]\nstrip = true\n\n[workspace]\n",
+            name, dependencies
+        ),
+    )?;
+    fs::write(crate_dir.join("src/main.rs"), main)?;
+    let target = crate_dir.join("target");
+    if target.exists() {
+        fs::remove_dir_all(&target)?;
+    }
+
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut command = Command::new(cargo);
+    command
+        .args(["build", "--release", "--quiet", "--manifest-path"])
+        .arg(crate_dir.join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", &target);
+    if offline {
+        command.arg("--offline");
+    }
+    let start = Instant::now();
+    let status = command.status()?;
+    let build_secs = start.elapsed().as_secs_f64();
+    if !status.success() {
+        return Err(format!("building {} failed ({})", name, status).into());
+    }
+    let binary = target
+        .join("release")
+        .join(format!("{}{}", name, std::env::consts::EXE_SUFFIX));
+    Ok((binary, build_secs))
+}
+
+fn build(probe: &Probe, dir: &Path, offline: bool) -> Result<Footprint, Box<dyn Error>> {
+    let (binary, build_secs) = build_crate(
+        &dir.join(probe.id),
+        &format!("probe-{}", probe.id),
+        probe.dependencies,
+        probe.main,
+        offline,
+    )?;
+    Ok(Footprint {
+        id: probe.id,
+        binary_bytes: fs::metadata(&binary)?.len(),
+        build_secs,
+    })
+}
+
+// Release-build size and clean compile time each compiled-in backend adds to a minimal binary
+pub fn run(dir: &Path, offline: bool) -> Result<(), Box<dyn Error>> {
+    let probes: Vec<&Probe> = PROBES
+        .iter()
+        .filter(|probe| {
+            backends::REGISTRY
+                .iter()
+                .any(|backend| backend.id == probe.id)
+        })
+        .collect();
+    println!(
+        "\nBuilding {} probe binaries in {} (clean release builds, stripped)...",
+        probes.len() + 1,
+        dir.display()
+    );
+    let baseline = build(&BASELINE, dir, offline)?;
+
+    println!("\n=== Backend Footprint ===\n");
+    println!(
+        "{:<10} {:>12} {:>12} {:>11} {:>12}",
+        "Backend", "Binary KB", "Added KB", "Build s", "Added s"
+    );
+    println!("{}", "-".repeat(61));
+    println!(
+        "{:<10} {:>12.1} {:>12} {:>11.1} {:>12}",
+        baseline.id,
+        baseline.binary_bytes as f64 / 1024.0,
+        "-",
+        baseline.build_secs,
+        "-"
+    );
+    for probe in probes {
+        let footprint = build(probe, dir, offline)?;
+        println!(
+            "{:<10} {:>12.1} {:>+12.1} {:>11.1} {:>+12.1}",
+            footprint.id,
+            footprint.binary_bytes as f64 / 1024.0,
+            (footprint.binary_bytes as f64 - baseline.binary_bytes as f64) / 1024.0,
+            footprint.build_secs,
+            footprint.build_secs - baseline.build_secs
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,235 @@
+#[cfg(feature = "gzip")]
+use flate2::Compression;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+// Failures listed in full after the summary
+const LISTED_FAILURES: usize = 20;
+
+// An input shape; each takes the generator and the largest size to produce
+type Generate = fn(&mut StdRng, usize) -> Vec<u8>;
+
+const GENERATORS: &[(&str, Generate)] = &[
+    ("random", random),
+    ("zeros", zeros),
+    ("pattern", pattern),
+    ("text", text),
+    ("alternating", alternating),
+    ("boundary", boundary),
+    #[cfg(feature = "gzip")]
+    ("truncated", truncated),
+];
+
+fn random(rng: &mut StdRng, max: usize) -> Vec<u8> {
+    let len = rng.gen_range(0..=max);
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+fn zeros(rng: &mut StdRng, max: usize) -> Vec<u8> {
+    vec![0; rng.gen_range(0..=max)]
+}
+
+// A 1–16 byte unit repeated, the longest matches a codec can see
+fn pattern(rng: &mut StdRng, max: usize) -> Vec<u8> {
+    let unit: Vec<u8> = (0..rng.gen_range(1..=16)).map(|_| rng.gen()).collect();
+    unit.iter()
+        .copied()
+        .cycle()
+        .take(rng.gen_range(0..=max))
+        .collect()
+}
+
+// Words over a small alphabet, with short matches everywhere
+fn text(rng: &mut StdRng, max: usize) -> Vec<u8> {
+    let len = rng.gen_range(0..=max);
+    let mut text = Vec::with_capacity(len + 8);
+    while text.len() < len {
+        for _ in 0..rng.gen_range(1..8) {
+            text.push(b"etaoinsrhl"[rng.gen_range(0..10)]);
+        }
+        text.push(b' ');
+    }
+    text.truncate(len);
+    text
+}
+
+// Incompressible and highly compressible runs back to back, switching the encoder's mode
+fn alternating(rng: &mut StdRng, max: usize) -> Vec<u8> {
+    let len = rng.gen_range(0..=max);
+    let mut data = Vec::with_capacity(len);
+    while data.len() < len {
+        let run = rng.gen_range(1..=4096);
+        if rng.gen_bool(0.5) {
+            data.extend((0..run).map(|_| rng.gen::<u8>()));
+        } else {
+            data.extend(std::iter::repeat_n(rng.gen::<u8>(), run));
+        }
+    }
+    data.truncate(len);
+    data
+}
+
+// Random bytes one either side of a power of two, where block and buffer edges fall
+fn boundary(rng: &mut StdRng, max: usize) -> Vec<u8> {
+    let exponent = rng.gen_range(0..=max.max(2).ilog2());
+    let len = ((1usize << exponent) as isize + rng.gen_range(-1..=1)).max(0) as usize;
+    (0..len.min(max)).map(|_| rng.gen()).collect()
+}
+
+// The front of some other input's gzip stream: compressed-looking data that ends abruptly
+#[cfg(feature = "gzip")]
+fn truncated(rng: &mut StdRng, max: usize) -> Vec<u8> {
+    let source = text(rng, max);
+    let mut compressed = backends::Gzip::new(Compression::fast())
+        .compress(&source)
+        .expect("gzip compresses any input");
+    compressed.truncate(rng.gen_range(0..=compressed.len()));
+    compressed
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Mismatch,
+    Error,
+    Panic,
+}
+
+struct Failure {
+    label: String,
+    generator: &'static str,
+    iteration: usize,
+    len: usize,
+    kind: Kind,
+    detail: String,
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic".to_string())
+}
+
+// One compress→decompress round trip, with a panic in either step caught and reported
+fn round_trip(codec: &dyn Compressor, input: &[u8]) -> Result<(), (Kind, String)> {
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+        let compressed = codec
+            .compress(input)
+            .map_err(|e| format!("compress: {}", e))?;
+        codec
+            .decompress(&compressed)
+            .map_err(|e| format!("decompress: {}", e))
+    }));
+    match outcome {
+        Err(payload) => Err((Kind::Panic, panic_message(payload))),
+        Ok(Err(e)) => Err((Kind::Error, e)),
+        Ok(Ok(restored)) if restored != input => {
+            Err((Kind::Mismatch, format!("{} bytes restored", restored.len())))
+        }
+        Ok(Ok(_)) => Ok(()),
+    }
+}
+
+// Arbitrary and adversarial inputs round-tripped through every configuration
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    iterations: usize,
+    max_size_kb: usize,
+    seed: Option<u64>,
+    save_dir: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::configurations().collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let seed = seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let max = max_size_kb * 1024;
+    if let Some(dir) = save_dir {
+        fs::create_dir_all(dir)?;
+    }
+    println!(
+        "\nFuzzing {} configurations with {} inputs up to {}KB (seed {})",
+        algorithms.len(),
+        iterations,
+        max_size_kb,
+        seed
+    );
+
+    // Round trips and failures of each kind, per configuration
+    let mut counts = vec![(0usize, [0usize; 3]); algorithms.len()];
+    let mut failures = Vec::new();
+    for iteration in 0..iterations {
+        let (generator, generate) = GENERATORS[iteration % GENERATORS.len()];
+        let input = generate(&mut rng, max);
+        for (codec, (trips, kinds)) in algorithms.iter().zip(&mut counts) {
+            *trips += 1;
+            let Err((kind, detail)) = round_trip(*codec, &input) else {
+                continue;
+            };
+            kinds[kind as usize] += 1;
+            let label = format!("{} {}", codec.name(), codec.level());
+            if let Some(dir) = save_dir {
+                let name = format!("{}-{}.bin", label.replace(' ', "-"), iteration);
+                fs::write(dir.join(name), &input)?;
+            }
+            failures.push(Failure {
+                label,
+                generator,
+                iteration,
+                len: input.len(),
+                kind,
+                detail,
+            });
+        }
+    }
+
+    println!(
+        "\n{:<18} {:>11} {:>11} {:>8} {:>8}",
+        "Configuration", "Round trips", "Mismatches", "Errors", "Panics"
+    );
+    println!("{}", "-".repeat(60));
+    for (codec, (trips, kinds)) in algorithms.iter().zip(&counts) {
+        println!(
+            "{:<18} {:>11} {:>11} {:>8} {:>8}",
+            format!("{} {}", codec.name(), codec.level()),
+            trips,
+            kinds[Kind::Mismatch as usize],
+            kinds[Kind::Error as usize],
+            kinds[Kind::Panic as usize]
+        );
+    }
+    if failures.is_empty() {
+        println!("\nAll round trips restored their input");
+        return Ok(());
+    }
+    println!();
+    for failure in failures.iter().take(LISTED_FAILURES) {
+        let kind = match failure.kind {
+            Kind::Mismatch => "mismatch",
+            Kind::Error => "error",
+            Kind::Panic => "panic",
+        };
+        println!(
+            "{} on {} input #{} ({} bytes): {} ({})",
+            failure.label, failure.generator, failure.iteration, failure.len, kind, failure.detail
+        );
+    }
+    if failures.len() > LISTED_FAILURES {
+        println!("... and {} more", failures.len() - LISTED_FAILURES);
+    }
+    Err(format!(
+        "{} round trips failed; rerun with --seed {} to reproduce",
+        failures.len(),
+        seed
+    )
+    .into())
+}
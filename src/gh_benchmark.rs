@@ -0,0 +1,94 @@
+use crate::results::{Interval, RunResults};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+// Which github-action-benchmark `tool` the file is meant for
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Direction {
+    /// `customBiggerIsBetter`: ratios and MB/s
+    Bigger,
+    /// `customSmallerIsBetter`: compression and decompression times
+    Smaller,
+}
+
+// One entry of the customBiggerIsBetter / customSmallerIsBetter JSON array; `range` is the 95% CI
+#[derive(Serialize)]
+struct Entry {
+    name: String,
+    unit: &'static str,
+    value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    range: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extra: Option<String>,
+}
+
+fn entries(results: &RunResults, direction: Direction) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for cell in &results.cells {
+        let name = |metric: &str| format!("{} / {} / {}", cell.data_type, cell.label(), metric);
+        let range = |interval: Interval| Some(format!("± {:.3}", interval.half_width()));
+        let extra = Some(format!(
+            "{} bytes in, {} bytes out, data quality {}",
+            cell.original_size,
+            cell.compressed_size,
+            cell.quality.label()
+        ));
+        match direction {
+            Direction::Bigger => {
+                entries.push(Entry {
+                    name: name("ratio"),
+                    unit: "x",
+                    value: cell.ratio,
+                    range: range(cell.confidence.ratio),
+                    extra: extra.clone(),
+                });
+                entries.push(Entry {
+                    name: name("compress"),
+                    unit: "MB/s",
+                    value: cell.compress_mbps(),
+                    range: range(cell.confidence.compress_mbps),
+                    extra: None,
+                });
+                if cell.decompress_secs > 0.0 {
+                    entries.push(Entry {
+                        name: name("decompress"),
+                        unit: "MB/s",
+                        value: cell.decompress_mbps(),
+                        range: range(cell.confidence.decompress_mbps),
+                        extra: None,
+                    });
+                }
+            }
+            Direction::Smaller => {
+                entries.push(Entry {
+                    name: name("compress"),
+                    unit: "ms",
+                    value: cell.compress_secs * 1000.0,
+                    range: None,
+                    extra,
+                });
+                if cell.decompress_secs > 0.0 {
+                    entries.push(Entry {
+                        name: name("decompress"),
+                        unit: "ms",
+                        value: cell.decompress_secs * 1000.0,
+                        range: None,
+                        extra: None,
+                    });
+                }
+            }
+        }
+    }
+    entries
+}
+
+// Writes the results in the JSON schema github-action-benchmark's custom tools consume
+pub fn write(results: &RunResults, path: &Path, direction: Direction) -> io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(writer, &entries(results, direction))?;
+    Ok(())
+}
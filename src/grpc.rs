@@ -0,0 +1,245 @@
+use crate::results::percentile;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use rand::{thread_rng, Rng};
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::time::Instant;
+
+pub const DEFAULT_SIZES: [usize; 7] = [64, 256, 1024, 4096, 16 * 1024, 64 * 1024, 256 * 1024];
+// Distinct messages cycled through per bucket, so no single buffer stays hot in cache
+const DISTINCT_MESSAGES: usize = 64;
+// Length-prefixed message framing: a compressed flag byte and a big-endian u32 length
+const FRAME_HEADER: usize = 5;
+// What gRPC implementations use for zstd when no level is configured
+const ZSTD_LEVEL: i32 = 3;
+
+const NAMES: [&str; 6] = ["alpha", "bravo", "charlie", "delta", "echo", "foxtrot"];
+const TAGS: [&str; 5] = ["new", "sale", "featured", "clearance", "limited"];
+
+// A `grpc-encoding` as servers negotiate it; every message is compressed on its own
+struct Encoding {
+    name: &'static str,
+    compress: fn(&[u8]) -> io::Result<Vec<u8>>,
+    decompress: fn(&[u8]) -> io::Result<Vec<u8>>,
+}
+
+fn identity(message: &[u8]) -> io::Result<Vec<u8>> {
+    Ok(message.to_vec())
+}
+
+fn gzip(message: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(message)?;
+    encoder.finish()
+}
+
+fn gunzip(compressed: &[u8]) -> io::Result<Vec<u8>> {
+    let mut restored = Vec::new();
+    GzDecoder::new(compressed).read_to_end(&mut restored)?;
+    Ok(restored)
+}
+
+// gRPC's "deflate" is the zlib format, not raw deflate
+fn deflate(message: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(message)?;
+    encoder.finish()
+}
+
+fn inflate(compressed: &[u8]) -> io::Result<Vec<u8>> {
+    let mut restored = Vec::new();
+    ZlibDecoder::new(compressed).read_to_end(&mut restored)?;
+    Ok(restored)
+}
+
+fn zstd_compress(message: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::encode_all(message, ZSTD_LEVEL)
+}
+
+fn zstd_decompress(compressed: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::decode_all(compressed)
+}
+
+const ENCODINGS: [Encoding; 4] = [
+    Encoding {
+        name: "identity",
+        compress: identity,
+        decompress: identity,
+    },
+    Encoding {
+        name: "gzip",
+        compress: gzip,
+        decompress: gunzip,
+    },
+    Encoding {
+        name: "deflate",
+        compress: deflate,
+        decompress: inflate,
+    },
+    Encoding {
+        name: "zstd",
+        compress: zstd_compress,
+        decompress: zstd_decompress,
+    },
+];
+
+fn push_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn push_key(out: &mut Vec<u8>, field: u64, wire_type: u64) {
+    push_varint(out, field << 3 | wire_type);
+}
+
+fn push_bytes(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    push_key(out, field, 2);
+    push_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+// An `Item { uint64 id = 1; string name = 2; int64 updated_ms = 3; double price = 4;
+// repeated string tags = 5; }` with random but plausible values
+fn item(rng: &mut impl Rng, index: u64) -> Vec<u8> {
+    let mut item = Vec::new();
+    push_key(&mut item, 1, 0);
+    push_varint(&mut item, 10_000 + index);
+    let name = format!(
+        "{}-{}",
+        NAMES[rng.gen_range(0..NAMES.len())],
+        rng.gen_range(0..1000)
+    );
+    push_bytes(&mut item, 2, name.as_bytes());
+    push_key(&mut item, 3, 0);
+    push_varint(&mut item, 1_700_000_000_000 + rng.gen_range(0..86_400_000));
+    push_key(&mut item, 4, 1);
+    item.extend_from_slice(&rng.gen_range(0.5f64..500.0).to_le_bytes());
+    for _ in 0..rng.gen_range(0..3) {
+        push_bytes(&mut item, 5, TAGS[rng.gen_range(0..TAGS.len())].as_bytes());
+    }
+    item
+}
+
+// A `Response { string request_id = 1; repeated Item items = 2; }` of roughly `size` bytes
+fn message(size: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    let mut message = Vec::with_capacity(size + 64);
+    push_bytes(
+        &mut message,
+        1,
+        format!("{:032x}", rng.gen::<u128>()).as_bytes(),
+    );
+    let mut index = 0;
+    while message.len() < size {
+        let item = item(&mut rng, index);
+        push_bytes(&mut message, 2, &item);
+        index += 1;
+    }
+    message.truncate(size);
+    message
+}
+
+// The length-prefixed message as it goes on the wire
+fn frame(payload: &[u8], compressed: bool) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_HEADER + payload.len());
+    frame.push(compressed as u8);
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+struct Bucket {
+    wire_bytes: f64,
+    encode: Vec<f64>,
+    decode: Vec<f64>,
+}
+
+fn measure(
+    encoding: &Encoding,
+    messages: &[Vec<u8>],
+    calls: usize,
+) -> Result<Bucket, Box<dyn Error>> {
+    let compressed = encoding.name != "identity";
+    let mut wire_bytes = 0;
+    let mut encode = Vec::with_capacity(calls);
+    let mut decode = Vec::with_capacity(calls);
+    for call in 0..calls {
+        let message = &messages[call % messages.len()];
+        let start = Instant::now();
+        let frame = frame(&(encoding.compress)(message)?, compressed);
+        encode.push(start.elapsed().as_secs_f64());
+        wire_bytes += frame.len();
+
+        let start = Instant::now();
+        let restored = (encoding.decompress)(&frame[FRAME_HEADER..])?;
+        decode.push(start.elapsed().as_secs_f64());
+        if restored != *message {
+            return Err(format!("{} round trip mismatch", encoding.name).into());
+        }
+    }
+    encode.sort_by(f64::total_cmp);
+    decode.sort_by(f64::total_cmp);
+    Ok(Bucket {
+        wire_bytes: wire_bytes as f64 / calls as f64,
+        encode,
+        decode,
+    })
+}
+
+// Per-message gRPC compression of protobuf-encoded responses, bucketed by message size
+pub fn run(sizes: &[usize], calls: usize) -> Result<(), Box<dyn Error>> {
+    println!(
+        "\ngRPC per-message compression ({} messages per size, {}-byte frame header included)",
+        calls, FRAME_HEADER
+    );
+    for &size in sizes {
+        let messages: Vec<Vec<u8>> = (0..DISTINCT_MESSAGES.min(calls))
+            .map(|_| message(size))
+            .collect();
+        println!("\n=== {} byte messages ===\n", size);
+        println!(
+            "{:<10} {:>11} {:>7} {:>11} {:>10} {:>10} {:>10} {:>10}",
+            "Encoding",
+            "Wire bytes",
+            "Ratio",
+            "Saved/msg",
+            "Enc p50",
+            "Enc p99",
+            "Dec p50",
+            "Dec p99"
+        );
+        println!("{}", "-".repeat(86));
+        let mut identity_bytes = None;
+        let mut smallest: Option<(&str, f64)> = None;
+        for encoding in &ENCODINGS {
+            let bucket = measure(encoding, &messages, calls)?;
+            let identity = *identity_bytes.get_or_insert(bucket.wire_bytes);
+            println!(
+                "{:<10} {:>11.1} {:>6.2}x {:>11.1} {:>8.1}us {:>8.1}us {:>8.1}us {:>8.1}us",
+                encoding.name,
+                bucket.wire_bytes,
+                identity / bucket.wire_bytes,
+                identity - bucket.wire_bytes,
+                percentile(&bucket.encode, 0.5) * 1e6,
+                percentile(&bucket.encode, 0.99) * 1e6,
+                percentile(&bucket.decode, 0.5) * 1e6,
+                percentile(&bucket.decode, 0.99) * 1e6
+            );
+            if smallest.is_none_or(|(_, bytes)| bucket.wire_bytes < bytes) {
+                smallest = Some((encoding.name, bucket.wire_bytes));
+            }
+        }
+        if let Some((name, _)) = smallest {
+            println!("\nSmallest on the wire: {}", name);
+        }
+    }
+    println!(
+        "\nSaved/msg is wire bytes saved per message against identity; latencies include framing"
+    );
+    Ok(())
+}
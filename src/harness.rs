@@ -0,0 +1,187 @@
+// Test data and the timed, verified round trip shared by the CLI and the criterion benches,
+// so both measure the same inputs the same way
+use crate::cache::CacheState;
+use crate::counters::{self, CounterValues};
+use crate::error::{CompressionBenchError, Result};
+use crate::memory::{self, AllocationCount};
+use crate::{Compressor, Instant};
+use clap::ValueEnum;
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use serde::Deserialize;
+use std::io;
+
+// Different types of test data
+#[derive(Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestData {
+    Random,
+    Repeating,
+    Mixed,
+}
+
+impl TestData {
+    // Name used for the data type in reports
+    pub fn name(self) -> &'static str {
+        match self {
+            TestData::Random => "Random",
+            TestData::Repeating => "Repeating",
+            TestData::Mixed => "Mixed",
+        }
+    }
+}
+
+// Data types every full run covers, with their report names
+pub const TEST_CASES: [(&str, TestData); 3] = [
+    ("Random", TestData::Random),
+    ("Repeating", TestData::Repeating),
+    ("Mixed", TestData::Mixed),
+];
+
+pub fn generate_test_data(data_type: &TestData, size: usize) -> Vec<u8> {
+    match data_type {
+        TestData::Random => thread_rng().sample_iter(&Alphanumeric).take(size).collect(),
+
+        TestData::Repeating => {
+            let pattern = b"HelloWorld";
+            let mut data = Vec::with_capacity(size);
+            while data.len() < size {
+                data.extend_from_slice(pattern);
+            }
+            data.truncate(size);
+            data
+        }
+
+        TestData::Mixed => {
+            let mut data = Vec::with_capacity(size);
+            let mut rng = thread_rng();
+
+            while data.len() < size {
+                if rng.gen_bool(0.3) {
+                    data.extend_from_slice(b"HelloWorld");
+                } else {
+                    data.push(rng.sample(Alphanumeric) as u8);
+                }
+            }
+            data.truncate(size);
+            data
+        }
+    }
+}
+
+// Runs one codec call, returning its output and the wall-clock seconds it took
+pub fn try_timed(codec_call: impl FnOnce() -> io::Result<Vec<u8>>) -> io::Result<(Vec<u8>, f64)> {
+    let start = Instant::now();
+    let output = codec_call()?;
+    Ok((output, start.elapsed().as_secs_f64()))
+}
+
+// Sizes and timings of one verified compress/decompress round trip
+pub struct RoundTrip {
+    pub compressed_size: usize,
+    pub compress_secs: f64,
+    pub decompress_secs: f64,
+    // Resident memory each step added at its peak; read outside the timed calls
+    pub compress_peak_rss: Option<u64>,
+    pub decompress_peak_rss: Option<u64>,
+    pub compress_allocations: Option<AllocationCount>,
+    pub decompress_allocations: Option<AllocationCount>,
+    pub compress_counters: Option<CounterValues>,
+    pub decompress_counters: Option<CounterValues>,
+}
+
+pub fn compress(codec: &dyn Compressor, data: &[u8]) -> Result<Vec<u8>> {
+    codec
+        .compress(data)
+        .map_err(|source| compress_error(codec, source))
+}
+
+// Decompresses `compressed` and checks it restores `original`
+pub fn verify(codec: &dyn Compressor, original: &[u8], compressed: &[u8]) -> Result<()> {
+    let restored = codec
+        .decompress(compressed)
+        .map_err(|source| decompress_error(codec, source))?;
+    check_restored(codec, original, &restored)
+}
+
+// Times compression and decompression of `data`; with `verify`, also checks the data survived
+pub fn round_trip(codec: &dyn Compressor, data: &[u8], verify: bool) -> Result<RoundTrip> {
+    round_trip_with(codec, data, verify, CacheState::Warm)
+}
+
+// `round_trip`, with each step's input evicted from the CPU caches first when `cache` is cold
+pub fn round_trip_with(
+    codec: &dyn Compressor,
+    data: &[u8],
+    verify: bool,
+    cache: CacheState,
+) -> Result<RoundTrip> {
+    let baseline = memory::reset_peak();
+    let allocations = memory::allocation_count();
+    cache.prepare(data);
+    let counters = counters::read();
+    let (compressed, compress_secs) =
+        try_timed(|| codec.compress(data)).map_err(|source| compress_error(codec, source))?;
+    let compress_counters = counters_since(counters);
+    let compress_allocations = allocations_since(allocations);
+    let compress_peak_rss = memory::peak_since(baseline);
+    let baseline = memory::reset_peak();
+    let allocations = memory::allocation_count();
+    cache.prepare(&compressed);
+    let counters = counters::read();
+    let (restored, decompress_secs) = try_timed(|| codec.decompress(&compressed))
+        .map_err(|source| decompress_error(codec, source))?;
+    let decompress_counters = counters_since(counters);
+    let decompress_allocations = allocations_since(allocations);
+    let decompress_peak_rss = memory::peak_since(baseline);
+    if verify {
+        check_restored(codec, data, &restored)?;
+    }
+    Ok(RoundTrip {
+        compressed_size: compressed.len(),
+        compress_secs,
+        decompress_secs,
+        compress_peak_rss,
+        decompress_peak_rss,
+        compress_allocations,
+        decompress_allocations,
+        compress_counters,
+        decompress_counters,
+    })
+}
+
+fn allocations_since(earlier: Option<AllocationCount>) -> Option<AllocationCount> {
+    Some(memory::allocation_count()?.since(earlier?))
+}
+
+fn counters_since(earlier: Option<CounterValues>) -> Option<CounterValues> {
+    Some(counters::read()?.since(earlier?))
+}
+
+pub fn compress_error(codec: &dyn Compressor, source: io::Error) -> CompressionBenchError {
+    CompressionBenchError::Compress {
+        backend: codec.name(),
+        level: codec.level(),
+        source,
+    }
+}
+
+fn decompress_error(codec: &dyn Compressor, source: io::Error) -> CompressionBenchError {
+    CompressionBenchError::Decompress {
+        backend: codec.name(),
+        level: codec.level(),
+        source,
+    }
+}
+
+fn check_restored(codec: &dyn Compressor, original: &[u8], restored: &[u8]) -> Result<()> {
+    if restored == original {
+        Ok(())
+    } else {
+        Err(CompressionBenchError::RoundTrip {
+            backend: codec.name(),
+            level: codec.level(),
+            original: original.len(),
+            restored: restored.len(),
+        })
+    }
+}
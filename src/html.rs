@@ -0,0 +1,408 @@
+use crate::pareto::{self, ParetoPoint};
+use crate::results::{CellResult, RunResults};
+use crate::table;
+use std::fmt::Write;
+
+const PALETTE: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#9c755f",
+];
+
+const CHART_WIDTH: f64 = 640.0;
+const CHART_HEIGHT: f64 = 360.0;
+const MARGIN: f64 = 50.0;
+
+// Sorts a table by the clicked column, toggling direction on repeated clicks
+const SORT_SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable th").forEach(function (th, col) {
+  th.addEventListener("click", function () {
+    var tbody = th.closest("table").tBodies[0];
+    var asc = th.dataset.dir !== "asc";
+    th.closest("tr").querySelectorAll("th").forEach(function (h) { delete h.dataset.dir; });
+    th.dataset.dir = asc ? "asc" : "desc";
+    Array.from(tbody.rows).sort(function (a, b) {
+      var x = a.cells[col].dataset.value || a.cells[col].textContent;
+      var y = b.cells[col].dataset.value || b.cells[col].textContent;
+      var nx = parseFloat(x), ny = parseFloat(y);
+      var cmp = isNaN(nx) || isNaN(ny) ? x.localeCompare(y) : nx - ny;
+      return asc ? cmp : -cmp;
+    }).forEach(function (row) { tbody.appendChild(row); });
+  });
+});
+"#;
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+h1, h2 { font-weight: 600; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { padding: 4px 10px; border-bottom: 1px solid #ddd; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+th { cursor: pointer; background: #f4f4f4; }
+th[data-dir="asc"]::after { content: " \25B2"; }
+th[data-dir="desc"]::after { content: " \25BC"; }
+svg { background: #fafafa; border: 1px solid #eee; margin: 0.5em 1em 1em 0; }
+.meta { color: #555; }
+"#;
+
+// Renders a self-contained HTML page with charts and sortable tables
+pub fn render(results: &RunResults) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Compression Benchmark Report</title>\n");
+    writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE).unwrap();
+    html.push_str("<h1>Compression Benchmark Report</h1>\n");
+
+    render_metadata(&mut html, results);
+
+    let algorithms = algorithm_names(&results.cells);
+    for data_type in results.data_types() {
+        let cells: Vec<&CellResult> = results
+            .cells
+            .iter()
+            .filter(|c| c.data_type == data_type)
+            .collect();
+        writeln!(html, "<h2>{} Data</h2>", escape(data_type)).unwrap();
+        render_scatter(&mut html, &cells, &algorithms);
+        render_table(&mut html, &cells);
+    }
+
+    html.push_str("<h2>Compression Throughput by Input Size</h2>\n");
+    let mut sizes: Vec<usize> = results.cells.iter().map(|c| c.original_size).collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+    for size in sizes {
+        let cells: Vec<&CellResult> = results
+            .cells
+            .iter()
+            .filter(|c| c.original_size == size)
+            .collect();
+        render_bars(&mut html, size, &cells, &algorithms);
+    }
+
+    render_failures(&mut html, results);
+
+    writeln!(html, "<script>{}</script>\n</body>\n</html>", SORT_SCRIPT).unwrap();
+    html
+}
+
+fn render_metadata(html: &mut String, results: &RunResults) {
+    let metadata = &results.metadata;
+    html.push_str("<div class=\"meta\">\n");
+    writeln!(
+        html,
+        "<div><b>allocator</b>: {}</div>",
+        escape(&metadata.allocator)
+    )
+    .unwrap();
+    for (key, value) in &metadata.labels {
+        writeln!(html, "<div><b>{}</b>: {}</div>", escape(key), escape(value)).unwrap();
+    }
+    for note in &metadata.notes {
+        writeln!(html, "<div><i>{}</i></div>", escape(note)).unwrap();
+    }
+    html.push_str("</div>\n");
+}
+
+fn render_failures(html: &mut String, results: &RunResults) {
+    if results.failures.is_empty() {
+        return;
+    }
+    html.push_str("<h2>Failed Cells</h2>\n<table>\n");
+    html.push_str("<tr><th>Data</th><th>Algorithm</th><th>Level</th><th>Error</th></tr>\n");
+    for failure in &results.failures {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&failure.data_type),
+            escape(&failure.algorithm),
+            escape(&failure.level),
+            escape(&failure.error)
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+}
+
+// Ratio (y) against compression throughput (x, log scale)
+fn render_scatter(html: &mut String, cells: &[&CellResult], algorithms: &[&str]) {
+    let max_ratio = cells.iter().map(|c| c.ratio).fold(1.0, f64::max) * 1.1;
+    // The axis is log10, so cells with no measurable throughput are left off the chart
+    let plotted: Vec<(&CellResult, f64)> = cells
+        .iter()
+        .filter(|c| c.compress_mbps() > 0.0 && c.compress_mbps().is_finite())
+        .map(|c| (*c, c.compress_mbps().log10()))
+        .collect();
+    let (min_x, max_x) = if plotted.is_empty() {
+        (0.0, 1.0)
+    } else {
+        let speeds = plotted.iter().map(|(_, speed)| *speed);
+        (
+            speeds.clone().fold(f64::INFINITY, f64::min).floor(),
+            speeds.fold(f64::NEG_INFINITY, f64::max).ceil(),
+        )
+    };
+    let span_x = (max_x - min_x).max(1.0);
+    let plot_w = CHART_WIDTH - 2.0 * MARGIN;
+    let plot_h = CHART_HEIGHT - 2.0 * MARGIN;
+
+    open_svg(html, CHART_WIDTH, CHART_HEIGHT);
+    draw_axes(html, "Compression MB/s (log)", "Ratio");
+    let mut decade = min_x;
+    while decade <= min_x + span_x {
+        let x = MARGIN + (decade - min_x) / span_x * plot_w;
+        writeln!(
+            html,
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\" text-anchor=\"middle\">{}</text>",
+            x,
+            CHART_HEIGHT - MARGIN + 14.0,
+            10f64.powf(decade)
+        )
+        .unwrap();
+        decade += 1.0;
+    }
+    writeln!(
+        html,
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\" text-anchor=\"end\">{:.1}x</text>",
+        MARGIN - 4.0,
+        MARGIN + 4.0,
+        max_ratio
+    )
+    .unwrap();
+
+    for (cell, speed) in &plotted {
+        let x = MARGIN + (speed - min_x) / span_x * plot_w;
+        let y = CHART_HEIGHT - MARGIN - cell.ratio / max_ratio * plot_h;
+        writeln!(
+            html,
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"6\" fill=\"{}\"><title>{}: {:.2}x @ {:.1} MB/s</title></circle>",
+            x,
+            y,
+            color_for(&cell.algorithm, algorithms),
+            escape(&cell.label()),
+            cell.ratio,
+            cell.compress_mbps()
+        )
+        .unwrap();
+    }
+    draw_legend(html, algorithms);
+    html.push_str("</svg>\n");
+}
+
+// Compression throughput per configuration for a single input size
+fn render_bars(html: &mut String, size: usize, cells: &[&CellResult], algorithms: &[&str]) {
+    let max_speed = cells
+        .iter()
+        .map(|c| c.compress_mbps())
+        .fold(0.0, f64::max)
+        .max(f64::MIN_POSITIVE);
+    let row_height = 18.0;
+    let label_width = 220.0;
+    let bar_width = CHART_WIDTH - label_width - 80.0;
+    let height = cells.len() as f64 * row_height + 40.0;
+
+    writeln!(html, "<h3>{} input</h3>", format_size(size)).unwrap();
+    open_svg(html, CHART_WIDTH, height);
+    for (i, cell) in cells.iter().enumerate() {
+        let y = 20.0 + i as f64 * row_height;
+        let w = cell.compress_mbps() / max_speed * bar_width;
+        writeln!(
+            html,
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\" text-anchor=\"end\">{} / {}</text>",
+            label_width - 6.0,
+            y + 12.0,
+            escape(&cell.data_type),
+            escape(&cell.label())
+        )
+        .unwrap();
+        writeln!(
+            html,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{:.1} MB/s</title></rect>",
+            label_width,
+            y,
+            w,
+            row_height - 4.0,
+            color_for(&cell.algorithm, algorithms),
+            cell.compress_mbps()
+        )
+        .unwrap();
+        writeln!(
+            html,
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\">{:.1}</text>",
+            label_width + w + 4.0,
+            y + 12.0,
+            cell.compress_mbps()
+        )
+        .unwrap();
+    }
+    html.push_str("</svg>\n");
+}
+
+fn render_table(html: &mut String, cells: &[&CellResult]) {
+    html.push_str("<table class=\"sortable\">\n<thead><tr>");
+    for header in [
+        "Algorithm",
+        "Level",
+        "Ratio",
+        "Comp in MB/s",
+        "Comp out MB/s",
+        "Decomp in MB/s",
+        "Decomp out MB/s",
+        "Ratio 95% CI",
+        "Comp MB/s 95% CI",
+        "Decomp MB/s 95% CI",
+        "Comp ms ± sd [min, max]",
+        "Comp ms p50 / p90 / p99",
+        "Decomp ms ± sd [min, max]",
+        "Decomp ms p50 / p90 / p99",
+        "Peak RSS MB comp / decomp",
+        "Allocs (MB) comp / decomp",
+        "Instr (IPC, LLC misses) comp / decomp",
+        "Outliers",
+        "Quality",
+        "Size (MB)",
+        "Saved MB/CPU-s",
+        "Pareto (comp)",
+        "Pareto (decomp)",
+    ] {
+        write!(html, "<th>{}</th>", header).unwrap();
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    let compress_frontier = pareto::frontier(cells, |c| c.compress_secs);
+    let decompress_frontier = pareto::frontier(cells, |c| c.decompress_secs);
+    for cell in cells {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td data-value=\"{}\">{:.2}x</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td><td>{}</td><td>{}</td><td>{}</td><td data-value=\"{}\">{}</td><td data-value=\"{}\">{}</td><td data-value=\"{}\">{}</td><td data-value=\"{}\">{}</td><td data-value=\"{}\">{}</td><td data-value=\"{}\">{}</td><td data-value=\"{}\">{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.1}</td><td>{}</td><td>{}</td></tr>",
+            escape(&cell.algorithm),
+            escape(&cell.level),
+            cell.ratio,
+            cell.ratio,
+            cell.throughput.compress_input_mbps,
+            cell.throughput.compress_output_mbps,
+            cell.throughput.decompress_input_mbps,
+            cell.throughput.decompress_output_mbps,
+            cell.confidence.ratio.describe(2),
+            cell.confidence.compress_mbps.describe(1),
+            cell.confidence.decompress_mbps.describe(1),
+            cell.compress_secs,
+            cell.spread.compress.describe_ms(cell.compress_secs),
+            cell.spread.compress.median_secs,
+            cell.spread.compress.percentiles_ms(),
+            cell.decompress_secs,
+            cell.spread.decompress.describe_ms(cell.decompress_secs),
+            cell.spread.decompress.median_secs,
+            cell.spread.decompress.percentiles_ms(),
+            cell.peak_rss.compress_bytes.unwrap_or(0),
+            cell.peak_rss.describe_mb(),
+            cell.allocations.compress_count.unwrap_or(0),
+            cell.allocations.describe(),
+            cell.counters.compress.map_or(0, |c| c.instructions),
+            cell.counters.describe(),
+            table::outliers(cell),
+            cell.quality.label(),
+            cell.compressed_size as f64 / (1024.0 * 1024.0),
+            cell.efficiency.saved_mb_per_cpu_sec,
+            pareto_status(cell, &compress_frontier),
+            pareto_status(cell, &decompress_frontier)
+        )
+        .unwrap();
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+fn pareto_status(cell: &CellResult, frontier: &[ParetoPoint]) -> String {
+    match frontier
+        .iter()
+        .find(|p| std::ptr::eq(p.cell, cell))
+        .and_then(|p| p.dominated_by)
+    {
+        Some(winner) => format!("dominated by {}", escape(&winner.label())),
+        None => "optimal".to_string(),
+    }
+}
+
+fn open_svg(html: &mut String, width: f64, height: f64) {
+    writeln!(
+        html,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+        width, height
+    )
+    .unwrap();
+}
+
+fn draw_axes(html: &mut String, x_label: &str, y_label: &str) {
+    let bottom = CHART_HEIGHT - MARGIN;
+    writeln!(
+        html,
+        "<line x1=\"{0}\" y1=\"{1}\" x2=\"{2}\" y2=\"{1}\" stroke=\"#333\"/><line x1=\"{0}\" y1=\"{3}\" x2=\"{0}\" y2=\"{1}\" stroke=\"#333\"/>",
+        MARGIN,
+        bottom,
+        CHART_WIDTH - MARGIN,
+        MARGIN
+    )
+    .unwrap();
+    writeln!(
+        html,
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"12\" text-anchor=\"middle\">{}</text>",
+        CHART_WIDTH / 2.0,
+        CHART_HEIGHT - 12.0,
+        x_label
+    )
+    .unwrap();
+    writeln!(
+        html,
+        "<text x=\"14\" y=\"{:.1}\" font-size=\"12\" text-anchor=\"middle\" transform=\"rotate(-90 14 {:.1})\">{}</text>",
+        CHART_HEIGHT / 2.0,
+        CHART_HEIGHT / 2.0,
+        y_label
+    )
+    .unwrap();
+}
+
+fn draw_legend(html: &mut String, algorithms: &[&str]) {
+    for (i, algorithm) in algorithms.iter().enumerate() {
+        let y = MARGIN + i as f64 * 16.0;
+        writeln!(
+            html,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"10\" height=\"10\" fill=\"{}\"/><text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\">{}</text>",
+            CHART_WIDTH - MARGIN - 90.0,
+            y,
+            PALETTE[i % PALETTE.len()],
+            CHART_WIDTH - MARGIN - 75.0,
+            y + 9.0,
+            escape(algorithm)
+        )
+        .unwrap();
+    }
+}
+
+fn algorithm_names(cells: &[CellResult]) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+    for cell in cells {
+        if !names.contains(&cell.algorithm.as_str()) {
+            names.push(&cell.algorithm);
+        }
+    }
+    names
+}
+
+fn color_for(algorithm: &str, algorithms: &[&str]) -> &'static str {
+    let index = algorithms.iter().position(|a| *a == algorithm).unwrap_or(0);
+    PALETTE[index % PALETTE.len()]
+}
+
+fn format_size(size: usize) -> String {
+    if size >= 1024 * 1024 {
+        format!("{}MB", size / 1024 / 1024)
+    } else if size >= 1024 {
+        format!("{}KB", size / 1024)
+    } else {
+        format!("{}B", size)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
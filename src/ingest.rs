@@ -0,0 +1,196 @@
+use crate::{generate_test_data, TestData};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::PathBuf;
+use std::sync::mpsc::sync_channel;
+use std::thread;
+use std::time::Instant;
+
+// Where blocks come from: generated on the fly, or read from a file in order
+pub enum Source {
+    Generated { total: usize },
+    File(PathBuf),
+}
+
+impl Source {
+    fn describe(&self) -> String {
+        match self {
+            Source::Generated { total } => {
+                format!("{}MB generated mixed data", total / 1024 / 1024)
+            }
+            Source::File(path) => path.display().to_string(),
+        }
+    }
+
+    // Calls `block` with each block in turn; generated blocks are fresh every call
+    fn produce(
+        &self,
+        block_size: usize,
+        mut block: impl FnMut(Vec<u8>) -> io::Result<()>,
+    ) -> io::Result<()> {
+        match self {
+            Source::Generated { total } => {
+                for offset in (0..*total).step_by(block_size) {
+                    block(generate_test_data(
+                        &TestData::Mixed,
+                        block_size.min(total - offset),
+                    ))?;
+                }
+            }
+            Source::File(path) => {
+                let mut reader = BufReader::new(File::open(path)?);
+                loop {
+                    let mut buffer = Vec::with_capacity(block_size);
+                    (&mut reader)
+                        .take(block_size as u64)
+                        .read_to_end(&mut buffer)?;
+                    if buffer.is_empty() {
+                        break;
+                    }
+                    block(buffer)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Sequential {
+    produce_secs: f64,
+    compress_secs: f64,
+    total_secs: f64,
+    bytes: usize,
+}
+
+// Produce a block, compress it, then produce the next
+fn run_sequential(
+    source: &Source,
+    codec: &dyn Compressor,
+    block_size: usize,
+) -> io::Result<Sequential> {
+    let mut produce_secs = 0.0;
+    let mut compress_secs = 0.0;
+    let mut bytes = 0;
+    let start = Instant::now();
+    let mut produced = Instant::now();
+    source.produce(block_size, |block| {
+        produce_secs += produced.elapsed().as_secs_f64();
+        let compress_start = Instant::now();
+        codec.compress(&block)?;
+        compress_secs += compress_start.elapsed().as_secs_f64();
+        bytes += block.len();
+        produced = Instant::now();
+        Ok(())
+    })?;
+    Ok(Sequential {
+        produce_secs,
+        compress_secs,
+        total_secs: start.elapsed().as_secs_f64(),
+        bytes,
+    })
+}
+
+struct Pipelined {
+    total_secs: f64,
+    // Time the producer spent blocked on a full channel: the compressor couldn't keep up
+    producer_stalled_secs: f64,
+}
+
+// A producer thread feeds blocks through a channel of `depth` slots to the compressing thread
+fn run_pipelined(
+    source: &Source,
+    codec: &dyn Compressor,
+    block_size: usize,
+    depth: usize,
+) -> io::Result<Pipelined> {
+    let start = Instant::now();
+    let (sender, receiver) = sync_channel::<Vec<u8>>(depth);
+    let producer_stalled_secs = thread::scope(|scope| {
+        let producer = scope.spawn(move || {
+            let mut stalled = 0.0;
+            source.produce(block_size, |block| {
+                let send_start = Instant::now();
+                sender
+                    .send(block)
+                    .map_err(|_| io::Error::other("compressor stopped"))?;
+                stalled += send_start.elapsed().as_secs_f64();
+                Ok(())
+            })?;
+            Ok::<f64, io::Error>(stalled)
+        });
+        for block in receiver {
+            codec.compress(&block)?;
+        }
+        producer.join().expect("producer thread panicked")
+    })?;
+    Ok(Pipelined {
+        total_secs: start.elapsed().as_secs_f64(),
+        producer_stalled_secs,
+    })
+}
+
+// End-to-end throughput with production overlapped with compression, against doing them in turn
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    source: Source,
+    block_size: usize,
+    depth: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::REGISTRY
+            .iter()
+            .filter_map(|backend| backend.level("Default"))
+            .collect()
+    } else {
+        algorithms.to_vec()
+    };
+    if let Source::File(path) = &source {
+        File::open(path)?;
+    }
+
+    println!(
+        "\nIngest pipeline ({}, {}KB blocks, channel depth {})\n",
+        source.describe(),
+        block_size / 1024,
+        depth
+    );
+    if thread::available_parallelism().map_or(1, |n| n.get()) < 2 {
+        println!("Only one core is available, so producing and compressing cannot overlap\n");
+    }
+    println!(
+        "{:<18} {:>10} {:>10} {:>12} {:>11} {:>11} {:>9} {:>9}",
+        "Configuration",
+        "Produce",
+        "Compress",
+        "Sequential",
+        "Pipelined",
+        "E2E MB/s",
+        "Speedup",
+        "Stalled"
+    );
+    println!("{}", "-".repeat(98));
+    for codec in algorithms {
+        let label = format!("{} {}", codec.name(), codec.level());
+        let sequential = run_sequential(&source, codec, block_size)?;
+        let pipelined = run_pipelined(&source, codec, block_size, depth)?;
+        println!(
+            "{:<18} {:>9.3}s {:>9.3}s {:>11.3}s {:>10.3}s {:>11.1} {:>8.2}x {:>8.0}%",
+            label,
+            sequential.produce_secs,
+            sequential.compress_secs,
+            sequential.total_secs,
+            pipelined.total_secs,
+            sequential.bytes as f64 / (1024.0 * 1024.0) / pipelined.total_secs,
+            sequential.total_secs / pipelined.total_secs,
+            pipelined.producer_stalled_secs / pipelined.total_secs * 100.0
+        );
+    }
+    println!(
+        "\nStalled: share of the pipelined run the producer waited on a full channel; near 0% \
+         means the codec keeps up with the producer"
+    );
+    Ok(())
+}
@@ -0,0 +1,362 @@
+// Independent implementations of the same wire formats, to check that one's output decodes with
+// another's decoder: the guarantee a migration between crates relies on
+use std::io;
+#[cfg(any(feature = "gzip", feature = "lz4", feature = "lz4-flex"))]
+use std::io::{Read, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Gzip,
+    Zlib,
+    RawDeflate,
+    Lz4Frame,
+    // A bare LZ4 block; the decoder is told the uncompressed size
+    Lz4Block,
+}
+
+impl Format {
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Gzip => "gzip",
+            Format::Zlib => "zlib",
+            Format::RawDeflate => "raw deflate",
+            Format::Lz4Frame => "LZ4 frame",
+            Format::Lz4Block => "LZ4 block",
+        }
+    }
+}
+
+pub const FORMATS: [Format; 5] = [
+    Format::Gzip,
+    Format::Zlib,
+    Format::RawDeflate,
+    Format::Lz4Frame,
+    Format::Lz4Block,
+];
+
+// One crate's encoder and decoder for a format; decoders get the original size, which
+// buffer-oriented APIs need up front
+pub struct Implementation {
+    pub name: &'static str,
+    pub format: Format,
+    pub compress: fn(&[u8]) -> io::Result<Vec<u8>>,
+    pub decompress: fn(&[u8], usize) -> io::Result<Vec<u8>>,
+}
+
+// Every implementation compiled in, grouped by format
+pub const IMPLEMENTATIONS: &[Implementation] = &[
+    #[cfg(feature = "gzip")]
+    Implementation {
+        name: "flate2 (miniz_oxide)",
+        format: Format::Gzip,
+        compress: flate2_impl::gzip,
+        decompress: flate2_impl::gunzip,
+    },
+    #[cfg(feature = "libdeflate")]
+    Implementation {
+        name: "libdeflater",
+        format: Format::Gzip,
+        compress: libdeflate_impl::gzip,
+        decompress: libdeflate_impl::gunzip,
+    },
+    #[cfg(feature = "deflate-dict")]
+    Implementation {
+        name: "zlib-rs",
+        format: Format::Gzip,
+        compress: |data| zlib_rs_impl::compress(data, zlib_rs_impl::GZIP_BITS),
+        decompress: |data, size| zlib_rs_impl::decompress(data, size, zlib_rs_impl::GZIP_BITS),
+    },
+    #[cfg(feature = "gzip")]
+    Implementation {
+        name: "flate2 (miniz_oxide)",
+        format: Format::Zlib,
+        compress: flate2_impl::zlib,
+        decompress: flate2_impl::unzlib,
+    },
+    #[cfg(feature = "libdeflate")]
+    Implementation {
+        name: "libdeflater",
+        format: Format::Zlib,
+        compress: libdeflate_impl::zlib,
+        decompress: libdeflate_impl::unzlib,
+    },
+    #[cfg(feature = "deflate-dict")]
+    Implementation {
+        name: "zlib-rs",
+        format: Format::Zlib,
+        compress: |data| zlib_rs_impl::compress(data, zlib_rs_impl::ZLIB_BITS),
+        decompress: |data, size| zlib_rs_impl::decompress(data, size, zlib_rs_impl::ZLIB_BITS),
+    },
+    #[cfg(feature = "gzip")]
+    Implementation {
+        name: "flate2 (miniz_oxide)",
+        format: Format::RawDeflate,
+        compress: flate2_impl::deflate,
+        decompress: flate2_impl::inflate,
+    },
+    #[cfg(feature = "libdeflate")]
+    Implementation {
+        name: "libdeflater",
+        format: Format::RawDeflate,
+        compress: libdeflate_impl::deflate,
+        decompress: libdeflate_impl::inflate,
+    },
+    #[cfg(feature = "deflate-dict")]
+    Implementation {
+        name: "zlib-rs",
+        format: Format::RawDeflate,
+        compress: |data| zlib_rs_impl::compress(data, zlib_rs_impl::RAW_BITS),
+        decompress: |data, size| zlib_rs_impl::decompress(data, size, zlib_rs_impl::RAW_BITS),
+    },
+    #[cfg(feature = "lz4")]
+    Implementation {
+        name: "lz4 (liblz4)",
+        format: Format::Lz4Frame,
+        compress: lz4_impl::frame,
+        decompress: lz4_impl::unframe,
+    },
+    #[cfg(feature = "lz4-flex")]
+    Implementation {
+        name: "lz4_flex",
+        format: Format::Lz4Frame,
+        compress: lz4_flex_impl::frame,
+        decompress: lz4_flex_impl::unframe,
+    },
+    #[cfg(feature = "lz4")]
+    Implementation {
+        name: "lz4 (liblz4)",
+        format: Format::Lz4Block,
+        compress: lz4_impl::block,
+        decompress: lz4_impl::unblock,
+    },
+    #[cfg(feature = "lz4-flex")]
+    Implementation {
+        name: "lz4_flex",
+        format: Format::Lz4Block,
+        compress: |data| Ok(lz4_flex::block::compress(data)),
+        decompress: |data, size| lz4_flex::block::decompress(data, size).map_err(io::Error::other),
+    },
+];
+
+pub fn implementations(format: Format) -> impl Iterator<Item = &'static Implementation> {
+    IMPLEMENTATIONS.iter().filter(move |i| i.format == format)
+}
+
+// Whether `decoder` restores what `encoder` compressed from `data`; Err describes the failure
+pub fn check(
+    encoder: &Implementation,
+    decoder: &Implementation,
+    data: &[u8],
+) -> Result<(), String> {
+    let compressed = (encoder.compress)(data).map_err(|e| format!("compress: {}", e))?;
+    let restored =
+        (decoder.decompress)(&compressed, data.len()).map_err(|e| format!("decompress: {}", e))?;
+    if restored != data {
+        return Err(format!(
+            "{} of {} bytes restored differently",
+            restored.len(),
+            data.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "gzip")]
+mod flate2_impl {
+    use super::*;
+    use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+    use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+
+    fn finish<W: Write>(mut encoder: W, data: &[u8]) -> io::Result<W> {
+        encoder.write_all(data)?;
+        Ok(encoder)
+    }
+
+    fn drain(mut decoder: impl Read, size: usize) -> io::Result<Vec<u8>> {
+        let mut restored = Vec::with_capacity(size);
+        decoder.read_to_end(&mut restored)?;
+        Ok(restored)
+    }
+
+    pub fn gzip(data: &[u8]) -> io::Result<Vec<u8>> {
+        finish(GzEncoder::new(Vec::new(), Compression::default()), data)?.finish()
+    }
+
+    pub fn gunzip(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        drain(GzDecoder::new(data), size)
+    }
+
+    pub fn zlib(data: &[u8]) -> io::Result<Vec<u8>> {
+        finish(ZlibEncoder::new(Vec::new(), Compression::default()), data)?.finish()
+    }
+
+    pub fn unzlib(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        drain(ZlibDecoder::new(data), size)
+    }
+
+    pub fn deflate(data: &[u8]) -> io::Result<Vec<u8>> {
+        finish(
+            DeflateEncoder::new(Vec::new(), Compression::default()),
+            data,
+        )?
+        .finish()
+    }
+
+    pub fn inflate(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        drain(DeflateDecoder::new(data), size)
+    }
+}
+
+#[cfg(feature = "libdeflate")]
+mod libdeflate_impl {
+    use super::*;
+    use libdeflater::{
+        CompressionError, CompressionLvl, Compressor, DecompressionError, Decompressor,
+    };
+
+    type Compress = fn(&mut Compressor, &[u8], &mut [u8]) -> Result<usize, CompressionError>;
+    type Decompress = fn(&mut Decompressor, &[u8], &mut [u8]) -> Result<usize, DecompressionError>;
+
+    fn compress(
+        data: &[u8],
+        bound: fn(&mut Compressor, usize) -> usize,
+        compress: Compress,
+    ) -> io::Result<Vec<u8>> {
+        let mut compressor = Compressor::new(CompressionLvl::default());
+        let mut output = vec![0u8; bound(&mut compressor, data.len())];
+        let len = compress(&mut compressor, data, &mut output).map_err(io::Error::other)?;
+        output.truncate(len);
+        Ok(output)
+    }
+
+    fn decompress(data: &[u8], size: usize, decompress: Decompress) -> io::Result<Vec<u8>> {
+        let mut output = vec![0u8; size];
+        let len =
+            decompress(&mut Decompressor::new(), data, &mut output).map_err(io::Error::other)?;
+        output.truncate(len);
+        Ok(output)
+    }
+
+    pub fn gzip(data: &[u8]) -> io::Result<Vec<u8>> {
+        compress(
+            data,
+            Compressor::gzip_compress_bound,
+            Compressor::gzip_compress,
+        )
+    }
+
+    pub fn gunzip(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        decompress(data, size, Decompressor::gzip_decompress)
+    }
+
+    pub fn zlib(data: &[u8]) -> io::Result<Vec<u8>> {
+        compress(
+            data,
+            Compressor::zlib_compress_bound,
+            Compressor::zlib_compress,
+        )
+    }
+
+    pub fn unzlib(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        decompress(data, size, Decompressor::zlib_decompress)
+    }
+
+    pub fn deflate(data: &[u8]) -> io::Result<Vec<u8>> {
+        compress(
+            data,
+            Compressor::deflate_compress_bound,
+            Compressor::deflate_compress,
+        )
+    }
+
+    pub fn inflate(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        decompress(data, size, Decompressor::deflate_decompress)
+    }
+}
+
+#[cfg(feature = "deflate-dict")]
+mod zlib_rs_impl {
+    use super::*;
+    use zlib_rs::{DeflateConfig, InflateConfig, ReturnCode};
+
+    // zlib's window_bits convention: 15 for a zlib header, +16 for gzip, negative for raw
+    pub const ZLIB_BITS: i32 = 15;
+    pub const GZIP_BITS: i32 = 31;
+    pub const RAW_BITS: i32 = -15;
+
+    fn check(code: ReturnCode) -> io::Result<()> {
+        match code {
+            ReturnCode::Ok => Ok(()),
+            code => Err(io::Error::other(format!("zlib-rs returned {:?}", code))),
+        }
+    }
+
+    pub fn compress(data: &[u8], window_bits: i32) -> io::Result<Vec<u8>> {
+        let config = DeflateConfig {
+            window_bits,
+            ..DeflateConfig::default()
+        };
+        let mut output = vec![0u8; zlib_rs::compress_bound(data.len()) + 32];
+        let (compressed, code) = zlib_rs::compress_slice(&mut output, data, config);
+        check(code)?;
+        let len = compressed.len();
+        output.truncate(len);
+        Ok(output)
+    }
+
+    pub fn decompress(data: &[u8], size: usize, window_bits: i32) -> io::Result<Vec<u8>> {
+        let mut output = vec![0u8; size];
+        let (restored, code) =
+            zlib_rs::decompress_slice(&mut output, data, InflateConfig { window_bits });
+        check(code)?;
+        let len = restored.len();
+        output.truncate(len);
+        Ok(output)
+    }
+}
+
+#[cfg(feature = "lz4")]
+mod lz4_impl {
+    use super::*;
+
+    pub fn frame(data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut encoder = lz4::EncoderBuilder::new().build(Vec::new())?;
+        encoder.write_all(data)?;
+        let (output, result) = encoder.finish();
+        result.map(|()| output)
+    }
+
+    pub fn unframe(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        let mut restored = Vec::with_capacity(size);
+        lz4::Decoder::new(data)?.read_to_end(&mut restored)?;
+        Ok(restored)
+    }
+
+    pub fn block(data: &[u8]) -> io::Result<Vec<u8>> {
+        lz4::block::compress(data, None, false)
+    }
+
+    pub fn unblock(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        let size = i32::try_from(size).map_err(io::Error::other)?;
+        lz4::block::decompress(data, Some(size))
+    }
+}
+
+#[cfg(feature = "lz4-flex")]
+mod lz4_flex_impl {
+    use super::*;
+    use lz4_flex::frame::{FrameDecoder, FrameEncoder};
+
+    pub fn frame(data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut encoder = FrameEncoder::new(Vec::new());
+        encoder.write_all(data)?;
+        encoder.finish().map_err(io::Error::other)
+    }
+
+    pub fn unframe(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        let mut restored = Vec::with_capacity(size);
+        FrameDecoder::new(data).read_to_end(&mut restored)?;
+        Ok(restored)
+    }
+}
@@ -0,0 +1,65 @@
+use rust_compress_comparison::harness::{generate_test_data, TEST_CASES};
+use rust_compress_comparison::interop::{self, FORMATS};
+use std::error::Error;
+
+// Each format's encoder × decoder matrix over every data type; a cell passes only if every
+// input round-trips
+pub fn run(size_kb: usize) -> Result<(), Box<dyn Error>> {
+    let mut inputs: Vec<(&str, Vec<u8>)> = TEST_CASES
+        .iter()
+        .map(|(name, data_type)| (*name, generate_test_data(data_type, size_kb * 1024)))
+        .collect();
+    inputs.push(("Empty", Vec::new()));
+    println!(
+        "\nCross-implementation interop ({}KB of each data type, plus empty input)",
+        size_kb
+    );
+
+    let mut failures = Vec::new();
+    for format in FORMATS {
+        let implementations: Vec<_> = interop::implementations(format).collect();
+        if implementations.len() < 2 {
+            continue;
+        }
+        println!("\n=== {} ===\n", format.name());
+        print!("{:<22}", "Encoder \\ Decoder");
+        for decoder in &implementations {
+            print!(" {:>22}", decoder.name);
+        }
+        println!();
+        println!("{}", "-".repeat(22 + 23 * implementations.len()));
+        for encoder in &implementations {
+            print!("{:<22}", encoder.name);
+            for decoder in &implementations {
+                let mut passed = true;
+                for (name, data) in &inputs {
+                    if let Err(e) = interop::check(encoder, decoder, data) {
+                        passed = false;
+                        failures.push(format!(
+                            "{}: {} -> {} on {}: {}",
+                            format.name(),
+                            encoder.name,
+                            decoder.name,
+                            name,
+                            e
+                        ));
+                    }
+                }
+                print!(" {:>22}", if passed { "ok" } else { "FAIL" });
+            }
+            println!();
+        }
+    }
+    println!(
+        "\nflate2 builds with one backend per build (miniz_oxide here); zlib-rs stands in for its \
+         zlib backends"
+    );
+    if failures.is_empty() {
+        return Ok(());
+    }
+    println!();
+    for failure in &failures {
+        println!("{}", failure);
+    }
+    Err(format!("{} interop checks failed", failures.len()).into())
+}
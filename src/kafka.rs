@@ -0,0 +1,223 @@
+use crate::records::generate_records;
+use crate::results::percentile;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::time::Instant;
+
+pub const DEFAULT_BATCH_KB: [usize; 4] = [16, 64, 256, 1024];
+// Kafka's defaults since levels became configurable: gzip -1 (zlib's 6), lz4 9, zstd 3
+const GZIP_LEVEL: u32 = 6;
+const LZ4_LEVEL: u32 = 9;
+const ZSTD_LEVEL: i32 = 3;
+
+// A `compression.type`; the records section of each batch is compressed on its own
+struct Codec {
+    name: &'static str,
+    compress: fn(&[u8]) -> io::Result<Vec<u8>>,
+    decompress: fn(&[u8]) -> io::Result<Vec<u8>>,
+}
+
+fn gzip(records: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(GZIP_LEVEL));
+    encoder.write_all(records)?;
+    encoder.finish()
+}
+
+fn gunzip(compressed: &[u8]) -> io::Result<Vec<u8>> {
+    let mut restored = Vec::new();
+    GzDecoder::new(compressed).read_to_end(&mut restored)?;
+    Ok(restored)
+}
+
+// Kafka's lz4 is the LZ4 frame format
+fn lz4_compress(records: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = lz4::EncoderBuilder::new()
+        .level(LZ4_LEVEL)
+        .build(Vec::new())?;
+    encoder.write_all(records)?;
+    let (output, result) = encoder.finish();
+    result.map(|()| output)
+}
+
+fn lz4_decompress(compressed: &[u8]) -> io::Result<Vec<u8>> {
+    let mut restored = Vec::new();
+    lz4::Decoder::new(compressed)?.read_to_end(&mut restored)?;
+    Ok(restored)
+}
+
+// Raw snappy blocks; the Java client wraps them in xerial's framing, a few bytes per 32KB block
+fn snappy_compress(records: &[u8]) -> io::Result<Vec<u8>> {
+    snap::raw::Encoder::new()
+        .compress_vec(records)
+        .map_err(io::Error::other)
+}
+
+fn snappy_decompress(compressed: &[u8]) -> io::Result<Vec<u8>> {
+    snap::raw::Decoder::new()
+        .decompress_vec(compressed)
+        .map_err(io::Error::other)
+}
+
+fn zstd_compress(records: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::bulk::compress(records, ZSTD_LEVEL)
+}
+
+fn zstd_decompress(compressed: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::decode_all(compressed)
+}
+
+const CODECS: [Codec; 4] = [
+    Codec {
+        name: "gzip",
+        compress: gzip,
+        decompress: gunzip,
+    },
+    Codec {
+        name: "snappy",
+        compress: snappy_compress,
+        decompress: snappy_decompress,
+    },
+    Codec {
+        name: "lz4",
+        compress: lz4_compress,
+        decompress: lz4_decompress,
+    },
+    Codec {
+        name: "zstd",
+        compress: zstd_compress,
+        decompress: zstd_decompress,
+    },
+];
+
+fn push_varint(out: &mut Vec<u8>, value: i64) {
+    // Zigzag, as every varint in a v2 record is signed
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+// One record as it sits in a v2 RecordBatch: length, attributes, timestamp and offset deltas,
+// key, value and an empty header list
+fn encode_record(out: &mut Vec<u8>, offset_delta: i64, key: &[u8], value: &[u8]) {
+    let mut record = vec![0];
+    push_varint(&mut record, offset_delta * 3);
+    push_varint(&mut record, offset_delta);
+    push_varint(&mut record, key.len() as i64);
+    record.extend_from_slice(key);
+    push_varint(&mut record, value.len() as i64);
+    record.extend_from_slice(value);
+    push_varint(&mut record, 0);
+    push_varint(out, record.len() as i64);
+    out.extend_from_slice(&record);
+}
+
+// Records accumulated into batches the way the producer does: a batch is closed once the next
+// record would take it past `batch_size`
+fn batches(values: &[Vec<u8>], batch_size: usize) -> Vec<Vec<u8>> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut offset_delta = 0;
+    for (i, value) in values.iter().enumerate() {
+        let key = format!("user-{}", i % 1000);
+        let mut record = Vec::with_capacity(value.len() + 32);
+        encode_record(&mut record, offset_delta, key.as_bytes(), value);
+        if !batch.is_empty() && batch.len() + record.len() > batch_size {
+            batches.push(std::mem::replace(
+                &mut batch,
+                Vec::with_capacity(batch_size),
+            ));
+            offset_delta = 0;
+            record.clear();
+            encode_record(&mut record, offset_delta, key.as_bytes(), value);
+        }
+        batch.extend_from_slice(&record);
+        offset_delta += 1;
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+struct Measured {
+    ratio: f64,
+    // Sorted per-batch times
+    compress: Vec<f64>,
+    decompress: Vec<f64>,
+    compress_mbps: f64,
+}
+
+fn measure(codec: &Codec, batches: &[Vec<u8>]) -> Result<Measured, Box<dyn Error>> {
+    let mut original = 0;
+    let mut compressed_total = 0;
+    let mut compress = Vec::with_capacity(batches.len());
+    let mut decompress = Vec::with_capacity(batches.len());
+    for batch in batches {
+        let start = Instant::now();
+        let compressed = (codec.compress)(batch)?;
+        compress.push(start.elapsed().as_secs_f64());
+        let start = Instant::now();
+        let restored = (codec.decompress)(&compressed)?;
+        decompress.push(start.elapsed().as_secs_f64());
+        if restored != *batch {
+            return Err(format!("{} round trip mismatch", codec.name).into());
+        }
+        original += batch.len();
+        compressed_total += compressed.len();
+    }
+    let compress_secs: f64 = compress.iter().sum();
+    compress.sort_by(f64::total_cmp);
+    decompress.sort_by(f64::total_cmp);
+    Ok(Measured {
+        ratio: original as f64 / compressed_total as f64,
+        compress,
+        decompress,
+        compress_mbps: original as f64 / (1024.0 * 1024.0) / compress_secs,
+    })
+}
+
+// Producer-side batch compression with Kafka's codecs, per batch.size
+pub fn run(batch_kb: &[usize], record_count: usize) -> Result<(), Box<dyn Error>> {
+    let values = generate_records(record_count);
+    let bytes: usize = values.iter().map(Vec::len).sum();
+    println!(
+        "\nKafka producer batches ({} JSON records averaging {} bytes, compressed per batch)",
+        record_count,
+        bytes / record_count
+    );
+    for &kb in batch_kb {
+        let batches = batches(&values, kb * 1024);
+        println!(
+            "\n=== batch.size {}KB ({} batches of ~{} records) ===\n",
+            kb,
+            batches.len(),
+            record_count / batches.len()
+        );
+        println!(
+            "{:<8} {:>7} {:>12} {:>12} {:>12} {:>12} {:>10}",
+            "Codec", "Ratio", "Comp p50", "Comp p99", "Decomp p50", "Decomp p99", "Comp MB/s"
+        );
+        println!("{}", "-".repeat(79));
+        for codec in &CODECS {
+            let measured = measure(codec, &batches)?;
+            println!(
+                "{:<8} {:>6.2}x {:>10.1}us {:>10.1}us {:>10.1}us {:>10.1}us {:>10.1}",
+                codec.name,
+                measured.ratio,
+                percentile(&measured.compress, 0.5) * 1e6,
+                percentile(&measured.compress, 0.99) * 1e6,
+                percentile(&measured.decompress, 0.5) * 1e6,
+                percentile(&measured.decompress, 0.99) * 1e6,
+                measured.compress_mbps
+            );
+        }
+    }
+    println!("\nRatio is over all batches together; latencies are per batch");
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use crate::results::percentile;
+use crate::timer::{ClockChoice, Timer};
+use crate::{generate_test_data, TestData};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::harness;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::hint::black_box;
+
+pub const DEFAULT_PAYLOADS: [usize; 5] = [64, 256, 1024, 4096, 16 * 1024];
+// Distinct messages cycled through, so no single buffer stays hot in cache
+const DISTINCT_MESSAGES: usize = 256;
+
+// Per-call latencies of one direction, sorted, with timer overhead subtracted
+struct Latencies(Vec<f64>);
+
+impl Latencies {
+    fn describe_us(&self) -> String {
+        let us = |p: f64| percentile(&self.0, p) * 1e6;
+        format!(
+            "{:>8.1} {:>8.1} {:>8.1} {:>9.1}",
+            us(0.5),
+            us(0.99),
+            us(0.999),
+            self.0[self.0.len() - 1] * 1e6
+        )
+    }
+}
+
+// Times `calls` compressions and decompressions of `size`-byte messages, one call per sample
+fn measure(
+    timer: &Timer,
+    codec: &dyn Compressor,
+    messages: &[Vec<u8>],
+    calls: usize,
+) -> Result<(Latencies, Latencies, f64), Box<dyn Error>> {
+    let compressed: Vec<Vec<u8>> = messages
+        .iter()
+        .map(|message| harness::compress(codec, message))
+        .collect::<Result<_, _>>()?;
+    for (message, compressed) in messages.iter().zip(&compressed) {
+        harness::verify(codec, message, compressed)?;
+    }
+
+    let mut compress = Vec::with_capacity(calls);
+    let mut decompress = Vec::with_capacity(calls);
+    for call in 0..calls {
+        let index = call % messages.len();
+        let (output, secs) = timer.time(|| codec.compress(black_box(&messages[index])));
+        output?;
+        compress.push(timer.corrected(secs));
+        let (output, secs) = timer.time(|| codec.decompress(black_box(&compressed[index])));
+        output?;
+        decompress.push(timer.corrected(secs));
+    }
+    compress.sort_by(f64::total_cmp);
+    decompress.sort_by(f64::total_cmp);
+
+    let original: usize = messages.iter().map(Vec::len).sum();
+    let packed: usize = compressed.iter().map(Vec::len).sum();
+    Ok((
+        Latencies(compress),
+        Latencies(decompress),
+        original as f64 / packed as f64,
+    ))
+}
+
+// Per-call latency distributions for small messages, where fixed per-call costs dominate
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    payloads: &[usize],
+    calls: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::configurations().collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let timer = Timer::calibrate(ClockChoice::Auto)?;
+    println!(
+        "\nSmall-payload latency ({} calls per cell, mixed data, {}; overhead {:.1} ns subtracted)",
+        calls,
+        timer.name(),
+        timer.overhead_secs() * 1e9
+    );
+
+    for &size in payloads {
+        let messages: Vec<Vec<u8>> = (0..DISTINCT_MESSAGES.min(calls))
+            .map(|_| generate_test_data(&TestData::Mixed, size))
+            .collect();
+        println!("\n=== {} byte payloads (latency in us) ===\n", size);
+        println!(
+            "{:<18} {:>7} | {:>8} {:>8} {:>8} {:>9} | {:>8} {:>8} {:>8} {:>9}",
+            "Configuration",
+            "Ratio",
+            "c p50",
+            "c p99",
+            "c p99.9",
+            "c max",
+            "d p50",
+            "d p99",
+            "d p99.9",
+            "d max"
+        );
+        println!("{}", "-".repeat(111));
+        for codec in &algorithms {
+            let label = format!("{} {}", codec.name(), codec.level());
+            match measure(&timer, *codec, &messages, calls) {
+                Ok((compress, decompress, ratio)) => println!(
+                    "{:<18} {:>6.2}x | {} | {}",
+                    label,
+                    ratio,
+                    compress.describe_us(),
+                    decompress.describe_us()
+                ),
+                Err(e) => println!("{:<18} FAILED: {}", label, e),
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,143 @@
+// Compression backends behind one interface, shared by the CLI and the criterion benches
+pub mod backends;
+pub mod cache;
+#[cfg(feature = "parallel")]
+pub mod chunked;
+pub mod config;
+pub mod counters;
+pub mod cpu;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod harness;
+pub mod interop;
+pub mod machine;
+pub mod memory;
+pub mod metadata;
+pub mod noise;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+pub mod pool;
+#[cfg(feature = "python")]
+mod python;
+pub mod results;
+pub mod streamed;
+pub mod suite;
+pub mod system;
+pub mod timer;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use error::CompressionBenchError;
+pub use results::RunResults;
+pub use suite::BenchmarkSuite;
+
+// std's Instant panics on wasm32-unknown-unknown; web-time reads performance.now() there instead
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) use web_time::Instant;
+
+use std::io::{self, Read};
+
+// One (algorithm, level) configuration that can round-trip a buffer
+pub trait Compressor: Send + Sync {
+    // Algorithm name as shown in result tables, e.g. "Gzip"
+    fn name(&self) -> &'static str;
+
+    // Level name as shown in result tables, e.g. "Fast" or "9"
+    fn level(&self) -> String;
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>>;
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>>;
+
+    // `decompress`, failing once the output would pass `limit` bytes. The default checks the
+    // finished output, so it bounds the result but not the memory spent reaching it; backends
+    // with a streaming decoder or an up-front size stop early instead
+    fn decompress_limited(&self, data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        let decompressed = self.decompress(data)?;
+        if decompressed.len() > limit {
+            return Err(limit_exceeded(limit));
+        }
+        Ok(decompressed)
+    }
+
+    // Same output format as `compress`, but fed to a streaming encoder `write_size` bytes per
+    // write; backends without one return `Unsupported`
+    fn compress_streamed(&self, data: &[u8], write_size: usize) -> io::Result<Vec<u8>> {
+        self.compress_phased(data, write_size)
+            .map(|(compressed, _)| compressed)
+    }
+
+    // `compress_streamed`, also timing the writes apart from the final finish(), which flushes
+    // buffered input and writes the trailer
+    fn compress_phased(
+        &self,
+        data: &[u8],
+        write_size: usize,
+    ) -> io::Result<(Vec<u8>, StreamPhases)> {
+        let _ = (data, write_size);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("{} has no streaming encoder", self.name()),
+        ))
+    }
+}
+
+// Reads a decoder to the end, giving up as soon as it yields more than `limit` bytes rather than
+// growing the buffer with whatever an untrusted input expands to
+pub fn read_limited<R: Read>(reader: R, limit: usize) -> io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_to_end(&mut decompressed)?;
+    if decompressed.len() > limit {
+        return Err(limit_exceeded(limit));
+    }
+    Ok(decompressed)
+}
+
+pub fn limit_exceeded(limit: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::FileTooLarge,
+        format!("decompressed output exceeds the {} byte limit", limit),
+    )
+}
+
+// Where a streamed compression spent its time
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StreamPhases {
+    pub write_secs: f64,
+    pub finish_secs: f64,
+}
+
+// Only the backends with a streaming encoder construct phases
+#[cfg(any(feature = "gzip", feature = "zstd", feature = "lz4"))]
+impl StreamPhases {
+    // Encoder construction counts as writing; `finish` is when the final finish() was called
+    pub(crate) fn since(start: Instant, finish: Instant) -> Self {
+        StreamPhases {
+            write_secs: (finish - start).as_secs_f64(),
+            finish_secs: finish.elapsed().as_secs_f64(),
+        }
+    }
+}
+
+// A backend and the configurations it is benchmarked at
+pub struct Backend {
+    // Lowercase identifier used on the command line, e.g. "lz4-rs"
+    pub id: &'static str,
+    pub levels: &'static [&'static dyn Compressor],
+    // Leading bytes of this backend's output, for recognizing existing files; empty if it has none
+    pub magic: &'static [u8],
+}
+
+impl Backend {
+    pub fn level(&self, level: &str) -> Option<&'static dyn Compressor> {
+        self.levels
+            .iter()
+            .copied()
+            .find(|codec| codec.level().eq_ignore_ascii_case(level))
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::thread;
+
+// Host the benchmark ran on, recorded with every run
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct MachineInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    pub cpu_model: String,
+    pub cores: usize,
+    pub os: String,
+    pub arch: String,
+}
+
+impl MachineInfo {
+    pub fn detect() -> Self {
+        MachineInfo {
+            hostname: detect_hostname(),
+            cpu_model: detect_cpu_model().unwrap_or_else(|| "unknown".to_string()),
+            cores: thread::available_parallelism().map_or(1, |n| n.get()),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+        }
+    }
+
+    // Drops the hostname and coarsens the CPU model to its product family
+    pub fn anonymized(&self) -> Self {
+        MachineInfo {
+            hostname: None,
+            cpu_model: cpu_class(&self.cpu_model, &self.arch),
+            ..self.clone()
+        }
+    }
+}
+
+fn detect_hostname() -> Option<String> {
+    fs::read_to_string("/etc/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn detect_cpu_model() -> Option<String> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo
+        .lines()
+        .find(|line| line.starts_with("model name") || line.starts_with("Model"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, model)| model.trim().to_string())
+}
+
+// Product family without SKU, e.g. "Intel Xeon" or "AMD Ryzen"
+pub fn cpu_class(model: &str, arch: &str) -> String {
+    const FAMILIES: [(&str, &str); 8] = [
+        ("EPYC", "AMD EPYC"),
+        ("Ryzen", "AMD Ryzen"),
+        ("Threadripper", "AMD Threadripper"),
+        ("Xeon", "Intel Xeon"),
+        ("Core", "Intel Core"),
+        ("Apple", "Apple Silicon"),
+        ("Neoverse", "ARM Neoverse"),
+        ("Graviton", "AWS Graviton"),
+    ];
+    FAMILIES
+        .iter()
+        .find(|(needle, _)| model.contains(needle))
+        .map(|(_, family)| family.to_string())
+        .unwrap_or_else(|| format!("other {}", arch))
+}
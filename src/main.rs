@@ -1,190 +1,2051 @@
-use flate2::write::GzEncoder;
-use flate2::Compression;
-use lz4::EncoderBuilder;
-use lz4_flex::compress_prepend_size;
-use rand::{distributions::Alphanumeric, thread_rng, Rng};
-use std::io::Write;
-use std::time::Instant;
-
-const DATA_SIZE: usize = 1024 * 1024 * 10; // 10MB
-const NUM_TRIALS: usize = 1;
-
-// Different types of test data
-enum TestData {
-    Random,
-    Repeating,
-    Mixed,
-}
-
-fn generate_test_data(data_type: &TestData) -> Vec<u8> {
-    match data_type {
-        TestData::Random => thread_rng()
-            .sample_iter(&Alphanumeric)
-            .take(DATA_SIZE)
-            .map(|c| c as u8)
-            .collect(),
-
-        TestData::Repeating => {
-            let pattern = b"HelloWorld";
-            let mut data = Vec::with_capacity(DATA_SIZE);
-            while data.len() < DATA_SIZE {
-                data.extend_from_slice(pattern);
-            }
-            data.truncate(DATA_SIZE);
-            data
-        }
+#[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+mod acceleration;
+mod baseline;
+mod bombs;
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+mod buffers;
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+mod checksums;
+#[cfg(feature = "parallel")]
+mod chunking;
+mod cold_cache;
+#[cfg(all(feature = "gzip", feature = "lz4-flex", feature = "zstd"))]
+mod columnar;
+mod community;
+mod compare;
+#[cfg(all(feature = "gzip", feature = "zstd"))]
+mod content_encoding;
+mod contention;
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+mod contexts;
+mod corruption;
+mod cost;
+mod decompress_only;
+#[cfg(feature = "zstd")]
+mod dictionaries;
+mod diff;
+mod digests;
+#[cfg(all(feature = "lz4-flex", feature = "zstd"))]
+mod fallback;
+mod flushing;
+mod footprint;
+mod fuzz;
+mod gh_benchmark;
+#[cfg(all(feature = "gzip", feature = "zstd"))]
+mod grpc;
+mod html;
+mod ingest;
+mod interoperability;
+#[cfg(all(feature = "gzip", feature = "lz4", feature = "zstd"))]
+mod kafka;
+mod latency;
+mod merge;
+#[cfg(feature = "parallel")]
+mod multipart;
+#[cfg(feature = "otlp")]
+mod otlp;
+mod pages;
+mod pareto;
+mod phases;
+mod pipeline;
+mod plots;
+mod prometheus;
+mod quick;
+mod ranking;
+mod realistic;
+mod records;
+#[cfg(all(feature = "gzip", feature = "zstd"))]
+mod reuse;
+#[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+mod safety;
+mod samples;
+mod scaling;
+#[cfg(all(feature = "gzip", feature = "lz4", feature = "zstd"))]
+mod seekable;
+mod serialization;
+mod serve;
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+mod setup;
+mod soak;
+mod store;
+mod streaming;
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+mod study;
+mod table;
+mod tarball;
+mod timing;
+mod transfer;
+mod tui;
+mod wal;
+mod warmup;
+
+use baseline::Thresholds;
+use clap::{Args, Parser, Subcommand};
+use metadata::{parse_label, RunMetadata};
+use plots::PlotFormat;
+use ranking::{parse_objective, Objective, DEFAULT_OBJECTIVES};
+use realistic::SinkKind;
+use results::{CellResult, RunResults};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::cache::CacheState;
+use rust_compress_comparison::harness::{generate_test_data, try_timed, TestData, TEST_CASES};
+#[cfg(feature = "plugins")]
+use rust_compress_comparison::plugin;
+use rust_compress_comparison::suite::{
+    BenchmarkSuite, CompressionStats, Schedule, DEFAULT_WARMUP_ITERATIONS,
+};
+use rust_compress_comparison::Compressor;
+use rust_compress_comparison::{cpu, metadata, noise, results, system, timer};
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use timer::ClockChoice;
+
+// Command-line options
+#[derive(Parser)]
+#[command(about = "Compare compression ratio and speed across Rust compression crates")]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    bench: BenchArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the compression benchmarks (default)
+    Bench(Box<BenchArgs>),
+    /// Render a report from a saved results file
+    Report(ReportArgs),
+    /// Run a ~10 second sanity benchmark of the fast tiers only
+    Quick,
+    /// Report how many iterations each codec needs to reach steady-state throughput
+    Warmup,
+    /// Measure how well compression overlaps with writes to a slow sink
+    Pipeline(PipelineArgs),
+    /// Write a copy of a results file that is safe to share publicly
+    Export(ExportArgs),
+    /// Merge contributed result files into cross-hardware markdown tables
+    Aggregate(AggregateArgs),
+    /// Report Send/Sync/pooling support per codec type and benchmark pooled contexts
+    #[cfg(all(
+        feature = "gzip",
+        feature = "lz4",
+        feature = "lz4-flex",
+        feature = "zstd"
+    ))]
+    Contexts(ContextsArgs),
+    /// Compare black_box-retained output against writing it to a real sink
+    Realistic(RealisticArgs),
+    /// Calibrate timer overhead and report it against small-payload compression times
+    Timing(TimingArgs),
+    /// Compare framings for length-prefixed compressed JSON records
+    Records(RecordsArgs),
+    /// Show per-cell ratio and throughput changes between two results files
+    Diff(DiffArgs),
+    /// Benchmark tail latency of a zstd -> lz4 -> store fallback chain under CPU pressure
+    #[cfg(all(feature = "lz4-flex", feature = "zstd"))]
+    Fallback(FallbackArgs),
+    /// Sweep lz4's acceleration factor and chart the fast tier's speed/ratio curve
+    #[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+    Acceleration(AccelerationArgs),
+    /// Run a preset and write a short narrative summary for design docs
+    #[cfg(all(
+        feature = "gzip",
+        feature = "lz4",
+        feature = "lz4-flex",
+        feature = "zstd"
+    ))]
+    Study(StudyArgs),
+    /// Show per-configuration trends from a results database written by `bench --store`
+    History(HistoryArgs),
+    /// Combine results files from several machines into a cross-machine comparison
+    Merge(MergeArgs),
+    /// Test whether two configurations' throughput differs significantly on the same data
+    Compare(CompareArgs),
+    /// List the registered compression backends and their levels
+    Backends,
+    /// Measure how multithreaded compression throughput scales with thread count
+    Scaling(ScalingArgs),
+    /// Compress fixed-size chunks in parallel with any backend and report the ratio lost
+    #[cfg(feature = "parallel")]
+    Chunked(ChunkedArgs),
+    /// Feed each streaming encoder fixed-size writes instead of one whole buffer
+    Streaming(StreamingArgs),
+    /// Compare growing, worst-case-sized and reused output buffers per backend
+    #[cfg(all(
+        feature = "gzip",
+        feature = "lz4",
+        feature = "lz4-flex",
+        feature = "zstd"
+    ))]
+    Buffers,
+    /// Measure per-call latency percentiles for small payloads across all backends
+    Latency(LatencyArgs),
+    /// Run N independent compression streams at once and report how each backend scales
+    Contention(ContentionArgs),
+    /// Compare per-record compression with and without dictionaries against batching
+    #[cfg(feature = "zstd")]
+    Dictionaries(DictionariesArgs),
+    /// Benchmark only decompression of existing .gz, .lz4 and .zst files
+    Decompress(DecompressArgs),
+    /// Compress a continuous stream for a fixed time per configuration and sample throughput
+    Soak(SoakArgs),
+    /// Build minimal probe binaries per backend and report added binary size and compile time
+    Footprint(FootprintArgs),
+    /// Compare building a deflate or zstd context per message with resetting one kept context
+    #[cfg(all(feature = "gzip", feature = "zstd"))]
+    Reuse(ReuseArgs),
+    /// Time constructing each encoder and decoder apart from the compression work it does
+    #[cfg(all(
+        feature = "gzip",
+        feature = "lz4",
+        feature = "lz4-flex",
+        feature = "zstd"
+    ))]
+    Setup(SetupArgs),
+    /// Time flushing each backend's streaming encoder mid-stream and the ratio cost of flushing
+    Flush(FlushArgs),
+    /// Split streamed compression time into write() calls and the final finish()
+    Phases(PhasesArgs),
+    /// Build lz4_flex with and without its safe-encode/safe-decode features and compare them
+    #[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+    Lz4FlexSafety(Lz4FlexSafetyArgs),
+    /// Compare throughput with inputs in the CPU caches against inputs evicted before each call
+    Cache(CacheArgs),
+    /// Overlap producing blocks with compressing them through a bounded channel
+    Ingest(IngestArgs),
+    /// Serve a results file as Prometheus gauges for a scheduled runner to be scraped
+    Metrics(MetricsArgs),
+    /// Push a results file to an OpenTelemetry collector as OTLP metrics
+    #[cfg(feature = "otlp")]
+    Otlp(OtlpArgs),
+    /// Serve on-demand comparisons over HTTP: POST a payload or a suite config, get JSON results
+    Serve(ServeArgs),
+    /// Compress protobuf-encoded gRPC messages one by one per grpc-encoding, by message size
+    #[cfg(all(feature = "gzip", feature = "zstd"))]
+    Grpc(GrpcArgs),
+    /// Simulate a web server compressing HTML, JSON and CSS responses on the fly
+    #[cfg(all(feature = "gzip", feature = "zstd"))]
+    ContentEncoding(ContentEncodingArgs),
+    /// Compress record batches per batch.size with Kafka's compression types
+    #[cfg(all(feature = "gzip", feature = "lz4", feature = "zstd"))]
+    Kafka(KafkaArgs),
+    /// Compress Parquet-sized pages of each column type with Parquet's codecs
+    #[cfg(all(feature = "gzip", feature = "lz4-flex", feature = "zstd"))]
+    Columnar(ColumnarArgs),
+    /// Compress slotted B-tree pages one at a time, as a storage engine does
+    Pages(PagesArgs),
+    /// Compress a dataset as independent multipart-upload parts and report the ratio lost
+    #[cfg(feature = "parallel")]
+    Multipart(MultipartArgs),
+    /// tar a directory and stream the archive through each compressor, timing the whole pipeline
+    Tar(TarArgs),
+    /// Time random-range reads from seekable blocked formats and the ratio their blocking costs
+    #[cfg(all(feature = "gzip", feature = "lz4", feature = "zstd"))]
+    Seekable(SeekableArgs),
+    /// Serialize a batch of events with serde_json, bincode and prost, then compress each
+    Serialize(SerializeArgs),
+    /// Append records to a long-lived compressed log with group-commit flushes and rotation
+    Wal(WalArgs),
+    /// Price a results file's codecs by end-to-end transfer time and cost at given link speeds
+    Cost(CostArgs),
+    /// Send compressed blocks through a rate-limited link and time completion per codec
+    Transfer(TransferArgs),
+    /// Round-trip arbitrary and adversarial inputs through every backend, reporting any failure
+    Fuzz(FuzzArgs),
+    /// Check that each format's implementations decode one another's output
+    Interop(InteropArgs),
+    /// Damage compressed outputs and report how each decoder fails and how soon it notices
+    Corrupt(CorruptArgs),
+    /// Decode highly expansive inputs with and without an output size limit
+    Bomb(BombArgs),
+    /// Record or check digests of each configuration's compressed output for fixed inputs
+    Digests(DigestsArgs),
+    /// Measure what integrity checks cost: format checksums on and off, and external digests
+    #[cfg(all(
+        feature = "gzip",
+        feature = "lz4",
+        feature = "lz4-flex",
+        feature = "zstd"
+    ))]
+    Checksums(ChecksumsArgs),
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Attach a key=value label to the run (repeatable)
+    #[arg(long = "label", value_name = "KEY=VALUE", value_parser = parse_label)]
+    labels: Vec<(String, String)>,
+
+    /// Attach a free-form note to the run (repeatable)
+    #[arg(long = "note", value_name = "TEXT")]
+    notes: Vec<String>,
+
+    /// Save the results as JSON
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Disable ANSI colors in result tables (also honors NO_COLOR)
+    #[arg(long)]
+    no_color: bool,
+
+    /// Also print spread, percentile, memory and counter columns under each result table
+    #[arg(long)]
+    detail: bool,
+
+    /// Show results filling in live in a terminal dashboard
+    #[arg(long)]
+    tui: bool,
+
+    /// Untimed iterations per cell before measuring, to warm caches, allocator and CPU clocks
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_WARMUP_ITERATIONS)]
+    warmup: usize,
+
+    /// Leave trials outside the Tukey fences out of means, spread and confidence intervals
+    #[arg(long)]
+    exclude_outliers: bool,
+
+    /// Flag cells whose trial-to-trial coefficient of variation exceeds this fraction as noisy
+    #[arg(long, value_name = "CV", default_value_t = noise::DEFAULT_NOISY_CV)]
+    noisy_cv: f64,
+
+    /// Pin the benchmark to this CPU core (Linux only)
+    #[arg(long, value_name = "CORE")]
+    pin_core: Option<usize>,
+
+    /// Also benchmark the codec exported by this shared library (repeatable)
+    #[cfg(feature = "plugins")]
+    #[arg(long = "plugin", value_name = "LIBRARY")]
+    plugins: Vec<PathBuf>,
 
-        TestData::Mixed => {
-            let mut data = Vec::with_capacity(DATA_SIZE);
-            let mut rng = thread_rng();
+    /// Also benchmark the multithreaded backends (zstd workers, parallel gzip) at N threads
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    threads: Option<u64>,
+
+    /// Also benchmark the system gzip, zstd and xz binaries, where installed, for reference
+    #[arg(long)]
+    system_tools: bool,
+
+    /// Decompress every output and byte-compare it with the original, failing cells that differ
+    #[arg(long)]
+    verify: bool,
+
+    /// Round-trip a small input through every configuration first and stop if any is broken
+    #[arg(long)]
+    self_test: bool,
+
+    /// Order of trials within a data type
+    #[arg(long, value_enum, default_value = "interleaved")]
+    schedule: Schedule,
+
+    /// Whether inputs start each timed call in the CPU caches (see the `cache` subcommand)
+    #[arg(long, value_enum, default_value = "warm")]
+    cache: CacheState,
+
+    /// Append the run, with git commit, crate versions and machine info, to a SQLite database
+    #[arg(long, value_name = "FILE")]
+    store: Option<PathBuf>,
+
+    /// Push the results to this OpenTelemetry collector's OTLP/HTTP metrics endpoint
+    #[cfg(feature = "otlp")]
+    #[arg(long, value_name = "URL")]
+    otlp_endpoint: Option<String>,
+
+    /// Dump every individual trial to FILE (CSV if it ends in .csv, JSON Lines otherwise)
+    #[arg(long, value_name = "FILE")]
+    samples: Option<PathBuf>,
+
+    /// Winner-summary objective (repeatable): fastest-with-ratio[=X], best-ratio-within-ms[=MS], most-efficient[=READS], fastest-decompression
+    #[arg(long = "objective", value_name = "OBJECTIVE", value_parser = parse_objective)]
+    objectives: Vec<Objective>,
+
+    /// Save the results as a named baseline
+    #[arg(long, value_name = "NAME")]
+    save_baseline: Option<String>,
+
+    /// Compare the results against a previously saved baseline
+    #[arg(long, value_name = "NAME")]
+    compare_baseline: Option<String>,
+
+    /// Directory holding saved baselines
+    #[arg(long, value_name = "DIR", default_value = "target/baselines")]
+    baseline_dir: PathBuf,
+
+    /// Fail the comparison if compression or decompression time grows by more than PCT
+    #[arg(long, value_name = "PCT", default_value_t = 10.0)]
+    fail_time_pct: f64,
+
+    /// Fail the comparison if the compression ratio drops by more than PCT
+    #[arg(long, value_name = "PCT", default_value_t = 1.0)]
+    fail_ratio_pct: f64,
+}
+
+#[derive(Args)]
+struct PipelineArgs {
+    /// Size of each compressed block in KB
+    #[arg(
+        long,
+        default_value_t = 1024,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    block_kb: usize,
+
+    /// Bandwidth of the simulated sink in MB/s
+    #[arg(long, default_value_t = 100.0, value_parser = pipeline::parse_bandwidth)]
+    sink_mbps: f64,
+}
+
+#[derive(Args)]
+struct RealisticArgs {
+    /// Where realistic mode writes the compressed output
+    #[arg(long, value_enum, default_value = "file")]
+    sink: SinkKind,
+}
+
+#[derive(Args)]
+struct TimingArgs {
+    /// Clock used for the measurements
+    #[arg(long, value_enum, default_value = "auto")]
+    clock: ClockChoice,
+}
+
+#[derive(Args)]
+struct RecordsArgs {
+    /// Number of JSON records to write
+    #[arg(
+        long,
+        default_value_t = 10_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    records: usize,
+
+    /// Records per frame in the batched framing
+    #[arg(
+        long,
+        default_value_t = 64,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    batch: usize,
+
+    /// Random single-record reads to time per framing
+    #[arg(
+        long,
+        default_value_t = 1_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    lookups: usize,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// Earlier results file
+    old: PathBuf,
+
+    /// Later results file
+    new: PathBuf,
+
+    /// Highlight changes larger than PCT in either direction
+    #[arg(long, value_name = "PCT", default_value_t = 5.0)]
+    threshold_pct: f64,
+
+    /// Disable ANSI colors (also honors NO_COLOR)
+    #[arg(long)]
+    no_color: bool,
+}
+
+#[cfg(all(feature = "lz4-flex", feature = "zstd"))]
+#[derive(Args)]
+struct FallbackArgs {
+    /// Time zstd may spend on a message before the chain falls back to lz4
+    #[arg(long, value_name = "US", default_value_t = 500)]
+    budget_us: u64,
+
+    /// Busy threads competing for CPU in the pressure run
+    #[arg(long, default_value_t = thread::available_parallelism().map_or(1, |n| n.get()))]
+    pressure_threads: usize,
+}
+
+#[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+#[derive(Args)]
+struct AccelerationArgs {
+    /// Acceleration factors to measure, comma separated (1-65537)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = clap::value_parser!(i32).range(1..=65537),
+        default_values_t = acceleration::DEFAULT_ACCELERATIONS
+    )]
+    accelerations: Vec<i32>,
+
+    /// Write the speed/ratio curve to FILE
+    #[arg(long, value_name = "FILE")]
+    plot: Option<PathBuf>,
+
+    /// Image format for --plot
+    #[arg(long, value_enum, default_value = "svg")]
+    plot_format: PlotFormat,
+}
+
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+#[derive(Args)]
+struct StudyArgs {
+    /// Workload to study
+    #[arg(long, value_enum, default_value = "json8k")]
+    preset: study::Preset,
+
+    /// Write the narrative to FILE as well as stdout
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct HistoryArgs {
+    /// SQLite database written by `bench --store`
+    db: PathBuf,
+
+    /// Only show this algorithm (e.g. Gzip, LZ4-Flex)
+    #[arg(long)]
+    algorithm: Option<String>,
+
+    /// Only show this data type (e.g. Random, Mixed)
+    #[arg(long)]
+    data_type: Option<String>,
+
+    /// Only show runs recorded with this key=value label (repeatable; all must match)
+    #[arg(long = "label", value_name = "KEY=VALUE", value_parser = parse_label)]
+    labels: Vec<(String, String)>,
+
+    /// Most recent runs to show per configuration
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// Results file produced by `bench --output`
+    input: PathBuf,
+
+    /// Strip hostname, labels and notes, and reduce the CPU model to its family
+    #[arg(long)]
+    anonymized: bool,
+
+    /// Where to write the exported results
+    #[arg(long, short, value_name = "FILE")]
+    output: PathBuf,
+}
+
+#[derive(Args)]
+struct AggregateArgs {
+    /// Contributed (anonymized) results files
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Write the markdown tables here instead of stdout
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
 
-            while data.len() < DATA_SIZE {
-                if rng.gen_bool(0.3) {
-                    data.extend_from_slice(b"HelloWorld");
-                } else {
-                    data.push(rng.sample(Alphanumeric) as u8);
-                }
+#[derive(Args)]
+struct CompareArgs {
+    /// First configuration as BACKEND[:LEVEL], e.g. gzip:fast (see `backends`)
+    #[arg(value_parser = compare::parse_config)]
+    a: &'static dyn Compressor,
+
+    /// Second configuration as BACKEND[:LEVEL]
+    #[arg(value_parser = compare::parse_config)]
+    b: &'static dyn Compressor,
+
+    /// Kind of data both configurations compress
+    #[arg(long, value_enum, default_value = "mixed")]
+    data_type: TestData,
+
+    /// Size of each trial's buffer in MB
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_mb: usize,
+
+    /// Trials per configuration, run alternately
+    #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(3..))]
+    trials: u64,
+
+    /// Throughput to test
+    #[arg(long, value_enum, default_value = "compress")]
+    metric: compare::Metric,
+
+    /// Significance level, between 0 and 1
+    #[arg(long, default_value_t = 0.05, value_parser = compare::parse_alpha)]
+    alpha: f64,
+}
+
+#[derive(Args)]
+struct MergeArgs {
+    /// Results files produced by `bench --output`, one per machine; the first is the reference
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Write the markdown report here instead of stdout
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+#[derive(Args)]
+struct ContextsArgs {
+    /// Highest thread count for the concurrency sweep (doubling from 1)
+    #[arg(long, default_value_t = thread::available_parallelism().map_or(1, |n| n.get()))]
+    threads: usize,
+}
+
+#[derive(Args)]
+struct ScalingArgs {
+    /// Highest thread count for the sweep (doubling from 1)
+    #[arg(
+        long,
+        default_value_t = thread::available_parallelism().map_or(1, |n| n.get()),
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    threads: usize,
+}
+
+#[cfg(feature = "parallel")]
+#[derive(Args)]
+struct ChunkedArgs {
+    /// Configuration to chunk as BACKEND[:LEVEL] (repeatable; default every configuration)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Chunk size in KB (repeatable)
+    #[arg(
+        long = "chunk-kb",
+        value_name = "KB",
+        default_values_t = chunking::DEFAULT_CHUNK_KB,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    chunk_kb: Vec<usize>,
+
+    /// Threads the chunks are compressed on
+    #[arg(
+        long,
+        default_value_t = thread::available_parallelism().map_or(1, |n| n.get()),
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    threads: usize,
+}
+
+#[derive(Args)]
+struct StreamingArgs {
+    /// Configuration to stream as BACKEND[:LEVEL] (repeatable; default every configuration)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Size of each write to the encoder in KB (repeatable)
+    #[arg(
+        long = "write-kb",
+        value_name = "KB",
+        default_values_t = streaming::DEFAULT_WRITE_KB,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    write_kb: Vec<usize>,
+}
+
+#[derive(Args)]
+struct LatencyArgs {
+    /// Configuration to measure as BACKEND[:LEVEL] (repeatable; default every configuration)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Payload size in bytes (repeatable)
+    #[arg(
+        long = "payload",
+        value_name = "BYTES",
+        default_values_t = latency::DEFAULT_PAYLOADS,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    payloads: Vec<usize>,
+
+    /// Timed calls per configuration and payload size
+    #[arg(
+        long,
+        default_value_t = 5_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1_000..)
+    )]
+    calls: usize,
+}
+
+#[derive(Args)]
+struct ContentionArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default each backend's Default level)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Highest number of concurrent streams (doubling from 1)
+    #[arg(
+        long,
+        default_value_t = thread::available_parallelism().map_or(1, |n| n.get()),
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    streams: usize,
+}
+
+#[cfg(feature = "zstd")]
+#[derive(Args)]
+struct DictionariesArgs {
+    /// Records compressed per strategy
+    #[arg(
+        long,
+        default_value_t = 10_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    records: usize,
+
+    /// Records per unit for the batching strategies
+    #[arg(
+        long,
+        default_value_t = 100,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    batch: usize,
+
+    /// Dictionary size in KB
+    #[arg(
+        long,
+        default_value_t = 16,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    dict_kb: usize,
+}
+
+#[derive(Args)]
+struct DecompressArgs {
+    /// Compressed files; the format is detected from their magic bytes
+    #[arg(required = true, value_name = "FILE")]
+    files: Vec<PathBuf>,
+
+    /// Timed decompressions per file
+    #[arg(
+        long,
+        default_value_t = 10,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    trials: usize,
+}
+
+#[derive(Args)]
+struct SoakArgs {
+    /// Configuration to soak as BACKEND[:LEVEL] (repeatable; default each backend's Default level)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Wall-clock time per configuration, e.g. 10m or 90s
+    #[arg(long, default_value = "10m", value_parser = humantime::parse_duration)]
+    duration: Duration,
+
+    /// Time between throughput samples
+    #[arg(long, default_value = "10s", value_parser = humantime::parse_duration)]
+    interval: Duration,
+}
+
+#[derive(Args)]
+struct FootprintArgs {
+    /// Directory the probe crates are written and built in
+    #[arg(long, default_value = "target/footprint")]
+    dir: PathBuf,
+
+    /// Pass --offline to cargo when building the probes
+    #[arg(long)]
+    offline: bool,
+}
+
+#[cfg(all(feature = "gzip", feature = "zstd"))]
+#[derive(Args)]
+struct ReuseArgs {
+    /// Payload size in bytes (repeatable)
+    #[arg(
+        long = "payload",
+        value_name = "BYTES",
+        default_values_t = reuse::DEFAULT_PAYLOADS,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    payloads: Vec<usize>,
+
+    /// Messages compressed per cell
+    #[arg(
+        long,
+        default_value_t = 10_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    messages: usize,
+}
+
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+#[derive(Args)]
+struct SetupArgs {
+    /// Payload size in bytes (repeatable)
+    #[arg(
+        long = "payload",
+        value_name = "BYTES",
+        default_values_t = setup::DEFAULT_PAYLOADS,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    payloads: Vec<usize>,
+
+    /// Constructions timed per encoder or decoder
+    #[arg(
+        long,
+        default_value_t = 2_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    iterations: usize,
+}
+
+#[derive(Args)]
+struct FlushArgs {
+    /// Flush after every N messages (repeatable)
+    #[arg(
+        long,
+        value_name = "N",
+        default_values_t = flushing::DEFAULT_EVERY,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    every: Vec<usize>,
+
+    /// JSON records streamed through each encoder
+    #[arg(
+        long,
+        default_value_t = 10_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    messages: usize,
+}
+
+#[derive(Args)]
+struct PhasesArgs {
+    /// Configuration to measure as BACKEND[:LEVEL] (repeatable; default every configuration)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Input size in bytes (repeatable)
+    #[arg(
+        long = "payload",
+        value_name = "BYTES",
+        default_values_t = phases::DEFAULT_PAYLOADS,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    payloads: Vec<usize>,
+
+    /// Size of each write() handed to the encoder, in KB
+    #[arg(
+        long,
+        default_value_t = 64,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    write_kb: usize,
+}
+
+#[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+#[derive(Args)]
+struct Lz4FlexSafetyArgs {
+    /// Directory the probe crates are written and built in
+    #[arg(long, default_value = "target/lz4-flex-safety")]
+    dir: PathBuf,
+
+    /// Timed round trips per build and data type
+    #[arg(
+        long,
+        default_value_t = 10,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    trials: usize,
+
+    /// Pass --offline to cargo when building the probes
+    #[arg(long)]
+    offline: bool,
+}
+
+#[derive(Args)]
+struct CacheArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default each backend's Default level)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Input size in KB (repeatable)
+    #[arg(
+        long = "size-kb",
+        value_name = "KB",
+        default_values_t = cold_cache::DEFAULT_SIZE_KB,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_kb: Vec<usize>,
+
+    /// Timed round trips per configuration, size and cache state
+    #[arg(
+        long,
+        default_value_t = 10,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    trials: usize,
+}
+
+#[derive(Args)]
+struct IngestArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default each backend's Default level)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Read blocks from this file instead of generating mixed data
+    #[arg(long, value_name = "FILE")]
+    input: Option<PathBuf>,
+
+    /// Generated data per configuration in MB, when no --input is given
+    #[arg(
+        long,
+        default_value_t = 64,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_mb: usize,
+
+    /// Size of each produced block in KB
+    #[arg(
+        long,
+        default_value_t = 1024,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    block_kb: usize,
+
+    /// Blocks the channel holds before the producer has to wait
+    #[arg(
+        long,
+        default_value_t = 4,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    depth: usize,
+}
+
+#[derive(Args)]
+struct MetricsArgs {
+    /// Results file produced by `bench --output`; re-read on every scrape
+    input: PathBuf,
+
+    /// Address to serve /metrics on
+    #[arg(long, default_value = "127.0.0.1:9898")]
+    listen: String,
+}
+
+#[cfg(feature = "otlp")]
+#[derive(Args)]
+struct OtlpArgs {
+    /// Results file produced by `bench --output`
+    input: PathBuf,
+
+    /// OTLP/HTTP metrics endpoint of the collector
+    #[arg(long, default_value = otlp::DEFAULT_ENDPOINT)]
+    endpoint: String,
+}
+
+#[derive(Args)]
+struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: String,
+
+    /// Largest payload, and largest suite data_size, a request may ask for in MB
+    #[arg(
+        long,
+        default_value_t = 64,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    max_payload_mb: usize,
+
+    /// Timed round trips per configuration for POST /compare, unless the request sets trials=N
+    #[arg(
+        long,
+        default_value_t = 10,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    trials: usize,
+
+    /// Most trials, or warmup iterations in a POSTed suite, a request may ask for
+    #[arg(
+        long,
+        default_value_t = 1000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    max_trials: usize,
+}
+
+#[cfg(all(feature = "gzip", feature = "zstd"))]
+#[derive(Args)]
+struct GrpcArgs {
+    /// Message size in bytes (repeatable)
+    #[arg(
+        long = "size",
+        value_name = "BYTES",
+        default_values_t = grpc::DEFAULT_SIZES,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    sizes: Vec<usize>,
+
+    /// Messages compressed per size and encoding
+    #[arg(
+        long,
+        default_value_t = 1000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    messages: usize,
+}
+
+#[cfg(all(feature = "gzip", feature = "zstd"))]
+#[derive(Args)]
+struct ContentEncodingArgs {
+    /// Responses compressed per content type and encoding
+    #[arg(
+        long,
+        default_value_t = 200,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    requests: usize,
+
+    /// Size of each chunk the body is written and flushed in, in KB
+    #[arg(
+        long,
+        default_value_t = 8,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    chunk_kb: usize,
+}
+
+#[cfg(all(feature = "gzip", feature = "lz4", feature = "zstd"))]
+#[derive(Args)]
+struct KafkaArgs {
+    /// Producer batch.size in KB (repeatable)
+    #[arg(
+        long = "batch-kb",
+        value_name = "KB",
+        default_values_t = kafka::DEFAULT_BATCH_KB,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    batch_kb: Vec<usize>,
+
+    /// Records produced, then batched at each batch.size
+    #[arg(
+        long,
+        default_value_t = 100_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    records: usize,
+}
+
+#[cfg(all(feature = "gzip", feature = "lz4-flex", feature = "zstd"))]
+#[derive(Args)]
+struct ColumnarArgs {
+    /// Page size in KB
+    #[arg(
+        long,
+        default_value_t = 1024,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    page_kb: usize,
+
+    /// Pages compressed per column type and codec
+    #[arg(
+        long,
+        default_value_t = 8,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pages: usize,
+}
+
+#[derive(Args)]
+struct PagesArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default every configuration)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Page size in KB (repeatable)
+    #[arg(
+        long = "page-kb",
+        value_name = "KB",
+        default_values_t = pages::DEFAULT_PAGE_KB,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=64)
+    )]
+    page_kb: Vec<usize>,
+
+    /// Pages compressed per size and configuration
+    #[arg(
+        long,
+        default_value_t = 2000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pages: usize,
+
+    /// How full each page is with rows; the rest is zeroed free space
+    #[arg(
+        long,
+        default_value_t = 90,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=100)
+    )]
+    fill_pct: usize,
+}
+
+#[cfg(feature = "parallel")]
+#[derive(Args)]
+struct MultipartArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default each backend's Default level)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Dataset size in MB
+    #[arg(
+        long,
+        default_value_t = 128,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_mb: usize,
+
+    /// Uncompressed part size in MB (repeatable)
+    #[arg(
+        long = "part-mb",
+        value_name = "MB",
+        default_values_t = multipart::DEFAULT_PART_MB,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    part_mb: Vec<usize>,
+
+    /// Threads the parts are compressed on
+    #[arg(
+        long,
+        default_value_t = thread::available_parallelism().map_or(1, |n| n.get()),
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    threads: usize,
+}
+
+#[derive(Args)]
+struct TarArgs {
+    /// Directory to archive
+    dir: PathBuf,
+
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default each backend's Default level)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Size of each write into the streaming encoder in KB
+    #[arg(
+        long,
+        default_value_t = 64,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    write_kb: usize,
+}
+
+#[cfg(all(feature = "gzip", feature = "lz4", feature = "zstd"))]
+#[derive(Args)]
+struct SeekableArgs {
+    /// Uncompressed data size in MB
+    #[arg(
+        long,
+        default_value_t = 1024,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_mb: usize,
+
+    /// Uncompressed frame size in KB for zstd seekable and lz4 (BGZF blocks are fixed)
+    #[arg(
+        long,
+        default_value_t = 1024,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    frame_kb: usize,
+
+    /// Size of each random read in KB
+    #[arg(
+        long,
+        default_value_t = 64,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    read_kb: usize,
+
+    /// Random reads per format
+    #[arg(
+        long,
+        default_value_t = 1000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    reads: usize,
+}
+
+#[derive(Args)]
+struct SerializeArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default each backend's Default level)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Events in the serialized batch
+    #[arg(
+        long,
+        default_value_t = 10_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    records: usize,
+
+    /// Trials per pair; the median is reported
+    #[arg(
+        long,
+        default_value_t = 5,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    trials: usize,
+}
+
+#[derive(Args)]
+struct WalArgs {
+    /// Records appended to the log
+    #[arg(
+        long,
+        default_value_t = 200_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    records: usize,
+
+    /// Flush after every N records, as a group commit does
+    #[arg(
+        long,
+        default_value_t = 16,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    flush_every: usize,
+
+    /// Rotate to a new stream after this many uncompressed MB
+    #[arg(
+        long,
+        default_value_t = 8,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    rotate_mb: usize,
+}
+
+#[derive(Args)]
+struct CostArgs {
+    /// Results file produced by `bench --output`
+    input: PathBuf,
+
+    /// Link bandwidth in Mbit/s (repeatable)
+    #[arg(
+        long = "bandwidth-mbit",
+        value_name = "MBIT",
+        default_values_t = cost::DEFAULT_BANDWIDTH_MBIT,
+        value_parser = pipeline::parse_bandwidth
+    )]
+    bandwidths: Vec<f64>,
+
+    /// Price of one CPU core for an hour, in dollars
+    #[arg(long, value_name = "DOLLARS", default_value_t = 0.04)]
+    cpu_per_hour: f64,
+
+    /// Price of sending one GB out of the network, in dollars
+    #[arg(long, value_name = "DOLLARS", default_value_t = 0.09)]
+    egress_per_gb: f64,
+}
+
+#[derive(Args)]
+struct TransferArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default each backend's Default level)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Link bandwidth in Mbit/s (repeatable)
+    #[arg(
+        long = "bandwidth-mbit",
+        value_name = "MBIT",
+        default_values_t = cost::DEFAULT_BANDWIDTH_MBIT,
+        value_parser = pipeline::parse_bandwidth
+    )]
+    bandwidths: Vec<f64>,
+
+    /// Data sent per configuration in MB
+    #[arg(
+        long,
+        default_value_t = 16,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_mb: usize,
+
+    /// Size of each compressed block in KB
+    #[arg(
+        long,
+        default_value_t = 256,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    block_kb: usize,
+}
+
+#[derive(Args)]
+struct FuzzArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default every configuration)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Inputs to generate
+    #[arg(
+        long,
+        default_value_t = 1000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    iterations: usize,
+
+    /// Largest generated input in KB
+    #[arg(
+        long,
+        default_value_t = 256,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    max_size_kb: usize,
+
+    /// Seed for the input generator, printed on every run (default random)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Write every input that failed a round trip into this directory
+    #[arg(long, value_name = "DIR")]
+    save_dir: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct InteropArgs {
+    /// Size of each data type's input in KB
+    #[arg(
+        long,
+        default_value_t = 1024,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_kb: usize,
+}
+
+#[derive(Args)]
+struct CorruptArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default every configuration)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Size of the compressed input in KB
+    #[arg(
+        long,
+        default_value_t = 256,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_kb: usize,
+
+    /// Damaged copies per configuration and mutation
+    #[arg(
+        long,
+        default_value_t = 200,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    trials: usize,
+}
+
+#[derive(Args)]
+struct BombArgs {
+    /// Configuration to run as BACKEND[:LEVEL] (repeatable; default every backend's Default level)
+    #[arg(
+        long = "algorithm",
+        value_name = "BACKEND[:LEVEL]",
+        value_parser = compare::parse_config
+    )]
+    algorithms: Vec<&'static dyn Compressor>,
+
+    /// Size the bomb expands to in MB
+    #[arg(
+        long,
+        default_value_t = 256,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_mb: usize,
+
+    /// Output limit for the bounded decode in MB
+    #[arg(
+        long,
+        default_value_t = 16,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    limit_mb: usize,
+}
+
+#[derive(Args)]
+struct DigestsArgs {
+    /// Size of each seeded input in KB (a check uses the recorded size)
+    #[arg(
+        long,
+        default_value_t = 256,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_kb: usize,
+
+    /// Seed for the input generator (a check uses the recorded seed)
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+
+    /// Write the digests to this JSON file
+    #[arg(long, value_name = "FILE")]
+    save: Option<PathBuf>,
+
+    /// Compare against digests saved earlier and fail if any output changed
+    #[arg(long, value_name = "FILE")]
+    check: Option<PathBuf>,
+}
+
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+#[derive(Args)]
+struct ChecksumsArgs {
+    /// Size of the input in MB
+    #[arg(
+        long,
+        default_value_t = 64,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    size_mb: usize,
+
+    /// Timed trials per variant; the median is reported
+    #[arg(
+        long,
+        default_value_t = 5,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    trials: usize,
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    /// Results file produced by `bench --output`
+    input: PathBuf,
+
+    /// Write a self-contained HTML report
+    #[arg(long, value_name = "FILE")]
+    html: Option<PathBuf>,
+
+    /// Write ratio-vs-speed and throughput-by-size plots per data type into DIR
+    #[arg(long, value_name = "DIR")]
+    plots: Option<PathBuf>,
+
+    /// Image format for --plots
+    #[arg(long, value_enum, default_value = "svg")]
+    plot_format: PlotFormat,
+
+    /// Write the results as github-action-benchmark custom JSON
+    #[arg(long, value_name = "FILE")]
+    github_benchmark: Option<PathBuf>,
+
+    /// Which github-action-benchmark custom tool --github-benchmark targets
+    #[arg(long, value_enum, default_value = "bigger")]
+    github_benchmark_direction: gh_benchmark::Direction,
+
+    /// Winner-summary objective (repeatable), as for `bench --objective`
+    #[arg(long = "objective", value_name = "OBJECTIVE", value_parser = parse_objective)]
+    objectives: Vec<Objective>,
+}
+
+// `try_timed` for the fixed codecs of the side studies, where a failure is a bug
+fn timed(codec_call: impl FnOnce() -> io::Result<Vec<u8>>) -> (Vec<u8>, f64) {
+    try_timed(codec_call).expect("codec failed on benchmark data")
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Bench(args)) => run_bench(*args),
+        Some(Command::Report(args)) => run_report(args),
+        Some(Command::Quick) => {
+            quick::run();
+            Ok(())
+        }
+        Some(Command::Export(args)) => run_export(args),
+        Some(Command::Diff(args)) => run_diff(args),
+        #[cfg(all(
+            feature = "gzip",
+            feature = "lz4",
+            feature = "lz4-flex",
+            feature = "zstd"
+        ))]
+        Some(Command::Study(args)) => run_study(args),
+        Some(Command::History(args)) => {
+            let filter = store::HistoryFilter {
+                algorithm: args.algorithm,
+                data_type: args.data_type,
+                labels: args.labels,
+                limit: args.limit,
+            };
+            Ok(store::print_history(&args.db, &filter)?)
+        }
+        #[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+        Some(Command::Acceleration(args)) => {
+            acceleration::run(&args.accelerations, args.plot.as_deref(), args.plot_format)
+        }
+        #[cfg(all(feature = "lz4-flex", feature = "zstd"))]
+        Some(Command::Fallback(args)) => {
+            fallback::run(args.budget_us, args.pressure_threads);
+            Ok(())
+        }
+        Some(Command::Aggregate(args)) => run_aggregate(args),
+        Some(Command::Merge(args)) => run_merge(args),
+        Some(Command::Backends) => {
+            println!("\n{:<10} {:<10} Levels", "Backend", "Name");
+            println!("{}", "-".repeat(40));
+            for backend in backends::REGISTRY {
+                let levels: Vec<String> = backend
+                    .levels
+                    .iter()
+                    .map(|codec| codec.level().to_lowercase())
+                    .collect();
+                println!(
+                    "{:<10} {:<10} {}",
+                    backend.id,
+                    backend.levels[0].name(),
+                    levels.join(", ")
+                );
             }
-            data.truncate(DATA_SIZE);
-            data
+            Ok(())
+        }
+        Some(Command::Compare(args)) => {
+            compare::run(&compare::CompareOptions {
+                a: args.a,
+                b: args.b,
+                data_type: args.data_type,
+                size_mb: args.size_mb,
+                trials: args.trials as usize,
+                metric: args.metric,
+                alpha: args.alpha,
+            });
+            Ok(())
+        }
+        Some(Command::Scaling(args)) => scaling::run(args.threads),
+        #[cfg(all(
+            feature = "gzip",
+            feature = "lz4",
+            feature = "lz4-flex",
+            feature = "zstd"
+        ))]
+        Some(Command::Checksums(args)) => checksums::run(args.size_mb, args.trials),
+        Some(Command::Digests(args)) => digests::run(
+            args.size_kb,
+            args.seed,
+            args.save.as_deref(),
+            args.check.as_deref(),
+        ),
+        Some(Command::Bomb(args)) => {
+            let algorithms: Vec<&'static dyn Compressor> = if args.algorithms.is_empty() {
+                backends::REGISTRY
+                    .iter()
+                    .filter_map(|backend| backend.level("Default"))
+                    .collect()
+            } else {
+                args.algorithms
+            };
+            bombs::run(&algorithms, args.size_mb, args.limit_mb)
+        }
+        Some(Command::Corrupt(args)) => {
+            corruption::run(&args.algorithms, args.size_kb, args.trials)
+        }
+        Some(Command::Interop(args)) => interoperability::run(args.size_kb),
+        Some(Command::Fuzz(args)) => fuzz::run(
+            &args.algorithms,
+            args.iterations,
+            args.max_size_kb,
+            args.seed,
+            args.save_dir.as_deref(),
+        ),
+        Some(Command::Transfer(args)) => transfer::run(
+            &args.algorithms,
+            &args.bandwidths,
+            args.size_mb,
+            args.block_kb,
+        ),
+        Some(Command::Cost(args)) => cost::run(
+            &args.input,
+            &args.bandwidths,
+            &cost::Prices {
+                cpu_per_hour: args.cpu_per_hour,
+                egress_per_gb: args.egress_per_gb,
+            },
+        ),
+        Some(Command::Wal(args)) => wal::run(args.records, args.flush_every, args.rotate_mb),
+        Some(Command::Serialize(args)) => {
+            serialization::run(&args.algorithms, args.records, args.trials)
+        }
+        #[cfg(all(feature = "gzip", feature = "lz4", feature = "zstd"))]
+        Some(Command::Seekable(args)) => {
+            seekable::run(args.size_mb, args.frame_kb, args.read_kb, args.reads)
+        }
+        Some(Command::Tar(args)) => tarball::run(&args.dir, &args.algorithms, args.write_kb),
+        #[cfg(feature = "parallel")]
+        Some(Command::Multipart(args)) => {
+            multipart::run(&args.algorithms, args.size_mb, &args.part_mb, args.threads)
+        }
+        Some(Command::Pages(args)) => {
+            pages::run(&args.algorithms, &args.page_kb, args.pages, args.fill_pct)
+        }
+        #[cfg(all(feature = "gzip", feature = "lz4-flex", feature = "zstd"))]
+        Some(Command::Columnar(args)) => columnar::run(args.page_kb, args.pages),
+        #[cfg(all(feature = "gzip", feature = "lz4", feature = "zstd"))]
+        Some(Command::Kafka(args)) => kafka::run(&args.batch_kb, args.records),
+        #[cfg(all(feature = "gzip", feature = "zstd"))]
+        Some(Command::ContentEncoding(args)) => content_encoding::run(args.requests, args.chunk_kb),
+        #[cfg(all(feature = "gzip", feature = "zstd"))]
+        Some(Command::Grpc(args)) => grpc::run(&args.sizes, args.messages),
+        Some(Command::Serve(args)) => serve::run(
+            &args.listen,
+            args.max_payload_mb * 1024 * 1024,
+            args.trials,
+            args.max_trials,
+        ),
+        #[cfg(feature = "otlp")]
+        Some(Command::Otlp(args)) => {
+            otlp::export(&RunResults::load(&args.input)?, &args.endpoint)?;
+            println!("Exported {} to {}", args.input.display(), args.endpoint);
+            Ok(())
+        }
+        Some(Command::Metrics(args)) => prometheus::serve(&args.input, &args.listen),
+        Some(Command::Ingest(args)) => {
+            let source = match args.input {
+                Some(path) => ingest::Source::File(path),
+                None => ingest::Source::Generated {
+                    total: args.size_mb * 1024 * 1024,
+                },
+            };
+            ingest::run(&args.algorithms, source, args.block_kb * 1024, args.depth)
+        }
+        Some(Command::Cache(args)) => cold_cache::run(&args.algorithms, &args.size_kb, args.trials),
+        #[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+        Some(Command::Lz4FlexSafety(args)) => safety::run(&args.dir, args.trials, args.offline),
+        Some(Command::Phases(args)) => phases::run(&args.algorithms, &args.payloads, args.write_kb),
+        Some(Command::Flush(args)) => flushing::run(&args.every, args.messages),
+        #[cfg(all(
+            feature = "gzip",
+            feature = "lz4",
+            feature = "lz4-flex",
+            feature = "zstd"
+        ))]
+        Some(Command::Setup(args)) => setup::run(&args.payloads, args.iterations),
+        #[cfg(all(feature = "gzip", feature = "zstd"))]
+        Some(Command::Reuse(args)) => reuse::run(&args.payloads, args.messages),
+        Some(Command::Footprint(args)) => footprint::run(&args.dir, args.offline),
+        Some(Command::Soak(args)) => soak::run(&args.algorithms, args.duration, args.interval),
+        Some(Command::Decompress(args)) => decompress_only::run(&args.files, args.trials),
+        #[cfg(feature = "zstd")]
+        Some(Command::Dictionaries(args)) => {
+            dictionaries::run(args.records, args.batch, args.dict_kb);
+            Ok(())
+        }
+        Some(Command::Contention(args)) => contention::run(&args.algorithms, args.streams),
+        Some(Command::Latency(args)) => latency::run(&args.algorithms, &args.payloads, args.calls),
+        #[cfg(all(
+            feature = "gzip",
+            feature = "lz4",
+            feature = "lz4-flex",
+            feature = "zstd"
+        ))]
+        Some(Command::Buffers) => Ok(buffers::run()?),
+        Some(Command::Streaming(args)) => streaming::run(&args.algorithms, &args.write_kb),
+        #[cfg(feature = "parallel")]
+        Some(Command::Chunked(args)) => {
+            chunking::run(&args.algorithms, &args.chunk_kb, args.threads)
+        }
+        #[cfg(all(
+            feature = "gzip",
+            feature = "lz4",
+            feature = "lz4-flex",
+            feature = "zstd"
+        ))]
+        Some(Command::Contexts(args)) => {
+            contexts::run(args.threads);
+            Ok(())
         }
+        Some(Command::Warmup) => {
+            warmup::run();
+            Ok(())
+        }
+        Some(Command::Pipeline(args)) => {
+            pipeline::run(args.block_kb * 1024, args.sink_mbps);
+            Ok(())
+        }
+        Some(Command::Realistic(args)) => Ok(realistic::run(args.sink)?),
+        Some(Command::Timing(args)) => Ok(timing::run(args.clock)?),
+        Some(Command::Records(args)) => {
+            records::run(args.records, args.batch, args.lookups);
+            Ok(())
+        }
+        None => run_bench(cli.bench),
     }
 }
 
-// Gzip Compression
-fn gzip_compression(data: &[u8], level: Compression) -> (Vec<u8>, f64) {
-    let start = Instant::now();
-    let mut encoder = GzEncoder::new(Vec::new(), level);
-    encoder.write_all(data).unwrap();
-    let compressed = encoder.finish().unwrap();
-    let duration = start.elapsed().as_secs_f64();
-    (compressed, duration)
+fn objectives_or_default(objectives: &[Objective]) -> &[Objective] {
+    if objectives.is_empty() {
+        &DEFAULT_OBJECTIVES
+    } else {
+        objectives
+    }
 }
 
-// LZ4-Flex Compression
-fn lz4_flex_compression(data: &[u8]) -> (Vec<u8>, f64) {
-    let start = Instant::now();
-    let compressed = compress_prepend_size(data);
-    let duration = start.elapsed().as_secs_f64();
-    (compressed, duration)
+fn run_export(args: ExportArgs) -> Result<(), Box<dyn Error>> {
+    let results = RunResults::load(&args.input)?;
+    let exported = if args.anonymized {
+        community::anonymize(&results)
+    } else {
+        results
+    };
+    exported.save(&args.output)?;
+    println!("Exported results to {}", args.output.display());
+    Ok(())
 }
 
-// LZ4-RS Compression (lz4 crate) with Different Levels
-fn lz4_rs_compression(data: &[u8], level: u32) -> (Vec<u8>, f64) {
-    let start = Instant::now();
-    let mut encoder = EncoderBuilder::new()
-        .level(level) // LZ4 compression level (0-16)
-        .build(Vec::new())
-        .unwrap();
-    encoder.write_all(data).unwrap();
-    let (compressed, result) = encoder.finish();
-    result.unwrap();
-    let duration = start.elapsed().as_secs_f64();
-    (compressed, duration)
+#[cfg(all(
+    feature = "gzip",
+    feature = "lz4",
+    feature = "lz4-flex",
+    feature = "zstd"
+))]
+fn run_study(args: StudyArgs) -> Result<(), Box<dyn Error>> {
+    let narrative = study::run(args.preset);
+    println!("\n=== Summary ===\n\n{}", narrative);
+    if let Some(path) = &args.output {
+        fs::write(path, format!("{}\n", narrative))?;
+        println!("\nWrote summary to {}", path.display());
+    }
+    Ok(())
 }
 
-// Struct to Store Benchmark Results
-#[derive(Default)]
-struct CompressionStats {
-    factor_sum: f64,
-    time_sum: f64,
-    size_sum: usize,
+fn run_diff(args: DiffArgs) -> Result<(), Box<dyn Error>> {
+    let old = RunResults::load(&args.old)?;
+    let new = RunResults::load(&args.new)?;
+    let color = !args.no_color && std::env::var_os("NO_COLOR").is_none();
+    diff::print(&old, &new, args.threshold_pct, color);
+    Ok(())
 }
 
-fn main() {
-    let compression_levels = [
-        ("Fast", Compression::fast()),
-        ("Default", Compression::default()),
-        ("Best", Compression::best()),
-    ];
+fn run_aggregate(args: AggregateArgs) -> Result<(), Box<dyn Error>> {
+    let runs = args
+        .inputs
+        .iter()
+        .map(|path| RunResults::load(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let markdown = community::aggregate_markdown(&runs);
+    match &args.output {
+        Some(path) => {
+            fs::write(path, markdown)?;
+            println!("Wrote aggregate tables to {}", path.display());
+        }
+        None => print!("{}", markdown),
+    }
+    Ok(())
+}
 
-    let lz4_rs_levels = [("Fast", 0), ("Default", 4), ("Best", 16)];
+fn run_merge(args: MergeArgs) -> Result<(), Box<dyn Error>> {
+    let runs = args
+        .inputs
+        .iter()
+        .map(|path| RunResults::load(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let markdown = merge::comparison_markdown(&runs);
+    match &args.output {
+        Some(path) => {
+            fs::write(path, markdown)?;
+            println!("Wrote cross-machine comparison to {}", path.display());
+        }
+        None => print!("{}", markdown),
+    }
+    Ok(())
+}
 
-    let test_cases = [
-        ("Random", TestData::Random),
-        ("Repeating", TestData::Repeating),
-        ("Mixed", TestData::Mixed),
-    ];
+fn run_report(args: ReportArgs) -> Result<(), Box<dyn Error>> {
+    let results = RunResults::load(&args.input)?;
+    pareto::print_analysis(&results);
+    ranking::print_summary(&results, objectives_or_default(&args.objectives));
+    if let Some(path) = &args.html {
+        fs::write(path, html::render(&results))?;
+        println!("Wrote HTML report to {}", path.display());
+    }
+    if let Some(dir) = &args.plots {
+        plots::render_all(&results, dir, args.plot_format)?;
+        println!("Wrote plots to {}", dir.display());
+    }
+    if let Some(path) = &args.github_benchmark {
+        gh_benchmark::write(&results, path, args.github_benchmark_direction)?;
+        println!("Wrote github-action-benchmark data to {}", path.display());
+    }
+    Ok(())
+}
 
-    println!(
-        "\nRunning compression benchmarks ({} trials of {}MB data)...\n",
-        NUM_TRIALS,
-        DATA_SIZE / 1024 / 1024
-    );
+fn run_bench(args: BenchArgs) -> Result<(), Box<dyn Error>> {
+    #[cfg(feature = "plugins")]
+    let plugins = args
+        .plugins
+        .iter()
+        .map(|path| plugin::load(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    #[cfg(not(feature = "plugins"))]
+    let plugins: Vec<&'static dyn Compressor> = Vec::new();
+    let mut tools = Vec::new();
+    if args.system_tools {
+        for tool in &system::TOOLS {
+            if tool.is_installed() {
+                tools.push(tool as &dyn Compressor);
+            } else {
+                println!("Skipping {}: not found on PATH", tool.program());
+            }
+        }
+    }
+    let multithreaded = match args.threads {
+        Some(threads) => backends::multithreaded(threads as usize)?,
+        None => Vec::new(),
+    };
+    let suite = BenchmarkSuite::builder()
+        .algorithms(
+            backends::configurations()
+                .chain(multithreaded)
+                .chain(plugins)
+                .chain(tools),
+        )
+        .warmup(args.warmup)
+        .exclude_outliers(args.exclude_outliers)
+        .schedule(args.schedule)
+        .noisy_cv(args.noisy_cv)
+        .verify(args.verify)
+        .cache(args.cache)
+        .build();
+    let mut results = RunResults {
+        metadata: RunMetadata::new(args.labels, args.notes),
+        cells: Vec::new(),
+        failures: Vec::new(),
+    };
+    if let Some(core) = args.pin_core {
+        cpu::pin_current_thread(core)
+            .map_err(|e| format!("could not pin to core {}: {}", core, e))?;
+        results.metadata.cpu.pinned_core = Some(core);
+    }
+    results.metadata.verified = suite.verifies();
+    results.metadata.cache = suite.cache();
 
-    for (data_name, data_type) in &test_cases {
-        println!("=== {} Data ===", data_name);
+    if args.self_test {
+        suite
+            .self_test()
+            .map_err(|e| format!("self-test failed, benchmark not started: {}", e))?;
+        println!("Self-test passed: every configuration round-tripped every data type");
+    }
 
-        // GZip Benchmarks
-        for (level_name, level) in &compression_levels {
-            let mut stats = CompressionStats::default();
+    results.metadata.calibration = suite.calibrate()?;
+    let calibration = results.metadata.calibration;
 
-            for _ in 0..NUM_TRIALS {
-                let data = generate_test_data(data_type);
-                let original_size = data.len();
-                let (compressed, duration) = gzip_compression(&data, *level);
-                let compressed_size = compressed.len();
+    let color = !args.no_color && std::env::var_os("NO_COLOR").is_none();
 
-                let factor = original_size as f64 / compressed_size as f64;
-                stats.factor_sum += factor;
-                stats.time_sum += duration;
-                stats.size_sum += compressed_size;
-            }
+    println!(
+        "\nRunning compression benchmarks ({} trials of {}MB data, {} warmup iterations per cell)...\n",
+        suite.trials(),
+        suite.data_size() / 1024 / 1024,
+        args.warmup
+    );
+    results.metadata.print_header();
+    calibration.print();
 
-            println!(
-                "\n--- Gzip {} ---\nCompression Factor: {:.2}x | Time: {:.3}s | Avg Size: {:.2}MB",
-                level_name,
-                stats.factor_sum / NUM_TRIALS as f64,
-                stats.time_sum / NUM_TRIALS as f64,
-                (stats.size_sum / NUM_TRIALS) as f64 / (1024.0 * 1024.0)
-            );
-        }
-
-        // LZ4-Flex Benchmarks
-        let mut stats = CompressionStats::default();
-        for _ in 0..NUM_TRIALS {
-            let data = generate_test_data(data_type);
-            let original_size = data.len();
-            let (compressed, duration) = lz4_flex_compression(&data);
-            let compressed_size = compressed.len();
-
-            let factor = original_size as f64 / compressed_size as f64;
-            stats.factor_sum += factor;
-            stats.time_sum += duration;
-            stats.size_sum += compressed_size;
-        }
-
-        println!(
-            "\n--- LZ4-Flex Compression ({} data) ---\nCompression Factor: {:.2}x | Time: {:.3}s | Avg Size: {:.2}MB",
-            data_name,
-            stats.factor_sum / NUM_TRIALS as f64,
-            stats.time_sum / NUM_TRIALS as f64,
-            (stats.size_sum / NUM_TRIALS) as f64 / (1024.0 * 1024.0)
-        );
-
-        // LZ4-RS Benchmarks at Multiple Levels
-        for (level_name, level) in &lz4_rs_levels {
-            let mut stats = CompressionStats::default();
-            for _ in 0..NUM_TRIALS {
-                let data = generate_test_data(data_type);
-                let original_size = data.len();
-                let (compressed, duration) = lz4_rs_compression(&data, *level);
-                let compressed_size = compressed.len();
-
-                let factor = original_size as f64 / compressed_size as f64;
-                stats.factor_sum += factor;
-                stats.time_sum += duration;
-                stats.size_sum += compressed_size;
+    if args.tui {
+        let (sender, receiver) = mpsc::channel();
+        let total = suite.cell_count();
+        let worker = thread::spawn(move || {
+            let mut failures = Vec::new();
+            for (data_name, data_type) in suite.data() {
+                suite.run_data_type(data_name, data_type, calibration, |cell| match cell {
+                    // Only fails if the dashboard itself errored out
+                    Ok(cell) => {
+                        let _ = sender.send(cell);
+                    }
+                    Err(failure) => failures.push(failure),
+                });
+            }
+            failures
+        });
+        results.cells = tui::run(receiver, total)?;
+        results.failures = worker.join().expect("benchmark thread panicked");
+        for (data_name, _) in &TEST_CASES {
+            let cells: Vec<CellResult> = results
+                .cells
+                .iter()
+                .filter(|c| c.data_type == *data_name)
+                .cloned()
+                .collect();
+            table::print_cells(data_name, &cells, color);
+            if args.detail {
+                table::print_detail(&cells, color);
+            }
+            table::print_failures(data_name, &results.failures);
+        }
+    } else {
+        for (data_name, data_type) in suite.data() {
+            let first_cell = results.cells.len();
+            suite.run_data_type(data_name, data_type, calibration, |cell| match cell {
+                Ok(cell) => results.cells.push(cell),
+                Err(failure) => results.failures.push(failure),
+            });
+            table::print_cells(data_name, &results.cells[first_cell..], color);
+            if args.detail {
+                table::print_detail(&results.cells[first_cell..], color);
             }
+            table::print_failures(data_name, &results.failures);
+        }
+    }
 
-            println!(
-                "\n--- LZ4-RS {} Compression ({} data) ---\nCompression Factor: {:.2}x | Time: {:.3}s | Avg Size: {:.2}MB",
-                level_name,
-                data_name,
-                stats.factor_sum / NUM_TRIALS as f64,
-                stats.time_sum / NUM_TRIALS as f64,
-                (stats.size_sum / NUM_TRIALS) as f64 / (1024.0 * 1024.0)
-            );
+    pareto::print_analysis(&results);
+    ranking::print_summary(&results, objectives_or_default(&args.objectives));
+
+    if let Some(path) = &args.output {
+        results.save(path)?;
+        println!("\nSaved results to {}", path.display());
+    }
+    if let Some(path) = &args.store {
+        let run_id = store::append(path, &results)?;
+        println!("\nStored run #{} in {}", run_id, path.display());
+    }
+    #[cfg(feature = "otlp")]
+    if let Some(endpoint) = &args.otlp_endpoint {
+        otlp::export(&results, endpoint)?;
+        println!("\nExported results to {}", endpoint);
+    }
+    if let Some(path) = &args.samples {
+        samples::write(path, &results.cells)?;
+        println!("\nWrote per-trial samples to {}", path.display());
+    }
+    if let Some(name) = &args.save_baseline {
+        let path = baseline::save(&results, &args.baseline_dir, name)?;
+        println!("\nSaved baseline `{}` to {}", name, path.display());
+    }
+    if let Some(name) = &args.compare_baseline {
+        let previous = baseline::load(&args.baseline_dir, name)?;
+        println!("\n=== Comparison against baseline `{}` ===", name);
+        let thresholds = Thresholds {
+            time_pct: args.fail_time_pct,
+            ratio_pct: args.fail_ratio_pct,
+        };
+        let regressions =
+            baseline::print_comparison(&baseline::compare(&previous, &results), &thresholds);
+        if regressions > 0 {
+            return Err(format!(
+                "{} cell(s) regressed against baseline `{}`",
+                regressions, name
+            )
+            .into());
         }
     }
+    Ok(())
 }
@@ -0,0 +1,206 @@
+// Memory a codec needs: peak resident memory of the process around a piece of work (Linux
+// only: the kernel's high-water mark is reset through /proc/self/clear_refs and read back from
+// /proc/self/status), and with the `count-allocations` feature, heap allocations it makes
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+// The allocator every allocation ends up in, counted or not; jemalloc wins if both are enabled
+#[cfg(all(feature = "mimalloc", not(feature = "jemalloc")))]
+use mimalloc::MiMalloc as Base;
+#[cfg(not(any(feature = "jemalloc", feature = "mimalloc")))]
+#[allow(unused_imports)]
+use std::alloc::System as Base;
+#[cfg(feature = "jemalloc")]
+use tikv_jemallocator::Jemalloc as Base;
+
+// Name of the global allocator compiled in, as recorded in run metadata
+pub const ALLOCATOR: &str = if cfg!(feature = "jemalloc") {
+    "jemalloc"
+} else if cfg!(feature = "mimalloc") {
+    "mimalloc"
+} else {
+    "system"
+};
+
+#[cfg(all(
+    any(feature = "jemalloc", feature = "mimalloc"),
+    not(feature = "count-allocations")
+))]
+#[global_allocator]
+static GLOBAL: Base = Base;
+
+// Peak resident memory a cell's codec added on top of what was already resident, in bytes;
+// the largest seen across trials. `None` where the platform can't measure it
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PeakRss {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compress_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decompress_bytes: Option<u64>,
+}
+
+impl PeakRss {
+    pub fn record(&mut self, compress: Option<u64>, decompress: Option<u64>) {
+        let max = |current: Option<u64>, new: Option<u64>| match (current, new) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.compress_bytes = max(self.compress_bytes, compress);
+        self.decompress_bytes = max(self.decompress_bytes, decompress);
+    }
+
+    // "comp / decomp" in MB, or "-" where unmeasured
+    pub fn describe_mb(&self) -> String {
+        let mb = |bytes: Option<u64>| {
+            bytes.map_or("-".to_string(), |b| {
+                format!("{:.1}", b as f64 / (1024.0 * 1024.0))
+            })
+        };
+        format!(
+            "{} / {}",
+            mb(self.compress_bytes),
+            mb(self.decompress_bytes)
+        )
+    }
+}
+
+// A `VmRSS` or `VmHWM` line of /proc/self/status, in bytes
+fn status_bytes(field: &str) -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with(field))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+// Current resident set size, without touching the high-water mark
+pub fn resident_bytes() -> Option<u64> {
+    status_bytes("VmRSS:")
+}
+
+// Resets the high-water mark to the current RSS and returns that RSS as the baseline
+pub fn reset_peak() -> Option<u64> {
+    // Hand memory freed by earlier work back to the kernel first, otherwise a codec reusing it
+    // would look like it needed nothing
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    // SAFETY: malloc_trim only releases free heap pages
+    unsafe {
+        libc::malloc_trim(0);
+    }
+    fs::write("/proc/self/clear_refs", "5").ok()?;
+    status_bytes("VmRSS:")
+}
+
+// How far the high-water mark has risen above `baseline` since `reset_peak`
+pub fn peak_since(baseline: Option<u64>) -> Option<u64> {
+    Some(status_bytes("VmHWM:")?.saturating_sub(baseline?))
+}
+
+// Mean heap allocations per trial on the benchmark thread; `None` without `count-allocations`
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Allocations {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compress_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compress_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decompress_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decompress_bytes: Option<u64>,
+}
+
+impl Allocations {
+    // "count (MB)" for compression / decompression, or "-" where unmeasured
+    pub fn describe(&self) -> String {
+        let describe = |count: Option<u64>, bytes: Option<u64>| match (count, bytes) {
+            (Some(count), Some(bytes)) => {
+                format!("{} ({:.1})", count, bytes as f64 / (1024.0 * 1024.0))
+            }
+            _ => "-".to_string(),
+        };
+        format!(
+            "{} / {}",
+            describe(self.compress_count, self.compress_bytes),
+            describe(self.decompress_count, self.decompress_bytes)
+        )
+    }
+}
+
+// Allocations and bytes requested on one thread since it started
+#[derive(Clone, Copy, Default)]
+pub struct AllocationCount {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+impl AllocationCount {
+    pub fn since(self, earlier: AllocationCount) -> AllocationCount {
+        AllocationCount {
+            count: self.count - earlier.count,
+            bytes: self.bytes - earlier.bytes,
+        }
+    }
+}
+
+#[cfg(feature = "count-allocations")]
+mod counting {
+    use super::{AllocationCount, Base};
+    use std::alloc::{GlobalAlloc, Layout};
+    use std::cell::Cell;
+
+    thread_local! {
+        // Const-initialized and drop-free, so touching it from the allocator can't recurse
+        static COUNT: Cell<AllocationCount> = const {
+            Cell::new(AllocationCount { count: 0, bytes: 0 })
+        };
+    }
+
+    fn add(bytes: usize) {
+        // Fails only while the thread is being torn down; those allocations go uncounted
+        let _ = COUNT.try_with(|count| {
+            let mut current = count.get();
+            current.count += 1;
+            current.bytes += bytes as u64;
+            count.set(current);
+        });
+    }
+
+    // The base allocator, counting every allocation and reallocation per thread
+    pub struct CountingAllocator;
+
+    // SAFETY: every call is forwarded unchanged to the base allocator
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            add(layout.size());
+            Base.alloc(layout)
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            add(layout.size());
+            Base.alloc_zeroed(layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            add(new_size);
+            Base.realloc(ptr, layout, new_size)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            Base.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    pub fn current() -> AllocationCount {
+        COUNT.try_with(Cell::get).unwrap_or_default()
+    }
+}
+
+// This thread's running allocation count, when the counting allocator is compiled in
+pub fn allocation_count() -> Option<AllocationCount> {
+    #[cfg(feature = "count-allocations")]
+    return Some(counting::current());
+    #[cfg(not(feature = "count-allocations"))]
+    None
+}
@@ -0,0 +1,119 @@
+use crate::results::RunResults;
+use std::fmt::Write;
+
+// Short column name for a run: its hostname if recorded, otherwise its position, plus the
+// allocator when it isn't the system one so allocator builds on one host stay apart
+fn machine_name(index: usize, run: &RunResults) -> String {
+    let name = run
+        .metadata
+        .machine
+        .hostname
+        .clone()
+        .unwrap_or_else(|| format!("machine {}", index + 1));
+    match run.metadata.allocator.as_str() {
+        "system" | "" => name,
+        allocator => format!("{} ({})", name, allocator),
+    }
+}
+
+// Cross-machine markdown report: one legend, then per data type a row per configuration
+// with compression / decompression MB/s on every machine relative to the first
+pub fn comparison_markdown(runs: &[RunResults]) -> String {
+    let mut markdown = String::from("# Cross-Machine Compression Comparison\n\n## Machines\n\n");
+    markdown.push_str("| # | Host | CPU | Cores | OS/Arch | CPU settings | Allocator | Labels |\n");
+    markdown.push_str("|---:|---|---|---:|---|---|---|---|\n");
+    for (i, run) in runs.iter().enumerate() {
+        let machine = &run.metadata.machine;
+        let labels: Vec<String> = run
+            .metadata
+            .labels
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        writeln!(
+            markdown,
+            "| {} | {} | {} | {} | {}/{} | {} | {} | {} |",
+            i + 1,
+            machine_name(i, run),
+            machine.cpu_model,
+            machine.cores,
+            machine.os,
+            machine.arch,
+            run.metadata.cpu.describe(),
+            run.metadata.allocator,
+            labels.join(", ")
+        )
+        .unwrap();
+    }
+
+    let Some(first) = runs.first() else {
+        return markdown;
+    };
+    let mut data_types: Vec<&str> = Vec::new();
+    for run in runs {
+        for data_type in run.data_types() {
+            if !data_types.contains(&data_type) {
+                data_types.push(data_type);
+            }
+        }
+    }
+
+    for data_type in data_types {
+        writeln!(
+            markdown,
+            "\n## {} Data\n\nCompression / decompression MB/s, relative compression speed vs {}\n",
+            data_type,
+            machine_name(0, first)
+        )
+        .unwrap();
+        markdown.push_str("| Algorithm | Ratio |");
+        for (i, run) in runs.iter().enumerate() {
+            write!(markdown, " {} |", machine_name(i, run)).unwrap();
+        }
+        markdown.push_str("\n|---|---:|");
+        markdown.push_str(&"---:|".repeat(runs.len()));
+        markdown.push('\n');
+
+        let mut labels: Vec<(String, &str, &str)> = Vec::new();
+        for run in runs {
+            for cell in run.cells.iter().filter(|c| c.data_type == data_type) {
+                if !labels.iter().any(|(label, _, _)| *label == cell.label()) {
+                    labels.push((cell.label(), &cell.algorithm, &cell.level));
+                }
+            }
+        }
+        for (label, algorithm, level) in labels {
+            let cells: Vec<_> = runs
+                .iter()
+                .map(|run| {
+                    run.cells.iter().find(|c| {
+                        c.data_type == data_type && c.algorithm == algorithm && c.level == level
+                    })
+                })
+                .collect();
+            let ratio = cells.iter().flatten().next().map_or(0.0, |c| c.ratio);
+            let reference = cells[0].map(|c| c.compress_mbps());
+            write!(markdown, "| {} | {:.2}x |", label, ratio).unwrap();
+            for cell in &cells {
+                match cell {
+                    Some(cell) => {
+                        let relative = reference.filter(|r| *r > 0.0).map_or(String::new(), |r| {
+                            format!(" ({:.2}x)", cell.compress_mbps() / r)
+                        });
+                        write!(
+                            markdown,
+                            " {:.1} / {:.1}{} |",
+                            cell.compress_mbps(),
+                            cell.decompress_mbps(),
+                            relative
+                        )
+                        .unwrap();
+                    }
+                    None => markdown.push_str(" - |"),
+                }
+            }
+            markdown.push('\n');
+        }
+    }
+    markdown
+}
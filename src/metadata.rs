@@ -0,0 +1,80 @@
+use crate::cache::CacheState;
+use crate::cpu::CpuSettings;
+use crate::machine::MachineInfo;
+use crate::memory;
+use crate::noise::Calibration;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Free-form annotations and host details attached to a benchmark run
+#[derive(Default, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub labels: BTreeMap<String, String>,
+    pub notes: Vec<String>,
+    #[serde(default)]
+    pub machine: MachineInfo,
+    #[serde(default)]
+    pub cpu: CpuSettings,
+    #[serde(default)]
+    pub calibration: Calibration,
+    // Every output was decompressed and byte-compared with its input
+    #[serde(default)]
+    pub verified: bool,
+    // Whether inputs were evicted from the CPU caches before every timed call
+    #[serde(default)]
+    pub cache: CacheState,
+    // Global allocator the run was built with; runs from before this was recorded used the system one
+    #[serde(default = "system_allocator")]
+    pub allocator: String,
+}
+
+fn system_allocator() -> String {
+    "system".to_string()
+}
+
+impl RunMetadata {
+    pub fn new(labels: Vec<(String, String)>, notes: Vec<String>) -> Self {
+        RunMetadata {
+            labels: labels.into_iter().collect(),
+            notes,
+            machine: MachineInfo::detect(),
+            cpu: CpuSettings::detect(),
+            calibration: Calibration::default(),
+            verified: false,
+            cache: CacheState::Warm,
+            allocator: memory::ALLOCATOR.to_string(),
+        }
+    }
+
+    pub fn print_header(&self) {
+        println!(
+            "Machine: {} ({} cores, {}/{})",
+            self.machine.cpu_model, self.machine.cores, self.machine.os, self.machine.arch
+        );
+        println!("CPU: {}", self.cpu.describe());
+        println!("Allocator: {}", self.allocator);
+        if self.verified {
+            println!("Verification: every output is decompressed and compared with its input");
+        }
+        if self.cache == CacheState::Cold {
+            println!("Cache: inputs evicted from the CPU caches before every timed call");
+        }
+        for warning in self.cpu.warnings() {
+            println!("Warning: {}", warning);
+        }
+        for (key, value) in &self.labels {
+            println!("Label: {}={}", key, value);
+        }
+        for note in &self.notes {
+            println!("Note: {}", note);
+        }
+    }
+}
+
+// Parses a `key=value` label from the command line
+pub fn parse_label(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("invalid label `{}`, expected key=value", s)),
+    }
+}
@@ -0,0 +1,124 @@
+use crate::results::percentile;
+use crate::{generate_test_data, TestData};
+use rayon::prelude::*;
+use rust_compress_comparison::backends;
+use rust_compress_comparison::chunked;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::io;
+use std::time::Instant;
+
+pub const DEFAULT_PART_MB: [usize; 3] = [5, 8, 16];
+// S3 rejects every part but the last below this size
+const MIN_PART: usize = 5 * 1024 * 1024;
+const MB: f64 = 1024.0 * 1024.0;
+
+struct Upload {
+    secs: f64,
+    // Compressed size of every part, in order
+    parts: Vec<usize>,
+}
+
+impl Upload {
+    fn total(&self) -> usize {
+        self.parts.iter().sum()
+    }
+}
+
+fn upload(
+    pool: &rayon::ThreadPool,
+    codec: &dyn Compressor,
+    data: &[u8],
+    part_size: usize,
+) -> io::Result<Upload> {
+    let start = Instant::now();
+    let parts = pool.install(|| {
+        data.par_chunks(part_size)
+            .map(|part| codec.compress(part).map(|compressed| compressed.len()))
+            .collect::<io::Result<Vec<usize>>>()
+    })?;
+    Ok(Upload {
+        secs: start.elapsed().as_secs_f64(),
+        parts,
+    })
+}
+
+// A dataset split into multipart-upload parts, each compressed on its own across `threads`,
+// against compressing it as one stream
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    size_mb: usize,
+    part_mb: &[usize],
+    threads: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::REGISTRY
+            .iter()
+            .filter_map(|backend| backend.level("Default"))
+            .collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let pool = chunked::pool(threads)?;
+    let data = generate_test_data(&TestData::Mixed, size_mb * 1024 * 1024);
+    println!(
+        "\nMultipart upload parts ({}MB mixed data, parts compressed on {} threads)",
+        size_mb, threads
+    );
+
+    for codec in algorithms {
+        let single = upload(&pool, codec, &data, data.len())?;
+        let single_ratio = data.len() as f64 / single.total() as f64;
+        println!(
+            "\n=== {} {} (single stream {:.2}x in {:.2}s) ===\n",
+            codec.name(),
+            codec.level(),
+            single_ratio,
+            single.secs
+        );
+        println!(
+            "{:>9} {:>6} {:>7} {:>11} {:>8} {:>9} {:>22} {:>10}",
+            "Part",
+            "Parts",
+            "Ratio",
+            "Ratio loss",
+            "Wall s",
+            "MB/s",
+            "Part MB min/p50/max",
+            "Under 5MB"
+        );
+        println!("{}", "-".repeat(90));
+        for &mb in part_mb {
+            let multipart = upload(&pool, codec, &data, mb * 1024 * 1024)?;
+            let ratio = data.len() as f64 / multipart.total() as f64;
+            let mut sizes: Vec<f64> = multipart.parts.iter().map(|&n| n as f64).collect();
+            sizes.sort_by(f64::total_cmp);
+            // The last part is allowed to be small
+            let under_minimum = multipart.parts[..multipart.parts.len() - 1]
+                .iter()
+                .filter(|&&n| n < MIN_PART)
+                .count();
+            println!(
+                "{:>7}MB {:>6} {:>6.2}x {:>10.2}% {:>8.2} {:>9.1} {:>22} {:>10}",
+                mb,
+                multipart.parts.len(),
+                ratio,
+                (single_ratio - ratio) / single_ratio * 100.0,
+                multipart.secs,
+                data.len() as f64 / MB / multipart.secs,
+                format!(
+                    "{:.1} / {:.1} / {:.1}",
+                    sizes[0] / MB,
+                    percentile(&sizes, 0.5) / MB,
+                    sizes[sizes.len() - 1] / MB
+                ),
+                under_minimum
+            );
+        }
+    }
+    println!(
+        "\nParts are split before compression; Under 5MB counts non-final compressed parts below \
+         S3's minimum part size, which would have to be merged before upload"
+    );
+    Ok(())
+}
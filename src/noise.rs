@@ -0,0 +1,79 @@
+use crate::results::{mean_and_stddev, CellResult, DataQuality};
+use crate::timer::{ClockChoice, Timer};
+use crate::Instant;
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+
+const MEMCPY_SIZE: usize = 1024 * 1024 * 10; // 10MB
+const MEMCPY_ROUNDS: usize = 10;
+// Cells whose mean time is under this many timer overheads mostly measure the clock
+const TIMER_BOUND_FACTOR: f64 = 100.0;
+
+pub const DEFAULT_NOISY_CV: f64 = 0.10;
+
+// Machine noise floor measured before the run, and the threshold cells are judged against
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Calibration {
+    pub timer_overhead_ns: f64,
+    pub memcpy_mbps: f64,
+    // Coefficient of variation of the memcpy rounds: the best any cell can hope for
+    pub memcpy_cv: f64,
+    pub noisy_cv: f64,
+}
+
+impl Calibration {
+    pub fn measure(noisy_cv: f64) -> Result<Self, String> {
+        let timer = Timer::calibrate(ClockChoice::Monotonic)?;
+        let source = vec![0xA5u8; MEMCPY_SIZE];
+        let mut destination = vec![0u8; MEMCPY_SIZE];
+        // Fault in the destination pages before timing
+        destination.copy_from_slice(&source);
+        let mbps: Vec<f64> = (0..MEMCPY_ROUNDS)
+            .map(|_| {
+                let start = Instant::now();
+                destination.copy_from_slice(black_box(&source));
+                black_box(&mut destination);
+                MEMCPY_SIZE as f64 / (1024.0 * 1024.0) / start.elapsed().as_secs_f64()
+            })
+            .collect();
+        let (mean, stddev) = mean_and_stddev(&mbps);
+        Ok(Calibration {
+            timer_overhead_ns: timer.overhead_secs() * 1e9,
+            memcpy_mbps: mean,
+            memcpy_cv: stddev / mean,
+            noisy_cv,
+        })
+    }
+
+    pub fn print(&self) {
+        println!(
+            "Calibration: timer overhead {:.1} ns, memcpy {:.0} MB/s (CV {:.1}%), noisy above CV {:.0}%",
+            self.timer_overhead_ns,
+            self.memcpy_mbps,
+            self.memcpy_cv * 100.0,
+            self.noisy_cv * 100.0
+        );
+        if self.memcpy_cv > self.noisy_cv {
+            println!(
+                "Warning: memcpy alone varies more than the noise threshold; expect noisy cells"
+            );
+        }
+    }
+
+    // Timer-bound beats noisy: a spread around a clock-sized mean says nothing about the codec
+    pub fn assess(&self, cell: &CellResult) -> DataQuality {
+        let overhead_secs = self.timer_overhead_ns / 1e9;
+        let shortest = cell.compress_secs.min(cell.decompress_secs);
+        if shortest < overhead_secs * TIMER_BOUND_FACTOR {
+            return DataQuality::TimerBound;
+        }
+        let cv = |stddev: f64, mean: f64| if mean > 0.0 { stddev / mean } else { 0.0 };
+        let worst = cv(cell.spread.compress.stddev_secs, cell.compress_secs)
+            .max(cv(cell.spread.decompress.stddev_secs, cell.decompress_secs));
+        if worst > self.noisy_cv {
+            DataQuality::Noisy
+        } else {
+            DataQuality::Good
+        }
+    }
+}
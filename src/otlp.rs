@@ -0,0 +1,113 @@
+use crate::results::RunResults;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:4318/v1/metrics";
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn gauge(name: &str, unit: &str, description: &str, points: Vec<Value>) -> Value {
+    json!({
+        "name": name,
+        "unit": unit,
+        "description": description,
+        "gauge": { "dataPoints": points },
+    })
+}
+
+// The run as an OTLP/HTTP JSON ExportMetricsServiceRequest: one gauge data point per cell and
+// metric, with the host and run labels as resource attributes
+pub fn payload(results: &RunResults) -> Value {
+    // OTLP carries 64-bit integers as JSON strings
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos())
+        .to_string();
+    let machine = &results.metadata.machine;
+    let mut resource = vec![
+        attribute("service.name", env!("CARGO_PKG_NAME")),
+        attribute("service.version", env!("CARGO_PKG_VERSION")),
+        attribute("host.arch", &machine.arch),
+        attribute("os.type", &machine.os),
+        attribute("host.cpu.model.name", &machine.cpu_model),
+        attribute("compression.allocator", &results.metadata.allocator),
+        attribute("compression.cache", results.metadata.cache.name()),
+    ];
+    if let Some(hostname) = &machine.hostname {
+        resource.push(attribute("host.name", hostname));
+    }
+    for (key, value) in &results.metadata.labels {
+        resource.push(attribute(&format!("compression.label.{}", key), value));
+    }
+
+    let mut ratio = Vec::new();
+    let mut compress = Vec::new();
+    let mut decompress = Vec::new();
+    let mut compress_secs = Vec::new();
+    for cell in &results.cells {
+        let point = |value: f64| {
+            json!({
+                "attributes": [
+                    attribute("algorithm", &cell.algorithm),
+                    attribute("level", &cell.level),
+                    attribute("data_type", &cell.data_type),
+                ],
+                "timeUnixNano": time,
+                "asDouble": value,
+            })
+        };
+        ratio.push(point(cell.ratio));
+        compress.push(point(cell.compress_mbps()));
+        compress_secs.push(point(cell.compress_secs));
+        if cell.decompress_secs > 0.0 {
+            decompress.push(point(cell.decompress_mbps()));
+        }
+    }
+
+    json!({
+        "resourceMetrics": [{
+            "resource": { "attributes": resource },
+            "scopeMetrics": [{
+                "scope": { "name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION") },
+                "metrics": [
+                    gauge(
+                        "compression.ratio",
+                        "1",
+                        "Original size divided by compressed size",
+                        ratio,
+                    ),
+                    gauge(
+                        "compression.compress.throughput",
+                        "MBy/s",
+                        "Compression throughput in MB/s of input",
+                        compress,
+                    ),
+                    gauge(
+                        "compression.decompress.throughput",
+                        "MBy/s",
+                        "Decompression throughput in MB/s of output",
+                        decompress,
+                    ),
+                    gauge(
+                        "compression.compress.duration",
+                        "s",
+                        "Mean time to compress one input",
+                        compress_secs,
+                    ),
+                ],
+            }],
+        }],
+    })
+}
+
+// POSTs the run to an OTLP/HTTP collector, e.g. http://localhost:4318/v1/metrics
+pub fn export(results: &RunResults, endpoint: &str) -> Result<(), Box<dyn Error>> {
+    ureq::post(endpoint)
+        .header("Content-Type", "application/json")
+        .send(payload(results).to_string())
+        .map_err(|e| format!("OTLP export to {} failed: {}", endpoint, e))?;
+    Ok(())
+}
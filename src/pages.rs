@@ -0,0 +1,167 @@
+use crate::results::percentile;
+use crate::timer::{ClockChoice, Timer};
+use rand::{thread_rng, Rng};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+
+pub const DEFAULT_PAGE_KB: [usize; 3] = [4, 8, 16];
+// LSN, checksum, flags, lower and upper free-space offsets, special-space offset, right sibling
+const HEADER: usize = 32;
+const SLOT: usize = 4;
+const STATUSES: [&str; 4] = ["active", "pending", "shipped", "cancelled"];
+const REGIONS: [&str; 6] = [
+    "us-east",
+    "us-west",
+    "eu-central",
+    "eu-west",
+    "ap-south",
+    "ap-east",
+];
+
+// One row of an `orders` table as a heap tuple: a small header, fixed-width columns, then
+// length-prefixed variable-width ones
+fn row(rng: &mut impl Rng, id: u64) -> Vec<u8> {
+    let mut row = Vec::with_capacity(96);
+    row.extend_from_slice(&rng.gen::<u32>().to_le_bytes());
+    row.extend_from_slice(&0u32.to_le_bytes());
+    row.extend_from_slice(&id.to_le_bytes());
+    row.extend_from_slice(&rng.gen_range(1u32..50_000).to_le_bytes());
+    row.extend_from_slice(&(1_700_000_000 + id * 7 + rng.gen_range(0..5)).to_le_bytes());
+    row.extend_from_slice(&rng.gen_range(100i64..1_000_000).to_le_bytes());
+    let status = STATUSES[rng.gen_range(0..STATUSES.len())];
+    let region = REGIONS[rng.gen_range(0..REGIONS.len())];
+    let note = format!("order {} for customer in {}", id, region);
+    for text in [status, region, note.as_str()] {
+        row.push(text.len() as u8);
+        row.extend_from_slice(text.as_bytes());
+    }
+    row
+}
+
+// A slotted leaf page filled to `fill` with consecutive rows: header, slot array growing up from
+// the front, rows growing down from the back, zeroed free space between
+fn page(size: usize, fill: f64, first_id: u64) -> (Vec<u8>, u64) {
+    let mut rng = thread_rng();
+    let mut page = vec![0u8; size];
+    let budget = (size as f64 * fill) as usize;
+    let mut lower = HEADER;
+    let mut upper = size;
+    let mut id = first_id;
+    loop {
+        let row = row(&mut rng, id);
+        if lower + SLOT + row.len() > upper || size - (upper - lower) + row.len() + SLOT > budget {
+            break;
+        }
+        upper -= row.len();
+        page[upper..upper + row.len()].copy_from_slice(&row);
+        page[lower..lower + 2].copy_from_slice(&(upper as u16).to_le_bytes());
+        page[lower + 2..lower + 4].copy_from_slice(&(row.len() as u16).to_le_bytes());
+        lower += SLOT;
+        id += 1;
+    }
+    page[..8].copy_from_slice(&rng.gen::<u64>().to_le_bytes());
+    page[12..14].copy_from_slice(&(lower as u16).to_le_bytes());
+    page[14..16].copy_from_slice(&(upper as u16).to_le_bytes());
+    (page, id)
+}
+
+struct Measured {
+    ratio: f64,
+    // Share of pages that compress into half their size, as storage engines that store
+    // compressed pages in half-size slots need
+    fit_half: f64,
+    compress: Vec<f64>,
+    decompress: Vec<f64>,
+}
+
+fn measure(
+    timer: &Timer,
+    codec: &dyn Compressor,
+    pages: &[Vec<u8>],
+) -> Result<Measured, Box<dyn Error>> {
+    let mut original = 0;
+    let mut compressed_total = 0;
+    let mut fit_half = 0;
+    let mut compress = Vec::with_capacity(pages.len());
+    let mut decompress = Vec::with_capacity(pages.len());
+    for page in pages {
+        let (compressed, secs) = timer.time(|| codec.compress(page));
+        let compressed = compressed?;
+        compress.push(timer.corrected(secs));
+        let (restored, secs) = timer.time(|| codec.decompress(&compressed));
+        decompress.push(timer.corrected(secs));
+        if restored? != *page {
+            return Err(format!("{} {} round trip mismatch", codec.name(), codec.level()).into());
+        }
+        original += page.len();
+        compressed_total += compressed.len();
+        if compressed.len() <= page.len() / 2 {
+            fit_half += 1;
+        }
+    }
+    compress.sort_by(f64::total_cmp);
+    decompress.sort_by(f64::total_cmp);
+    Ok(Measured {
+        ratio: original as f64 / compressed_total as f64,
+        fit_half: fit_half as f64 / pages.len() as f64,
+        compress,
+        decompress,
+    })
+}
+
+// Page-at-a-time compression of slotted B-tree leaf pages, per page size
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    page_kb: &[usize],
+    page_count: usize,
+    fill_pct: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::configurations().collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let timer = Timer::calibrate(ClockChoice::Auto)?;
+    println!(
+        "\nDatabase page compression ({} pages per size, {}% full, {}; overhead {:.1} ns subtracted)",
+        page_count,
+        fill_pct,
+        timer.name(),
+        timer.overhead_secs() * 1e9
+    );
+    for &kb in page_kb {
+        let mut next_id = 1;
+        let pages: Vec<Vec<u8>> = (0..page_count)
+            .map(|_| {
+                let (page, id) = page(kb * 1024, fill_pct as f64 / 100.0, next_id);
+                next_id = id;
+                page
+            })
+            .collect();
+        println!("\n=== {}KB pages ===\n", kb);
+        println!(
+            "{:<18} {:>7} {:>9} {:>11} {:>11} {:>12} {:>12}",
+            "Configuration", "Ratio", "Fit 1/2", "Comp p50", "Comp p99", "Decomp p50", "Decomp p99"
+        );
+        println!("{}", "-".repeat(86));
+        for codec in &algorithms {
+            let label = format!("{} {}", codec.name(), codec.level());
+            match measure(&timer, *codec, &pages) {
+                Ok(measured) => println!(
+                    "{:<18} {:>6.2}x {:>8.0}% {:>9.2}us {:>9.2}us {:>10.2}us {:>10.2}us",
+                    label,
+                    measured.ratio,
+                    measured.fit_half * 100.0,
+                    percentile(&measured.compress, 0.5) * 1e6,
+                    percentile(&measured.compress, 0.99) * 1e6,
+                    percentile(&measured.decompress, 0.5) * 1e6,
+                    percentile(&measured.decompress, 0.99) * 1e6
+                ),
+                Err(e) => println!("{:<18} FAILED: {}", label, e),
+            }
+        }
+    }
+    println!("\nFit 1/2: pages whose compressed form fits in half a page");
+    Ok(())
+}
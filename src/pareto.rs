@@ -0,0 +1,78 @@
+use crate::results::{CellResult, RunResults};
+
+// A configuration's standing on one ratio-vs-time trade-off
+pub struct ParetoPoint<'a> {
+    pub cell: &'a CellResult,
+    pub dominated_by: Option<&'a CellResult>,
+}
+
+// Which timing a frontier trades ratio against
+pub type TimeAxis = fn(&CellResult) -> f64;
+
+// `a` dominates `b` when it is at least as good on both axes and better on one
+fn dominates(a: &CellResult, b: &CellResult, time: TimeAxis) -> bool {
+    let no_worse = a.ratio >= b.ratio && time(a) <= time(b);
+    let better = a.ratio > b.ratio || time(a) < time(b);
+    no_worse && better
+}
+
+// Classifies each cell as Pareto-optimal or dominated, fastest first
+pub fn frontier<'a>(cells: &[&'a CellResult], time: TimeAxis) -> Vec<ParetoPoint<'a>> {
+    let mut points: Vec<ParetoPoint> = cells
+        .iter()
+        .map(|&cell| ParetoPoint {
+            cell,
+            dominated_by: cells
+                .iter()
+                .copied()
+                .filter(|other| dominates(other, cell, time))
+                .max_by(|a, b| a.ratio.total_cmp(&b.ratio)),
+        })
+        .collect();
+    points.sort_by(|a, b| time(a.cell).total_cmp(&time(b.cell)));
+    points
+}
+
+pub fn print_analysis(results: &RunResults) {
+    let axes: [(&str, TimeAxis); 2] = [
+        ("compression time", |c| c.compress_secs),
+        ("decompression time", |c| c.decompress_secs),
+    ];
+
+    println!("\n=== Pareto Analysis ===");
+    for data_type in results.data_types() {
+        let cells: Vec<&CellResult> = results
+            .cells
+            .iter()
+            .filter(|c| c.data_type == data_type)
+            .collect();
+
+        for (axis, time) in axes {
+            // Results saved before decompression was timed have nothing to rank
+            if cells.iter().all(|c| time(c) == 0.0) {
+                continue;
+            }
+            println!("\n--- {} Data: ratio vs {} ---", data_type, axis);
+            let points = frontier(&cells, time);
+            for point in points.iter().filter(|p| p.dominated_by.is_none()) {
+                println!(
+                    "  optimal    {:<20} {:>7.2}x {:>9.3}s",
+                    point.cell.label(),
+                    point.cell.ratio,
+                    time(point.cell)
+                );
+            }
+            for point in &points {
+                if let Some(winner) = point.dominated_by {
+                    println!(
+                        "  dominated  {:<20} {:>7.2}x {:>9.3}s  (by {})",
+                        point.cell.label(),
+                        point.cell.ratio,
+                        time(point.cell),
+                        winner.label()
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::results::percentile;
+use crate::{generate_test_data, TestData};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::harness;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::time::Instant;
+
+pub const DEFAULT_PAYLOADS: [usize; 5] = [256, 4096, 64 * 1024, 1024 * 1024, 16 * 1024 * 1024];
+// Roughly this many input bytes per cell, so small payloads get enough calls to be stable
+const BYTES_PER_CELL: usize = 64 * 1024 * 1024;
+const MIN_CALLS: usize = 5;
+const MAX_CALLS: usize = 10_000;
+// Cells stop early once past MIN_CALLS and this long; high zstd levels set up a large window
+// per stream, which takes milliseconds even for tiny inputs
+const MAX_CELL_SECS: f64 = 2.0;
+
+// Median write and finish time of up to `calls` streamed compressions, and the compressed size
+fn measure(
+    codec: &dyn Compressor,
+    data: &[u8],
+    write_size: usize,
+    calls: usize,
+) -> Result<(f64, f64, usize), Box<dyn Error>> {
+    let (compressed, _) = codec.compress_phased(data, write_size)?;
+    harness::verify(codec, data, &compressed)?;
+    let mut writes = Vec::with_capacity(calls);
+    let mut finishes = Vec::with_capacity(calls);
+    let start = Instant::now();
+    for _ in 0..calls {
+        if writes.len() >= MIN_CALLS && start.elapsed().as_secs_f64() > MAX_CELL_SECS {
+            break;
+        }
+        let (_, phases) = codec.compress_phased(data, write_size)?;
+        writes.push(phases.write_secs);
+        finishes.push(phases.finish_secs);
+    }
+    writes.sort_by(f64::total_cmp);
+    finishes.sort_by(f64::total_cmp);
+    Ok((
+        percentile(&writes, 0.5),
+        percentile(&finishes, 0.5),
+        compressed.len(),
+    ))
+}
+
+// Time each streaming encoder spends in write() calls against the final finish(), per input size
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    payloads: &[usize],
+    write_kb: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::configurations().collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let write_size = write_kb * 1024;
+    let algorithms: Vec<&'static dyn Compressor> = algorithms
+        .into_iter()
+        .filter(|codec| match codec.compress_phased(&[], write_size) {
+            Ok(_) => true,
+            Err(e) => {
+                println!("Skipping {} {}: {}", codec.name(), codec.level(), e);
+                false
+            }
+        })
+        .collect();
+    if algorithms.is_empty() {
+        return Err("none of the selected configurations has a streaming encoder".into());
+    }
+
+    println!(
+        "\nStreaming write() vs finish() (mixed data, {}KB writes, median of each phase)",
+        write_kb
+    );
+    for &size in payloads {
+        let data = generate_test_data(&TestData::Mixed, size);
+        let calls = (BYTES_PER_CELL / size).clamp(MIN_CALLS, MAX_CALLS);
+        println!("\n=== {} byte input (up to {} calls) ===\n", size, calls);
+        println!(
+            "{:<18} {:>7} {:>12} {:>12} {:>13}",
+            "Configuration", "Ratio", "Write us", "Finish us", "Finish share"
+        );
+        println!("{}", "-".repeat(66));
+        for codec in &algorithms {
+            let label = format!("{} {}", codec.name(), codec.level());
+            match measure(*codec, &data, write_size, calls) {
+                Ok((write, finish, compressed)) => println!(
+                    "{:<18} {:>6.2}x {:>12.2} {:>12.2} {:>12.1}%",
+                    label,
+                    size as f64 / compressed as f64,
+                    write * 1e6,
+                    finish * 1e6,
+                    finish / (write + finish) * 100.0
+                ),
+                Err(e) => println!("{:<18} FAILED: {}", label, e),
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use crate::{backends, generate_test_data, try_timed, Compressor, TestData};
+use rust_compress_comparison::harness::compress_error;
+use rust_compress_comparison::CompressionBenchError;
+use std::io::{self, Write};
+use std::sync::mpsc::sync_channel;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const PIPELINE_DATA_SIZE: usize = 1024 * 1024 * 10; // 10MB
+
+// The Fast and Default levels of the backends built in
+fn pipeline_codecs() -> impl Iterator<Item = &'static dyn Compressor> {
+    backends::configurations().filter(|codec| matches!(codec.level().as_str(), "Fast" | "Default"))
+}
+
+// Parses a bandwidth argument, which must be a positive, finite rate
+pub fn parse_bandwidth(s: &str) -> Result<f64, String> {
+    let rate: f64 = s.parse().map_err(|e| format!("{}", e))?;
+    if rate.is_finite() && rate > 0.0 {
+        Ok(rate)
+    } else {
+        Err(format!("{} is not a positive bandwidth", s))
+    }
+}
+
+// Sink that throttles writes to a fixed bandwidth, standing in for disk or network
+pub struct RateLimitedWriter<W> {
+    inner: W,
+    bytes_per_sec: f64,
+    busy_until: Instant,
+}
+
+impl<W: Write> RateLimitedWriter<W> {
+    pub fn new(inner: W, bytes_per_sec: f64) -> Self {
+        assert!(
+            bytes_per_sec.is_finite() && bytes_per_sec > 0.0,
+            "bandwidth must be positive and finite"
+        );
+        RateLimitedWriter {
+            inner,
+            bytes_per_sec,
+            busy_until: Instant::now(),
+        }
+    }
+}
+
+impl<W: Write> Write for RateLimitedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // An idle link doesn't bank bandwidth: transmission starts no earlier than now
+        let n = self.inner.write(buf)?;
+        let transmit = Duration::from_secs_f64(n as f64 / self.bytes_per_sec);
+        self.busy_until = self.busy_until.max(Instant::now()) + transmit;
+        if let Some(wait) = self.busy_until.checked_duration_since(Instant::now()) {
+            thread::sleep(wait);
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct PipelineResult {
+    compress_secs: f64,
+    write_secs: f64,
+    sequential_secs: f64,
+    pipelined_secs: f64,
+}
+
+impl PipelineResult {
+    // Share of the theoretically hideable time that the pipeline actually hid
+    fn overlap(&self) -> f64 {
+        let hideable = self.compress_secs.min(self.write_secs);
+        if hideable <= 0.0 {
+            return 0.0;
+        }
+        ((self.sequential_secs - self.pipelined_secs) / hideable).clamp(0.0, 1.0)
+    }
+}
+
+// Compress a block, then write it, then move to the next. A failed write fails the cell like a
+// failed compression, since the sink carries the codec's output
+fn run_sequential(
+    blocks: &[&[u8]],
+    codec: &dyn Compressor,
+    bytes_per_sec: f64,
+) -> Result<(f64, f64, f64), CompressionBenchError> {
+    let mut sink = RateLimitedWriter::new(io::sink(), bytes_per_sec);
+    let mut compress_secs = 0.0;
+    let mut write_secs = 0.0;
+    let start = Instant::now();
+    for block in blocks {
+        let (compressed, duration) =
+            try_timed(|| codec.compress(block)).map_err(|e| compress_error(codec, e))?;
+        compress_secs += duration;
+        let write_start = Instant::now();
+        sink.write_all(&compressed)
+            .map_err(|e| compress_error(codec, e))?;
+        write_secs += write_start.elapsed().as_secs_f64();
+    }
+    Ok((compress_secs, write_secs, start.elapsed().as_secs_f64()))
+}
+
+// Compress block N+1 while a writer thread drains block N (two buffers in flight)
+fn run_pipelined(
+    blocks: &[&[u8]],
+    codec: &dyn Compressor,
+    bytes_per_sec: f64,
+) -> Result<f64, CompressionBenchError> {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        let (sender, receiver) = sync_channel::<Vec<u8>>(1);
+        let writer = scope.spawn(move || {
+            let mut sink = RateLimitedWriter::new(io::sink(), bytes_per_sec);
+            for compressed in receiver {
+                sink.write_all(&compressed)?;
+            }
+            Ok(())
+        });
+        for block in blocks {
+            let compressed = codec
+                .compress(block)
+                .map_err(|e| compress_error(codec, e))?;
+            // A closed channel means the writer failed; its error is collected below
+            if sender.send(compressed).is_err() {
+                break;
+            }
+        }
+        drop(sender);
+        writer
+            .join()
+            .expect("pipeline writer panicked")
+            .map_err(|e: io::Error| compress_error(codec, e))
+    })?;
+    Ok(start.elapsed().as_secs_f64())
+}
+
+// Both runs of one codec, or the error that stopped either
+fn measure(
+    blocks: &[&[u8]],
+    codec: &dyn Compressor,
+    bytes_per_sec: f64,
+) -> Result<PipelineResult, CompressionBenchError> {
+    let (compress_secs, write_secs, sequential_secs) =
+        run_sequential(blocks, codec, bytes_per_sec)?;
+    let pipelined_secs = run_pipelined(blocks, codec, bytes_per_sec)?;
+    Ok(PipelineResult {
+        compress_secs,
+        write_secs,
+        sequential_secs,
+        pipelined_secs,
+    })
+}
+
+pub fn run(block_size: usize, sink_mbps: f64) {
+    let bytes_per_sec = sink_mbps * 1024.0 * 1024.0;
+    let data = generate_test_data(&TestData::Mixed, PIPELINE_DATA_SIZE);
+    let blocks: Vec<&[u8]> = data.chunks(block_size).collect();
+
+    println!(
+        "\nPipelining benchmark ({}MB Mixed data, {}KB blocks, {:.0} MB/s sink)\n",
+        PIPELINE_DATA_SIZE / 1024 / 1024,
+        block_size / 1024,
+        sink_mbps
+    );
+    println!(
+        "{:<16} {:>10} {:>10} {:>12} {:>12} {:>8}",
+        "Algorithm", "Compress", "Write", "Sequential", "Pipelined", "Overlap"
+    );
+    println!("{}", "-".repeat(73));
+
+    for codec in pipeline_codecs() {
+        let name = format!("{} {}", codec.name(), codec.level());
+        let result = match measure(&blocks, codec, bytes_per_sec) {
+            Ok(result) => result,
+            Err(error) => {
+                println!("FAILED {}: {}", name, error);
+                continue;
+            }
+        };
+        println!(
+            "{:<16} {:>9.3}s {:>9.3}s {:>11.3}s {:>11.3}s {:>7.0}%",
+            name,
+            result.compress_secs,
+            result.write_secs,
+            result.sequential_secs,
+            result.pipelined_secs,
+            result.overlap() * 100.0
+        );
+    }
+}
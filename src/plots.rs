@@ -0,0 +1,315 @@
+use crate::results::{CellResult, RunResults};
+use clap::ValueEnum;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::error::Error;
+use std::path::Path;
+
+const PLOT_SIZE: (u32, u32) = (800, 600);
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum PlotFormat {
+    Svg,
+    Png,
+}
+
+impl PlotFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            PlotFormat::Svg => "svg",
+            PlotFormat::Png => "png",
+        }
+    }
+}
+
+// Writes a ratio-vs-speed scatter and a throughput-by-size chart per data type
+pub fn render_all(
+    results: &RunResults,
+    dir: &Path,
+    format: PlotFormat,
+) -> Result<(), Box<dyn Error>> {
+    std::fs::create_dir_all(dir)?;
+    for data_type in results.data_types() {
+        let cells: Vec<&CellResult> = results
+            .cells
+            .iter()
+            .filter(|c| c.data_type == data_type)
+            .collect();
+        let stem = data_type.to_lowercase().replace(' ', "_");
+        let scatter = dir.join(format!("{}_ratio_vs_speed.{}", stem, format.extension()));
+        let lines = dir.join(format!(
+            "{}_throughput_by_size.{}",
+            stem,
+            format.extension()
+        ));
+
+        match format {
+            PlotFormat::Svg => {
+                draw_scatter(
+                    SVGBackend::new(&scatter, PLOT_SIZE).into_drawing_area(),
+                    data_type,
+                    &cells,
+                )?;
+                draw_size_lines(
+                    SVGBackend::new(&lines, PLOT_SIZE).into_drawing_area(),
+                    data_type,
+                    &cells,
+                )?;
+            }
+            PlotFormat::Png => {
+                draw_scatter(
+                    BitMapBackend::new(&scatter, PLOT_SIZE).into_drawing_area(),
+                    data_type,
+                    &cells,
+                )?;
+                draw_size_lines(
+                    BitMapBackend::new(&lines, PLOT_SIZE).into_drawing_area(),
+                    data_type,
+                    &cells,
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn draw_scatter<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    data_type: &str,
+    cells: &[&CellResult],
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let max_ratio = cells.iter().map(|c| c.ratio).fold(1.0, f64::max) * 1.1;
+    let (min_speed, max_speed) = speed_range(cells.iter().map(|c| c.compress_mbps()));
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            format!("{} data: ratio vs compression speed", data_type),
+            ("sans-serif", 24),
+        )
+        .margin(15)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d((min_speed..max_speed).log_scale(), 0.0..max_ratio)?;
+    chart
+        .configure_mesh()
+        .x_desc("Compression MB/s")
+        .y_desc("Ratio")
+        .draw()?;
+
+    for (i, algorithm) in algorithm_names(cells).into_iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        let points: Vec<&CellResult> = cells
+            .iter()
+            .copied()
+            .filter(|c| c.algorithm == algorithm)
+            .collect();
+        chart
+            .draw_series(
+                points
+                    .iter()
+                    .map(|c| Circle::new((c.compress_mbps(), c.ratio), 6, color.filled())),
+            )?
+            .label(algorithm)
+            .legend(move |(x, y)| Circle::new((x, y), 5, color.filled()));
+        chart.draw_series(points.iter().map(|c| {
+            Text::new(
+                c.level.clone(),
+                (c.compress_mbps(), c.ratio),
+                ("sans-serif", 12),
+            )
+        }))?;
+    }
+
+    chart
+        .configure_series_labels()
+        .border_style(BLACK)
+        .background_style(WHITE.mix(0.8))
+        .draw()?;
+    root.present()?;
+    Ok(())
+}
+
+// One line per configuration, compression MB/s across the benchmarked input sizes
+fn draw_size_lines<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    data_type: &str,
+    cells: &[&CellResult],
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let min_size = cells.iter().map(|c| c.original_size).min().unwrap_or(1) as f64;
+    let max_size = cells.iter().map(|c| c.original_size).max().unwrap_or(1) as f64;
+    let (min_speed, max_speed) = speed_range(cells.iter().map(|c| c.compress_mbps()));
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            format!("{} data: throughput by input size", data_type),
+            ("sans-serif", 24),
+        )
+        .margin(15)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(
+            (min_size / 2.0..max_size * 2.0).log_scale(),
+            (min_speed..max_speed).log_scale(),
+        )?;
+    chart
+        .configure_mesh()
+        .x_desc("Input size (bytes)")
+        .y_desc("Compression MB/s")
+        .draw()?;
+
+    let mut configs: Vec<String> = Vec::new();
+    for cell in cells {
+        if !configs.contains(&cell.label()) {
+            configs.push(cell.label());
+        }
+    }
+    for (i, config) in configs.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        let mut points: Vec<(f64, f64)> = cells
+            .iter()
+            .filter(|c| &c.label() == config)
+            .map(|c| (c.original_size as f64, c.compress_mbps()))
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        chart
+            .draw_series(LineSeries::new(points.clone(), color.stroke_width(2)))?
+            .label(config.as_str())
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 15, y)], color.stroke_width(2))
+            });
+        chart.draw_series(
+            points
+                .into_iter()
+                .map(|p| Circle::new(p, 4, color.filled())),
+        )?;
+    }
+
+    chart
+        .configure_series_labels()
+        .border_style(BLACK)
+        .background_style(WHITE.mix(0.8))
+        .draw()?;
+    root.present()?;
+    Ok(())
+}
+
+// Log-axis bounds padded out to the surrounding powers of ten
+fn speed_range(speeds: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = speeds.fold((f64::INFINITY, 0.0f64), |(lo, hi), s| {
+        (lo.min(s), hi.max(s))
+    });
+    if !min.is_finite() || max <= 0.0 {
+        return (1.0, 10.0);
+    }
+    (
+        10f64.powf(min.log10().floor()),
+        10f64.powf(max.log10().ceil().max(min.log10().floor() + 1.0)),
+    )
+}
+
+fn algorithm_names<'a>(cells: &[&'a CellResult]) -> Vec<&'a str> {
+    let mut names: Vec<&str> = Vec::new();
+    for cell in cells {
+        if !names.contains(&cell.algorithm.as_str()) {
+            names.push(&cell.algorithm);
+        }
+    }
+    names
+}
+
+// Speed/ratio curve across a parameter sweep, with an optional fixed reference point; only the
+// lz4 acceleration sweep draws one
+#[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+pub fn render_sweep(
+    path: &Path,
+    format: PlotFormat,
+    title: &str,
+    curve: &[(String, f64, f64)],
+    reference: Option<(&str, f64, f64)>,
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        PlotFormat::Svg => draw_sweep(
+            SVGBackend::new(path, PLOT_SIZE).into_drawing_area(),
+            title,
+            curve,
+            reference,
+        ),
+        PlotFormat::Png => draw_sweep(
+            BitMapBackend::new(path, PLOT_SIZE).into_drawing_area(),
+            title,
+            curve,
+            reference,
+        ),
+    }
+}
+
+#[cfg(all(feature = "lz4", feature = "lz4-flex"))]
+fn draw_sweep<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    title: &str,
+    curve: &[(String, f64, f64)],
+    reference: Option<(&str, f64, f64)>,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let speeds = curve
+        .iter()
+        .map(|p| p.1)
+        .chain(reference.iter().map(|r| r.1));
+    let (min_speed, max_speed) = speed_range(speeds);
+    let max_ratio = curve
+        .iter()
+        .map(|p| p.2)
+        .chain(reference.iter().map(|r| r.2))
+        .fold(1.0, f64::max)
+        * 1.1;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(title, ("sans-serif", 24))
+        .margin(15)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d((min_speed..max_speed).log_scale(), 0.0..max_ratio)?;
+    chart
+        .configure_mesh()
+        .x_desc("Compression MB/s")
+        .y_desc("Ratio")
+        .draw()?;
+
+    let color = Palette99::pick(0).to_rgba();
+    let points: Vec<(f64, f64)> = curve.iter().map(|p| (p.1, p.2)).collect();
+    chart.draw_series(LineSeries::new(points.clone(), color.stroke_width(2)))?;
+    chart.draw_series(points.iter().map(|&p| Circle::new(p, 4, color.filled())))?;
+    chart.draw_series(curve.iter().map(|(label, speed, ratio)| {
+        Text::new(label.clone(), (*speed, *ratio), ("sans-serif", 12))
+    }))?;
+
+    if let Some((label, speed, ratio)) = reference {
+        let color = Palette99::pick(1).to_rgba();
+        chart
+            .draw_series(std::iter::once(Circle::new(
+                (speed, ratio),
+                6,
+                color.filled(),
+            )))?
+            .label(label)
+            .legend(move |(x, y)| Circle::new((x, y), 5, color.filled()));
+        chart
+            .configure_series_labels()
+            .border_style(BLACK)
+            .background_style(WHITE.mix(0.8))
+            .draw()?;
+    }
+    root.present()?;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// External codecs loaded from shared libraries; the C side of the contract is
+// include/compress_comparison_plugin.h
+use crate::error::{CompressionBenchError, Result};
+use crate::Compressor;
+use libloading::Library;
+use std::ffi::{c_char, c_int, CStr};
+use std::io;
+use std::path::Path;
+use std::ptr;
+
+pub const ABI_VERSION: u32 = 1;
+const ENTRY_POINT: &[u8] = b"ccb_plugin_codec";
+
+type CodecFn = unsafe extern "C" fn(*const u8, usize, *mut *mut u8, *mut usize) -> c_int;
+
+// Mirrors `CcbCodecVTable`
+#[repr(C)]
+struct VTable {
+    abi_version: u32,
+    name: *const c_char,
+    level: *const c_char,
+    compress: CodecFn,
+    decompress: CodecFn,
+    free_buffer: unsafe extern "C" fn(*mut u8, usize),
+}
+
+// A plugin's codec; the library stays loaded for as long as the codec exists
+pub struct PluginCodec {
+    vtable: &'static VTable,
+    name: &'static str,
+    level: String,
+    _library: Library,
+}
+
+// SAFETY: the contract requires the vtable's functions to be callable from any thread
+unsafe impl Send for PluginCodec {}
+unsafe impl Sync for PluginCodec {}
+
+impl PluginCodec {
+    fn call(&self, codec: CodecFn, data: &[u8], action: &str) -> io::Result<Vec<u8>> {
+        let mut output: *mut u8 = ptr::null_mut();
+        let mut output_len = 0;
+        // SAFETY: `data` outlives the call and the out-pointers are valid locals
+        let status = unsafe { codec(data.as_ptr(), data.len(), &mut output, &mut output_len) };
+        if status != 0 {
+            return Err(io::Error::other(format!(
+                "plugin {} returned status {}",
+                action, status
+            )));
+        }
+        if output.is_null() {
+            return Ok(Vec::new());
+        }
+        // SAFETY: on success the plugin hands over `output_len` bytes, freed with its allocator
+        unsafe {
+            let copy = std::slice::from_raw_parts(output, output_len).to_vec();
+            (self.vtable.free_buffer)(output, output_len);
+            Ok(copy)
+        }
+    }
+}
+
+impl Compressor for PluginCodec {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn level(&self) -> String {
+        self.level.clone()
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        self.call(self.vtable.compress, data, "compression")
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        self.call(self.vtable.decompress, data, "decompression")
+    }
+}
+
+fn c_str(text: *const c_char, field: &str) -> std::result::Result<String, String> {
+    if text.is_null() {
+        return Err(format!("`{}` is null", field));
+    }
+    // SAFETY: the contract requires NUL-terminated strings that live as long as the library
+    let text = unsafe { CStr::from_ptr(text) };
+    text.to_str()
+        .map(str::to_string)
+        .map_err(|_| format!("`{}` is not UTF-8", field))
+}
+
+// Loads the codec a shared library exports through `ccb_plugin_codec`. Suites hold
+// `'static` configurations, so a loaded plugin is kept for the rest of the process
+pub fn load(path: &Path) -> Result<&'static dyn Compressor> {
+    let error = |message: String| CompressionBenchError::Plugin {
+        path: path.to_path_buf(),
+        message,
+    };
+    // SAFETY: loading runs the library's initializers; plugins are trusted like any dependency
+    let library = unsafe { Library::new(path) }.map_err(|e| error(e.to_string()))?;
+    // SAFETY: the symbol's type is fixed by the plugin header
+    let vtable = unsafe {
+        let entry = library
+            .get::<unsafe extern "C" fn() -> *const VTable>(ENTRY_POINT)
+            .map_err(|e| error(e.to_string()))?;
+        entry()
+            .as_ref()
+            .ok_or_else(|| error("`ccb_plugin_codec` returned null".to_string()))?
+    };
+    if vtable.abi_version != ABI_VERSION {
+        return Err(error(format!(
+            "built for plugin ABI {}, expected {}",
+            vtable.abi_version, ABI_VERSION
+        )));
+    }
+    let name = c_str(vtable.name, "name").map_err(error)?;
+    let level = c_str(vtable.level, "level").map_err(error)?;
+    // SAFETY: the vtable is static data of a library that is never unloaded
+    let vtable: &'static VTable = unsafe { &*(vtable as *const VTable) };
+    Ok(Box::leak(Box::new(PluginCodec {
+        vtable,
+        name: Box::leak(name.into_boxed_str()),
+        level,
+        _library: library,
+    })))
+}
@@ -0,0 +1,151 @@
+#[cfg(feature = "gzip")]
+use flate2::{Compress, Compression, Decompress};
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use std::cell::RefCell;
+#[cfg(feature = "zstd")]
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+// Shared pool of reusable codec contexts, reset before they are handed out again
+pub struct Pool<T> {
+    items: Mutex<Vec<T>>,
+    create: fn() -> T,
+    reset: fn(&mut T),
+}
+
+impl<T> Pool<T> {
+    pub fn new(create: fn() -> T, reset: fn(&mut T)) -> Self {
+        Pool {
+            items: Mutex::new(Vec::new()),
+            create,
+            reset,
+        }
+    }
+
+    // Takes an idle context, or builds a new one if the pool is empty
+    pub fn get(&self) -> Pooled<'_, T> {
+        let item = self.items.lock().unwrap().pop();
+        Pooled {
+            pool: self,
+            item: Some(item.unwrap_or_else(self.create)),
+        }
+    }
+}
+
+// A context checked out of a `Pool`; returned on drop
+pub struct Pooled<'a, T> {
+    pool: &'a Pool<T>,
+    item: Option<T>,
+}
+
+impl<T> Deref for Pooled<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.item.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for Pooled<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.item.as_mut().unwrap()
+    }
+}
+
+impl<T> Drop for Pooled<'_, T> {
+    fn drop(&mut self) {
+        if let Some(mut item) = self.item.take() {
+            (self.pool.reset)(&mut item);
+            self.pool.items.lock().unwrap().push(item);
+        }
+    }
+}
+
+#[cfg(feature = "gzip")]
+thread_local! {
+    static COMPRESS_CONTEXTS: RefCell<Vec<(u32, Compress)>> = const { RefCell::new(Vec::new()) };
+    static DECOMPRESS_CONTEXTS: RefCell<Vec<Decompress>> = const { RefCell::new(Vec::new()) };
+}
+
+#[cfg(feature = "zstd")]
+thread_local! {
+    static ZSTD_COMPRESS_CONTEXTS: RefCell<Vec<(i32, zstd::bulk::Compressor<'static>)>> =
+        const { RefCell::new(Vec::new()) };
+    static ZSTD_DECOMPRESS_CONTEXTS: RefCell<Vec<zstd::bulk::Decompressor<'static>>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+// Per-thread cache of flate2 and zstd contexts, so concurrent callers never contend on a lock
+pub struct CodecPool;
+
+#[cfg(feature = "gzip")]
+impl CodecPool {
+    // Runs `f` with a raw deflate context at `level`, reusing one cached on this thread
+    pub fn with_compress<R>(level: Compression, f: impl FnOnce(&mut Compress) -> R) -> R {
+        let cached = COMPRESS_CONTEXTS.with(|contexts| {
+            let mut contexts = contexts.borrow_mut();
+            let index = contexts.iter().position(|(l, _)| *l == level.level())?;
+            Some(contexts.swap_remove(index).1)
+        });
+        let mut context = cached.unwrap_or_else(|| Compress::new(level, false));
+        let result = f(&mut context);
+        context.reset();
+        COMPRESS_CONTEXTS.with(|contexts| contexts.borrow_mut().push((level.level(), context)));
+        result
+    }
+
+    // Runs `f` with a raw inflate context, reusing one cached on this thread
+    pub fn with_decompress<R>(f: impl FnOnce(&mut Decompress) -> R) -> R {
+        let cached = DECOMPRESS_CONTEXTS.with(|contexts| contexts.borrow_mut().pop());
+        let mut context = cached.unwrap_or_else(|| Decompress::new(false));
+        let result = f(&mut context);
+        context.reset(false);
+        DECOMPRESS_CONTEXTS.with(|contexts| contexts.borrow_mut().push(context));
+        result
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl CodecPool {
+    // Runs `f` with a zstd compression context at `level`, reusing one cached on this thread
+    pub fn with_zstd_compress<R>(
+        level: i32,
+        f: impl FnOnce(&mut zstd::bulk::Compressor<'static>) -> R,
+    ) -> io::Result<R> {
+        let cached = ZSTD_COMPRESS_CONTEXTS.with(|contexts| {
+            let mut contexts = contexts.borrow_mut();
+            let index = contexts.iter().position(|(l, _)| *l == level)?;
+            Some(contexts.swap_remove(index).1)
+        });
+        let mut context = match cached {
+            Some(context) => context,
+            None => zstd::bulk::Compressor::new(level)?,
+        };
+        let result = f(&mut context);
+        // Parameters `f` set (checksums, workers, ...) must not leak into the next caller
+        let reset = context
+            .context_mut()
+            .reset(zstd::zstd_safe::ResetDirective::SessionAndParameters);
+        if reset.is_ok() && context.set_compression_level(level).is_ok() {
+            ZSTD_COMPRESS_CONTEXTS.with(|contexts| contexts.borrow_mut().push((level, context)));
+        }
+        Ok(result)
+    }
+
+    // Runs `f` with a zstd decompression context, reusing one cached on this thread. Each bulk
+    // call decodes whole frames, so no session carries over; `Decompressor` exposes no reset, so
+    // parameters `f` sets stay on the context
+    pub fn with_zstd_decompress<R>(
+        f: impl FnOnce(&mut zstd::bulk::Decompressor<'static>) -> R,
+    ) -> io::Result<R> {
+        let cached = ZSTD_DECOMPRESS_CONTEXTS.with(|contexts| contexts.borrow_mut().pop());
+        let mut context = match cached {
+            Some(context) => context,
+            None => zstd::bulk::Decompressor::new()?,
+        };
+        let result = f(&mut context);
+        ZSTD_DECOMPRESS_CONTEXTS.with(|contexts| contexts.borrow_mut().push(context));
+        Ok(result)
+    }
+}
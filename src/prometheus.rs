@@ -0,0 +1,122 @@
+use crate::results::RunResults;
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tiny_http::{Header, Method, Response, Server};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// Label values may contain anything; the text format only needs these three escaped
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, samples: &[(String, f64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            let _ = writeln!(out, "{} {}", name, value);
+        } else {
+            let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+        }
+    }
+}
+
+// Results in the Prometheus text exposition format, one sample per cell and metric
+pub fn render(results: &RunResults, modified_secs: Option<f64>) -> String {
+    let mut ratio = Vec::new();
+    let mut compress = Vec::new();
+    let mut decompress = Vec::new();
+    for cell in &results.cells {
+        let labels = format!(
+            "algo=\"{}\",level=\"{}\",data=\"{}\"",
+            escape(&cell.algorithm),
+            escape(&cell.level),
+            escape(&cell.data_type)
+        );
+        ratio.push((labels.clone(), cell.ratio));
+        compress.push((labels.clone(), cell.compress_mbps()));
+        if cell.decompress_secs > 0.0 {
+            decompress.push((labels, cell.decompress_mbps()));
+        }
+    }
+
+    let mut out = String::new();
+    gauge(
+        &mut out,
+        "compression_ratio",
+        "Original size divided by compressed size",
+        &ratio,
+    );
+    gauge(
+        &mut out,
+        "compress_mbps",
+        "Compression throughput in MB/s of input",
+        &compress,
+    );
+    gauge(
+        &mut out,
+        "decompress_mbps",
+        "Decompression throughput in MB/s of output",
+        &decompress,
+    );
+    gauge(
+        &mut out,
+        "compression_failures",
+        "Cells of the run that failed instead of producing a result",
+        &[(String::new(), results.failures.len() as f64)],
+    );
+    if let Some(secs) = modified_secs {
+        gauge(
+            &mut out,
+            "compression_results_timestamp_seconds",
+            "When the results file was last written, for alerting on a stalled runner",
+            &[(String::new(), secs)],
+        );
+    }
+    out
+}
+
+fn scrape(input: &Path) -> Result<String, Box<dyn Error>> {
+    let results = RunResults::load(input)?;
+    let modified_secs = fs::metadata(input)?
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|age| age.as_secs_f64());
+    Ok(render(&results, modified_secs))
+}
+
+// Serves `input` at /metrics, re-reading it on every scrape so a scheduled `bench --output` run
+// that rewrites the file is picked up without restarting
+pub fn serve(input: &Path, listen: &str) -> Result<(), Box<dyn Error>> {
+    scrape(input)?;
+    let server =
+        Server::http(listen).map_err(|e| format!("could not listen on {}: {}", listen, e))?;
+    println!(
+        "Serving {} at http://{}/metrics",
+        input.display(),
+        server.server_addr()
+    );
+    let content_type = Header::from_bytes("Content-Type", CONTENT_TYPE).expect("valid header");
+    for request in server.incoming_requests() {
+        let response = match (request.method(), request.url()) {
+            (Method::Get, "/metrics") => match scrape(input) {
+                Ok(body) => Response::from_string(body).with_header(content_type.clone()),
+                Err(e) => Response::from_string(format!("could not read results: {}\n", e))
+                    .with_status_code(500),
+            },
+            _ => Response::from_string("not found\n").with_status_code(404),
+        };
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to send response: {}", e);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+// `compress_comparison` Python module: `run_benchmark(config) -> dict`
+use crate::config::SuiteConfig;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+// Runs a suite described by a `SuiteConfig`-shaped dict and returns the results as plain
+// dicts and lists, ready for pandas or any plotting library
+#[pyfunction]
+#[pyo3(signature = (config=None))]
+fn run_benchmark<'py>(
+    py: Python<'py>,
+    config: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let json = py.import("json")?;
+    let config = match config {
+        Some(config) => json.call_method1("dumps", (config,))?.extract::<String>()?,
+        None => "{}".to_string(),
+    };
+    // The run takes seconds to minutes; let other Python threads carry on meanwhile
+    let results = py
+        .detach(|| SuiteConfig::from_json(&config)?.builder()?.run())
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let results =
+        serde_json::to_string(&results).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    json.call_method1("loads", (results,))
+}
+
+#[pymodule]
+fn compress_comparison(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(run_benchmark, module)?)
+}
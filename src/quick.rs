@@ -0,0 +1,56 @@
+use crate::{backends, generate_test_data, timed, CompressionStats, Compressor, TEST_CASES};
+use std::time::{Duration, Instant};
+
+const QUICK_DATA_SIZE: usize = 1024 * 1024; // 1MB
+const QUICK_BUDGET: Duration = Duration::from_secs(10);
+
+// Fast tiers only: the configuration people reach for by default from each backend built in,
+// or its single level where it has no Fast one
+fn quick_codecs() -> Vec<&'static dyn Compressor> {
+    backends::REGISTRY
+        .iter()
+        .filter_map(|backend| backend.level("fast").or_else(|| backend.level("default")))
+        .collect()
+}
+
+// Repeats each cell until its share of the time budget is used up
+pub fn run() {
+    let codecs = quick_codecs();
+    let cell_budget = QUICK_BUDGET / (TEST_CASES.len() * codecs.len()).max(1) as u32;
+
+    println!(
+        "\nQuick sanity benchmark ({}MB data, ~{}s)\n",
+        QUICK_DATA_SIZE / 1024 / 1024,
+        QUICK_BUDGET.as_secs()
+    );
+    println!(
+        "{:<10} {:<16} {:>8} {:>12} {:>14} {:>7}",
+        "Data", "Algorithm", "Ratio", "Comp MB/s", "Decomp MB/s", "Trials"
+    );
+    println!("{}", "-".repeat(72));
+
+    for (data_name, data_type) in &TEST_CASES {
+        let data = generate_test_data(data_type, QUICK_DATA_SIZE);
+        for codec in &codecs {
+            let codec_name = format!("{} {}", codec.name(), codec.level());
+            let mut stats = CompressionStats::default();
+            let start = Instant::now();
+            while stats.trials() == 0 || start.elapsed() < cell_budget {
+                let (compressed, duration) = timed(|| codec.compress(&data));
+                stats.record(data.len(), compressed.len(), duration);
+                let (_, decompress_duration) = timed(|| codec.decompress(&compressed));
+                stats.record_decompression(decompress_duration);
+            }
+            let cell = stats.to_cell(data_name, &codec_name, "");
+            println!(
+                "{:<10} {:<16} {:>7.2}x {:>12.1} {:>14.1} {:>7}",
+                data_name,
+                codec_name,
+                cell.ratio,
+                cell.compress_mbps(),
+                cell.decompress_mbps(),
+                stats.trials()
+            );
+        }
+    }
+}
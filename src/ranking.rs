@@ -0,0 +1,135 @@
+use crate::results::{CellResult, RunResults};
+use std::fmt;
+
+// What "best" means when picking a winner per data type
+#[derive(Clone, Copy)]
+pub enum Objective {
+    // Fastest compression among configs reaching at least this ratio
+    FastestWithRatio(f64),
+    // Highest ratio among configs compressing within this many milliseconds
+    BestRatioWithin(f64),
+    // Most MB saved per CPU-second, counting this many decompressions per compression
+    MostEfficient(f64),
+    // Highest decompression throughput regardless of ratio
+    FastestDecompression,
+}
+
+pub const DEFAULT_OBJECTIVES: [Objective; 4] = [
+    Objective::FastestWithRatio(2.0),
+    Objective::BestRatioWithin(100.0),
+    Objective::MostEfficient(1.0),
+    Objective::FastestDecompression,
+];
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Objective::FastestWithRatio(ratio) => {
+                write!(f, "fastest achieving >= {}x ratio", ratio)
+            }
+            Objective::BestRatioWithin(ms) => write!(f, "best ratio under {}ms", ms),
+            Objective::MostEfficient(reads) => {
+                write!(f, "most saved MB/CPU-s at {} reads/write", reads)
+            }
+            Objective::FastestDecompression => write!(f, "best decompression throughput"),
+        }
+    }
+}
+
+impl Objective {
+    // Candidates meeting the constraint, best first
+    pub fn rank<'a>(&self, cells: &[&'a CellResult]) -> Vec<&'a CellResult> {
+        let mut ranked: Vec<&CellResult> = match self {
+            Objective::FastestWithRatio(ratio) => cells
+                .iter()
+                .copied()
+                .filter(|c| c.ratio >= *ratio)
+                .collect(),
+            Objective::BestRatioWithin(ms) => cells
+                .iter()
+                .copied()
+                .filter(|c| c.compress_secs * 1000.0 <= *ms)
+                .collect(),
+            Objective::MostEfficient(_) => cells.to_vec(),
+            Objective::FastestDecompression => cells
+                .iter()
+                .copied()
+                .filter(|c| c.decompress_secs > 0.0)
+                .collect(),
+        };
+        match self {
+            Objective::FastestWithRatio(_) => {
+                ranked.sort_by(|a, b| a.compress_secs.total_cmp(&b.compress_secs))
+            }
+            Objective::BestRatioWithin(_) => ranked.sort_by(|a, b| b.ratio.total_cmp(&a.ratio)),
+            Objective::MostEfficient(reads) => ranked.sort_by(|a, b| {
+                b.saved_mb_per_cpu_sec(*reads)
+                    .total_cmp(&a.saved_mb_per_cpu_sec(*reads))
+            }),
+            Objective::FastestDecompression => {
+                ranked.sort_by(|a, b| b.decompress_mbps().total_cmp(&a.decompress_mbps()))
+            }
+        }
+        ranked
+    }
+}
+
+// Parses `fastest-with-ratio=2`, `best-ratio-within-ms=100`, `most-efficient=1` or `fastest-decompression`
+pub fn parse_objective(s: &str) -> Result<Objective, String> {
+    let (name, value) = match s.split_once('=') {
+        Some((name, value)) => (name, Some(value)),
+        None => (s, None),
+    };
+    let number = |default: f64| -> Result<f64, String> {
+        value.map_or(Ok(default), |v| {
+            v.parse()
+                .map_err(|_| format!("invalid number `{}` in objective `{}`", v, s))
+        })
+    };
+    match name {
+        "fastest-with-ratio" => Ok(Objective::FastestWithRatio(number(2.0)?)),
+        "best-ratio-within-ms" => Ok(Objective::BestRatioWithin(number(100.0)?)),
+        "most-efficient" => Ok(Objective::MostEfficient(number(1.0)?)),
+        "fastest-decompression" => Ok(Objective::FastestDecompression),
+        _ => Err(format!(
+            "unknown objective `{}`, expected fastest-with-ratio[=X], best-ratio-within-ms[=MS], most-efficient[=READS] or fastest-decompression",
+            name
+        )),
+    }
+}
+
+pub fn print_summary(results: &RunResults, objectives: &[Objective]) {
+    println!("\n=== Winner Summary ===");
+    for data_type in results.data_types() {
+        let cells: Vec<&CellResult> = results
+            .cells
+            .iter()
+            .filter(|c| c.data_type == data_type)
+            .collect();
+
+        println!("\n--- {} Data ---", data_type);
+        for objective in objectives {
+            let ranked = objective.rank(&cells);
+            match ranked.first() {
+                Some(winner) => {
+                    let runners_up: Vec<String> =
+                        ranked.iter().skip(1).take(2).map(|c| c.label()).collect();
+                    println!(
+                        "  {:<36} {:<20} {:>7.2}x {:>9.3}s {:>10.1} MB/s decomp{}",
+                        objective.to_string(),
+                        winner.label(),
+                        winner.ratio,
+                        winner.compress_secs,
+                        winner.decompress_mbps(),
+                        if runners_up.is_empty() {
+                            String::new()
+                        } else {
+                            format!("  (then {})", runners_up.join(", "))
+                        }
+                    );
+                }
+                None => println!("  {:<36} no configuration qualifies", objective.to_string()),
+            }
+        }
+    }
+}
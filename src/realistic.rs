@@ -0,0 +1,157 @@
+use crate::{backends, generate_test_data, try_timed, Compressor, TestData};
+use clap::ValueEnum;
+use std::fs::{self, File};
+use std::hint::black_box;
+use std::io::{self, BufWriter, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+const REALISTIC_DATA_SIZE: usize = 1024 * 1024 * 10; // 10MB
+const REALISTIC_ITERATIONS: usize = 5;
+// Differences beyond this are worth a closer look at the microbenchmarks
+const SUSPICIOUS_DELTA: f64 = 0.10;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SinkKind {
+    /// A temporary file, synced after every write
+    File,
+    /// A loopback TCP connection drained by a reader thread
+    Socket,
+}
+
+// Where realistic mode sends compressed output so the optimizer can't discard it
+enum Sink {
+    File(BufWriter<File>),
+    Socket(TcpStream, JoinHandle<io::Result<u64>>),
+}
+
+impl Sink {
+    fn open(kind: SinkKind) -> io::Result<Sink> {
+        match kind {
+            SinkKind::File => {
+                let path = std::env::temp_dir()
+                    .join(format!("compress-realistic-{}.bin", std::process::id()));
+                let file = File::create(&path)?;
+                // The handle keeps the file alive on unix; elsewhere it is left behind in temp
+                let _ = fs::remove_file(&path);
+                Ok(Sink::File(BufWriter::new(file)))
+            }
+            SinkKind::Socket => {
+                let listener = TcpListener::bind("127.0.0.1:0")?;
+                let addr = listener.local_addr()?;
+                let reader = thread::spawn(move || {
+                    let (mut stream, _) = listener.accept()?;
+                    let mut buf = vec![0u8; 64 * 1024];
+                    let mut total = 0u64;
+                    loop {
+                        match stream.read(&mut buf)? {
+                            0 => return Ok(total),
+                            n => total += n as u64,
+                        }
+                    }
+                });
+                Ok(Sink::Socket(TcpStream::connect(addr)?, reader))
+            }
+        }
+    }
+
+    fn send(&mut self, compressed: &[u8]) -> io::Result<()> {
+        match self {
+            Sink::File(writer) => {
+                writer.write_all(compressed)?;
+                writer.flush()?;
+                writer.get_ref().sync_data()
+            }
+            Sink::Socket(stream, _) => stream.write_all(compressed),
+        }
+    }
+
+    fn close(self) -> io::Result<()> {
+        if let Sink::Socket(stream, reader) = self {
+            drop(stream);
+            reader.join().expect("socket reader panicked")?;
+        }
+        Ok(())
+    }
+}
+
+// Compressed output kept alive only through black_box, as in the criterion benches
+fn run_synthetic(data: &[u8], codec: &dyn Compressor) -> io::Result<f64> {
+    let mut total_secs = 0.0;
+    for _ in 0..REALISTIC_ITERATIONS {
+        let (compressed, duration) = try_timed(|| codec.compress(black_box(data)))?;
+        black_box(compressed);
+        total_secs += duration;
+    }
+    Ok(total_secs / REALISTIC_ITERATIONS as f64)
+}
+
+// Compressed output actually written out; returns (compress, write) seconds per iteration
+fn run_realistic(data: &[u8], codec: &dyn Compressor, kind: SinkKind) -> io::Result<(f64, f64)> {
+    let mut sink = Sink::open(kind)?;
+    let mut compress_secs = 0.0;
+    let mut write_secs = 0.0;
+    for _ in 0..REALISTIC_ITERATIONS {
+        let (compressed, duration) = try_timed(|| codec.compress(data))?;
+        compress_secs += duration;
+        let write_start = Instant::now();
+        sink.send(&compressed)?;
+        write_secs += write_start.elapsed().as_secs_f64();
+    }
+    sink.close()?;
+    Ok((
+        compress_secs / REALISTIC_ITERATIONS as f64,
+        write_secs / REALISTIC_ITERATIONS as f64,
+    ))
+}
+
+pub fn run(kind: SinkKind) -> io::Result<()> {
+    let data = generate_test_data(&TestData::Mixed, REALISTIC_DATA_SIZE);
+    let size_mb = REALISTIC_DATA_SIZE as f64 / (1024.0 * 1024.0);
+    let sink_name = match kind {
+        SinkKind::File => "temp file",
+        SinkKind::Socket => "loopback socket",
+    };
+
+    println!(
+        "\nSynthetic (black_box) vs realistic ({} sink) compression ({}MB Mixed data, {} iterations)\n",
+        sink_name,
+        REALISTIC_DATA_SIZE / 1024 / 1024,
+        REALISTIC_ITERATIONS
+    );
+    println!(
+        "{:<16} {:>15} {:>15} {:>10} {:>11}",
+        "Algorithm", "Synthetic MB/s", "Realistic MB/s", "Delta", "Write (ms)"
+    );
+    println!("{}", "-".repeat(71));
+
+    let mut suspicious = Vec::new();
+    for codec in backends::configurations() {
+        let name = format!("{} {}", codec.name(), codec.level());
+        let synthetic_secs = run_synthetic(&data, codec)?;
+        let (realistic_secs, write_secs) = run_realistic(&data, codec, kind)?;
+        let delta = (realistic_secs - synthetic_secs) / synthetic_secs;
+        if delta.abs() > SUSPICIOUS_DELTA {
+            suspicious.push(name.clone());
+        }
+        println!(
+            "{:<16} {:>15.1} {:>15.1} {:>+9.1}% {:>11.3}",
+            name,
+            size_mb / synthetic_secs,
+            size_mb / realistic_secs,
+            delta * 100.0,
+            write_secs * 1000.0
+        );
+    }
+
+    if !suspicious.is_empty() {
+        println!(
+            "\nCompression time differs by more than {:.0}% between modes for: {}",
+            SUSPICIOUS_DELTA * 100.0,
+            suspicious.join(", ")
+        );
+        println!("The black_box microbenchmarks may not reflect real-world cost for these codecs.");
+    }
+    Ok(())
+}
@@ -0,0 +1,374 @@
+#[cfg(feature = "gzip")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "gzip")]
+use flate2::write::GzEncoder;
+#[cfg(feature = "gzip")]
+use flate2::Compression;
+#[cfg(feature = "lz4-flex")]
+use lz4_flex::frame::{FrameDecoder, FrameEncoder};
+use rand::{thread_rng, Rng};
+use rust_compress_comparison::CompressionBenchError;
+#[cfg(any(feature = "gzip", feature = "lz4", feature = "lz4-flex"))]
+use std::io::Write;
+use std::io::{self, Read};
+use std::time::Instant;
+
+const SERVICES: [&str; 5] = ["api", "auth", "billing", "search", "worker"];
+const LEVELS: [&str; 4] = ["DEBUG", "INFO", "WARN", "ERROR"];
+const MESSAGES: [&str; 5] = [
+    "request completed",
+    "cache miss, fetching from upstream",
+    "retrying after transient failure",
+    "user session refreshed",
+    "background job finished",
+];
+
+// Log/event records as a store would receive them, one JSON object each
+pub fn generate_records(count: usize) -> Vec<Vec<u8>> {
+    let mut rng = thread_rng();
+    let start_ts: u64 = 1_700_000_000_000;
+    (0..count)
+        .map(|i| {
+            let record = serde_json::json!({
+                "ts": start_ts + i as u64 * rng.gen_range(1..50),
+                "level": LEVELS[rng.gen_range(0..LEVELS.len())],
+                "service": SERVICES[rng.gen_range(0..SERVICES.len())],
+                "msg": MESSAGES[rng.gen_range(0..MESSAGES.len())],
+                "user_id": rng.gen_range(1..100_000),
+                "latency_ms": rng.gen_range(0.1..500.0),
+                "trace_id": format!("{:032x}", rng.gen::<u128>()),
+            });
+            serde_json::to_vec(&record).unwrap()
+        })
+        .collect()
+}
+
+fn push_prefixed(out: &mut Vec<u8>, payload: &[u8]) {
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(payload);
+}
+
+// Walks length-prefixed payloads and returns the one at `index`
+fn nth_prefixed(mut buf: &[u8], index: usize) -> io::Result<&[u8]> {
+    let truncated = || io::Error::new(io::ErrorKind::UnexpectedEof, "truncated record frame");
+    for _ in 0..index {
+        let header = buf.get(..4).ok_or_else(truncated)?;
+        let len = u32::from_le_bytes(header.try_into().unwrap()) as usize;
+        buf = buf.get(4 + len..).ok_or_else(truncated)?;
+    }
+    let header = buf.get(..4).ok_or_else(truncated)?;
+    let len = u32::from_le_bytes(header.try_into().unwrap()) as usize;
+    buf.get(4..4 + len).ok_or_else(truncated)
+}
+
+struct RecordCodec {
+    backend: &'static str,
+    level: &'static str,
+    // One self-contained compressed frame
+    compress: fn(&[u8]) -> io::Result<Vec<u8>>,
+    decompress: fn(&[u8]) -> io::Result<Vec<u8>>,
+    // One continuous stream, flushed after every record so each is durable on its own
+    stream: fn(&[Vec<u8>]) -> io::Result<Vec<u8>>,
+    stream_reader: fn(&[u8]) -> io::Result<Box<dyn Read + '_>>,
+}
+
+impl RecordCodec {
+    fn compress_error(&self, source: io::Error) -> CompressionBenchError {
+        CompressionBenchError::Compress {
+            backend: self.backend,
+            level: self.level.to_string(),
+            source,
+        }
+    }
+
+    fn decompress_error(&self, source: io::Error) -> CompressionBenchError {
+        CompressionBenchError::Decompress {
+            backend: self.backend,
+            level: self.level.to_string(),
+            source,
+        }
+    }
+}
+
+// Each codec is compiled in with its backend feature
+const RECORD_CODECS: &[RecordCodec] = &[
+    #[cfg(feature = "gzip")]
+    RecordCodec {
+        backend: "Gzip",
+        level: "Default",
+        compress: |data| {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()
+        },
+        decompress: |data| {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(data).read_to_end(&mut decompressed)?;
+            Ok(decompressed)
+        },
+        stream: |records| {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            let mut framed = Vec::new();
+            for record in records {
+                framed.clear();
+                push_prefixed(&mut framed, record);
+                encoder.write_all(&framed)?;
+                encoder.flush()?;
+            }
+            encoder.finish()
+        },
+        stream_reader: |data| Ok(Box::new(GzDecoder::new(data))),
+    },
+    #[cfg(feature = "lz4-flex")]
+    RecordCodec {
+        backend: "LZ4-Flex",
+        level: "Default",
+        compress: |data| Ok(lz4_flex::compress_prepend_size(data)),
+        decompress: |data| {
+            lz4_flex::decompress_size_prepended(data)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        },
+        stream: |records| {
+            let mut encoder = FrameEncoder::new(Vec::new());
+            let mut framed = Vec::new();
+            for record in records {
+                framed.clear();
+                push_prefixed(&mut framed, record);
+                encoder.write_all(&framed)?;
+                encoder.flush()?;
+            }
+            encoder.finish().map_err(io::Error::from)
+        },
+        stream_reader: |data| Ok(Box::new(FrameDecoder::new(data))),
+    },
+    #[cfg(feature = "lz4")]
+    RecordCodec {
+        backend: "LZ4-RS",
+        level: "Default",
+        compress: |data| {
+            let mut encoder = lz4::EncoderBuilder::new().level(4).build(Vec::new())?;
+            encoder.write_all(data)?;
+            let (compressed, result) = encoder.finish();
+            result.map(|()| compressed)
+        },
+        decompress: |data| {
+            let mut decompressed = Vec::new();
+            lz4::Decoder::new(data)?.read_to_end(&mut decompressed)?;
+            Ok(decompressed)
+        },
+        stream: |records| {
+            let mut encoder = lz4::EncoderBuilder::new().level(4).build(Vec::new())?;
+            let mut framed = Vec::new();
+            for record in records {
+                framed.clear();
+                push_prefixed(&mut framed, record);
+                encoder.write_all(&framed)?;
+                encoder.flush()?;
+            }
+            let (compressed, result) = encoder.finish();
+            result.map(|()| compressed)
+        },
+        stream_reader: |data| Ok(Box::new(lz4::Decoder::new(data)?)),
+    },
+];
+
+// A compressed log plus what a consumer needs to find record N in it
+struct Layout {
+    file: Vec<u8>,
+    // Byte offset of each frame in `file`
+    frame_offsets: Vec<usize>,
+    // Uncompressed end offset of each record, for stream layouts
+    record_ends: Vec<usize>,
+    write_secs: f64,
+}
+
+fn layout_per_record(
+    codec: &RecordCodec,
+    records: &[Vec<u8>],
+) -> Result<Layout, CompressionBenchError> {
+    let start = Instant::now();
+    let mut file = Vec::new();
+    let mut frame_offsets = Vec::with_capacity(records.len());
+    for record in records {
+        frame_offsets.push(file.len());
+        let compressed = (codec.compress)(record).map_err(|e| codec.compress_error(e))?;
+        push_prefixed(&mut file, &compressed);
+    }
+    Ok(Layout {
+        file,
+        frame_offsets,
+        record_ends: Vec::new(),
+        write_secs: start.elapsed().as_secs_f64(),
+    })
+}
+
+fn layout_batched(
+    codec: &RecordCodec,
+    records: &[Vec<u8>],
+    batch: usize,
+) -> Result<Layout, CompressionBenchError> {
+    let start = Instant::now();
+    let mut file = Vec::new();
+    let mut frame_offsets = Vec::new();
+    let mut framed = Vec::new();
+    for chunk in records.chunks(batch) {
+        framed.clear();
+        for record in chunk {
+            push_prefixed(&mut framed, record);
+        }
+        frame_offsets.push(file.len());
+        let compressed = (codec.compress)(&framed).map_err(|e| codec.compress_error(e))?;
+        push_prefixed(&mut file, &compressed);
+    }
+    Ok(Layout {
+        file,
+        frame_offsets,
+        record_ends: Vec::new(),
+        write_secs: start.elapsed().as_secs_f64(),
+    })
+}
+
+fn layout_stream(
+    codec: &RecordCodec,
+    records: &[Vec<u8>],
+) -> Result<Layout, CompressionBenchError> {
+    let start = Instant::now();
+    let file = (codec.stream)(records).map_err(|e| codec.compress_error(e))?;
+    let write_secs = start.elapsed().as_secs_f64();
+    let mut end = 0;
+    let record_ends = records
+        .iter()
+        .map(|record| {
+            end += 4 + record.len();
+            end
+        })
+        .collect();
+    Ok(Layout {
+        file,
+        frame_offsets: vec![0],
+        record_ends,
+        write_secs,
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Strategy {
+    PerRecord,
+    Stream,
+    Batched(usize),
+}
+
+impl Strategy {
+    fn name(self) -> String {
+        match self {
+            Strategy::PerRecord => "frame per record".to_string(),
+            Strategy::Stream => "stream + flushes".to_string(),
+            Strategy::Batched(batch) => format!("batches of {}", batch),
+        }
+    }
+}
+
+// What a consumer does to fetch one record given the in-memory index
+fn read_record(
+    codec: &RecordCodec,
+    layout: &Layout,
+    strategy: Strategy,
+    index: usize,
+) -> io::Result<Vec<u8>> {
+    match strategy {
+        Strategy::PerRecord => {
+            let frame = nth_prefixed(&layout.file[layout.frame_offsets[index]..], 0)?;
+            (codec.decompress)(frame)
+        }
+        Strategy::Batched(batch) => {
+            let frame = nth_prefixed(&layout.file[layout.frame_offsets[index / batch]..], 0)?;
+            Ok(nth_prefixed(&(codec.decompress)(frame)?, index % batch)?.to_vec())
+        }
+        Strategy::Stream => {
+            // No restart points: everything before the record has to be decoded first
+            let mut prefix = vec![0u8; layout.record_ends[index]];
+            (codec.stream_reader)(&layout.file)?.read_exact(&mut prefix)?;
+            let start = index
+                .checked_sub(1)
+                .map_or(0, |prev| layout.record_ends[prev]);
+            Ok(nth_prefixed(&prefix[start..], 0)?.to_vec())
+        }
+    }
+}
+
+// Builds the layout and times the lookups, checking each record comes back unchanged;
+// returns the layout and seconds per lookup
+fn measure(
+    codec: &RecordCodec,
+    records: &[Vec<u8>],
+    strategy: Strategy,
+    lookup_indices: &[usize],
+) -> Result<(Layout, f64), CompressionBenchError> {
+    let layout = match strategy {
+        Strategy::PerRecord => layout_per_record(codec, records)?,
+        Strategy::Stream => layout_stream(codec, records)?,
+        Strategy::Batched(batch) => layout_batched(codec, records, batch)?,
+    };
+    let start = Instant::now();
+    for &index in lookup_indices {
+        let record =
+            read_record(codec, &layout, strategy, index).map_err(|e| codec.decompress_error(e))?;
+        if record != records[index] {
+            return Err(CompressionBenchError::RoundTrip {
+                backend: codec.backend,
+                level: codec.level.to_string(),
+                original: records[index].len(),
+                restored: record.len(),
+            });
+        }
+    }
+    let lookup_secs = start.elapsed().as_secs_f64() / lookup_indices.len() as f64;
+    Ok((layout, lookup_secs))
+}
+
+pub fn run(record_count: usize, batch: usize, lookups: usize) {
+    let records = generate_records(record_count);
+    let raw_size: usize = records.iter().map(|r| 4 + r.len()).sum();
+    let mut rng = thread_rng();
+    let lookup_indices: Vec<usize> = (0..lookups)
+        .map(|_| rng.gen_range(0..record_count))
+        .collect();
+
+    println!(
+        "\nLength-prefixed JSON records ({} records, {:.1}KB raw, {} random lookups)\n",
+        record_count,
+        raw_size as f64 / 1024.0,
+        lookups
+    );
+    println!(
+        "{:<16} {:<18} {:>10} {:>8} {:>11} {:>13}",
+        "Algorithm", "Framing", "Size (KB)", "Ratio", "Write (ms)", "Lookup (us)"
+    );
+    println!("{}", "-".repeat(81));
+
+    for codec in RECORD_CODECS {
+        for strategy in [
+            Strategy::PerRecord,
+            Strategy::Stream,
+            Strategy::Batched(batch),
+        ] {
+            let name = format!("{} {}", codec.backend, codec.level);
+            let (layout, lookup_secs) = match measure(codec, &records, strategy, &lookup_indices) {
+                Ok(measured) => measured,
+                Err(error) => {
+                    println!("FAILED {} {}: {}", name, strategy.name(), error);
+                    continue;
+                }
+            };
+            println!(
+                "{:<16} {:<18} {:>10.1} {:>7.2}x {:>11.2} {:>13.1}",
+                name,
+                strategy.name(),
+                layout.file.len() as f64 / 1024.0,
+                raw_size as f64 / layout.file.len() as f64,
+                layout.write_secs * 1000.0,
+                lookup_secs * 1e6
+            );
+        }
+    }
+}
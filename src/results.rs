@@ -0,0 +1,505 @@
+use crate::counters::HardwareCounters;
+use crate::error::Result;
+use crate::memory::{Allocations, PeakRss};
+use crate::metadata::RunMetadata;
+use crate::CompressionBenchError;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+// Everything recorded for a single benchmark run
+#[derive(Default, Serialize, Deserialize)]
+pub struct RunResults {
+    pub metadata: RunMetadata,
+    pub cells: Vec<CellResult>,
+    // Cells that errored instead of producing measurements
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<CellFailure>,
+}
+
+// A (data type, algorithm, level) cell that could not be measured, and why
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CellFailure {
+    pub data_type: String,
+    pub algorithm: String,
+    pub level: String,
+    pub error: String,
+}
+
+// Averaged measurements for one (data type, algorithm, level) combination
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CellResult {
+    pub data_type: String,
+    pub algorithm: String,
+    pub level: String,
+    pub original_size: usize,
+    pub compressed_size: usize,
+    pub ratio: f64,
+    pub compress_secs: f64,
+    #[serde(default)]
+    pub decompress_secs: f64,
+    #[serde(default)]
+    pub throughput: Throughput,
+    #[serde(default)]
+    pub efficiency: Efficiency,
+    // Variation across trials; zeroed in files written before it was recorded
+    #[serde(default)]
+    pub spread: Spread,
+    #[serde(default)]
+    pub confidence: Confidence,
+    #[serde(default)]
+    pub quality: DataQuality,
+    #[serde(default)]
+    pub peak_rss: PeakRss,
+    #[serde(default)]
+    pub allocations: Allocations,
+    #[serde(default)]
+    pub counters: HardwareCounters,
+    // Individual trials behind the averages; dumped separately, not stored in results files
+    #[serde(skip)]
+    pub samples: Vec<TrialSample>,
+}
+
+// One measured trial of a cell
+#[derive(Clone, Serialize)]
+pub struct TrialSample {
+    pub trial: usize,
+    pub compressed_size: usize,
+    pub compress_secs: f64,
+    pub decompress_secs: f64,
+    // Outside the Tukey fences on compression or decompression time
+    pub outlier: bool,
+    // Left out of the cell's summary statistics
+    pub excluded: bool,
+}
+
+// How far a cell's numbers can be trusted, judged against the run's noise calibration
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DataQuality {
+    // Not assessed, e.g. files written before calibration existed
+    #[default]
+    Unknown,
+    Good,
+    // Trial-to-trial variation above the run's CV threshold
+    Noisy,
+    // Mean time too close to the timer's own overhead
+    TimerBound,
+}
+
+impl DataQuality {
+    pub fn label(self) -> &'static str {
+        match self {
+            DataQuality::Unknown => "-",
+            DataQuality::Good => "good",
+            DataQuality::Noisy => "noisy",
+            DataQuality::TimerBound => "timer-bound",
+        }
+    }
+}
+
+// Tukey fence multiplier on the interquartile range
+const TUKEY_K: f64 = 1.5;
+
+// Flags trials whose compression or decompression time falls outside the Tukey fences;
+// fewer than four trials are never flagged
+pub fn flag_outliers(samples: &mut [TrialSample]) {
+    if samples.len() < 4 {
+        return;
+    }
+    let fences = |times: Vec<f64>| {
+        let mut sorted = times;
+        sorted.sort_by(f64::total_cmp);
+        let q1 = percentile(&sorted, 0.25);
+        let q3 = percentile(&sorted, 0.75);
+        let iqr = q3 - q1;
+        (q1 - TUKEY_K * iqr, q3 + TUKEY_K * iqr)
+    };
+    let compress = fences(samples.iter().map(|s| s.compress_secs).collect());
+    let decompress = fences(samples.iter().map(|s| s.decompress_secs).collect());
+    let outside = |value: f64, (low, high): (f64, f64)| value < low || value > high;
+    for sample in samples {
+        sample.outlier =
+            outside(sample.compress_secs, compress) || outside(sample.decompress_secs, decompress);
+    }
+}
+
+// Derived rates, written out so consumers of exported files don't recompute them
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Throughput {
+    pub compress_input_mbps: f64,
+    pub compress_output_mbps: f64,
+    pub decompress_input_mbps: f64,
+    pub decompress_output_mbps: f64,
+}
+
+// Ratio and time folded into single figures of merit, assuming one decompression per compression
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Efficiency {
+    pub saved_mb_per_cpu_sec: f64,
+    pub ratio_per_cpu_sec: f64,
+}
+
+// Per-trial timing variation behind a cell's mean times
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Spread {
+    // Trials behind the summary statistics, after any outlier exclusion
+    pub trials: usize,
+    #[serde(default)]
+    pub outliers: usize,
+    #[serde(default)]
+    pub outliers_excluded: bool,
+    pub compress: TimingSpread,
+    pub decompress: TimingSpread,
+}
+
+// Sample standard deviation, extremes and percentiles of one timing, in seconds
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct TimingSpread {
+    pub stddev_secs: f64,
+    pub min_secs: f64,
+    pub max_secs: f64,
+    #[serde(default)]
+    pub median_secs: f64,
+    #[serde(default)]
+    pub p90_secs: f64,
+    #[serde(default)]
+    pub p99_secs: f64,
+}
+
+// Mean and sample standard deviation; the deviation is zero for fewer than two values
+pub fn mean_and_stddev(values: &[f64]) -> (f64, f64) {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = if values.len() > 1 {
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+    } else {
+        0.0
+    };
+    (mean, variance.sqrt())
+}
+
+// Two-sided 95% Student's t critical values for 1..=30 degrees of freedom
+const T_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+// 95% confidence intervals of the per-trial means; zeroed in files written before they were recorded
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Confidence {
+    pub ratio: Interval,
+    pub compress_mbps: Interval,
+    pub decompress_mbps: Interval,
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Interval {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Interval {
+    // t-based interval around the mean; collapses to the value itself for a single trial
+    pub fn from_samples(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Interval::default();
+        }
+        let (mean, stddev) = mean_and_stddev(values);
+        let t = T_95
+            .get(values.len().saturating_sub(2))
+            .copied()
+            .unwrap_or(1.96);
+        let half_width = if values.len() > 1 {
+            t * stddev / (values.len() as f64).sqrt()
+        } else {
+            0.0
+        };
+        Interval {
+            low: mean - half_width,
+            high: mean + half_width,
+        }
+    }
+
+    pub fn half_width(&self) -> f64 {
+        (self.high - self.low) / 2.0
+    }
+
+    // "[low, high]" at the given precision
+    pub fn describe(&self, precision: usize) -> String {
+        format!("[{:.*}, {:.*}]", precision, self.low, precision, self.high)
+    }
+}
+
+// Linear-interpolated percentile of an ascending slice, `p` in 0..=1
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let position = (sorted.len() - 1) as f64 * p;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+impl TimingSpread {
+    pub fn from_secs(secs: &[f64]) -> Self {
+        if secs.is_empty() {
+            return TimingSpread::default();
+        }
+        let (_, stddev) = mean_and_stddev(secs);
+        let mut sorted = secs.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        TimingSpread {
+            stddev_secs: stddev,
+            min_secs: sorted[0],
+            max_secs: sorted[sorted.len() - 1],
+            median_secs: percentile(&sorted, 0.5),
+            p90_secs: percentile(&sorted, 0.9),
+            p99_secs: percentile(&sorted, 0.99),
+        }
+    }
+
+    // "p50 / p90 / p99" in milliseconds
+    pub fn percentiles_ms(&self) -> String {
+        format!(
+            "{:.2} / {:.2} / {:.2}",
+            self.median_secs * 1000.0,
+            self.p90_secs * 1000.0,
+            self.p99_secs * 1000.0
+        )
+    }
+
+    // "mean ± stddev [min, max]" in milliseconds
+    pub fn describe_ms(&self, mean_secs: f64) -> String {
+        format!(
+            "{:.2} ± {:.2} [{:.2}, {:.2}]",
+            mean_secs * 1000.0,
+            self.stddev_secs * 1000.0,
+            self.min_secs * 1000.0,
+            self.max_secs * 1000.0
+        )
+    }
+}
+
+fn mbps(bytes: usize, secs: f64) -> f64 {
+    if secs > 0.0 {
+        bytes as f64 / (1024.0 * 1024.0) / secs
+    } else {
+        0.0
+    }
+}
+
+impl CellResult {
+    pub fn label(&self) -> String {
+        format!("{} {}", self.algorithm, self.level)
+    }
+
+    // Input MB processed per second of compression
+    pub fn compress_mbps(&self) -> f64 {
+        mbps(self.original_size, self.compress_secs)
+    }
+
+    // Output MB produced per second of decompression
+    pub fn decompress_mbps(&self) -> f64 {
+        mbps(self.original_size, self.decompress_secs)
+    }
+
+    // CPU-seconds spent compressing once and decompressing `reads` times
+    fn cpu_secs(&self, reads: f64) -> f64 {
+        self.compress_secs + reads * self.decompress_secs
+    }
+
+    // MB of storage or transfer avoided per CPU-second, with `reads` decompressions per write
+    pub fn saved_mb_per_cpu_sec(&self, reads: f64) -> f64 {
+        let saved = self.original_size.saturating_sub(self.compressed_size);
+        mbps(saved, self.cpu_secs(reads))
+    }
+
+    pub fn compute_efficiency(&self) -> Efficiency {
+        let cpu_secs = self.cpu_secs(1.0);
+        Efficiency {
+            saved_mb_per_cpu_sec: self.saved_mb_per_cpu_sec(1.0),
+            ratio_per_cpu_sec: if cpu_secs > 0.0 {
+                self.ratio / cpu_secs
+            } else {
+                0.0
+            },
+        }
+    }
+
+    // Trials that count towards the summary statistics
+    fn kept_samples(&self) -> impl Iterator<Item = &TrialSample> {
+        self.samples.iter().filter(|s| !s.excluded)
+    }
+
+    pub fn compute_spread(&self) -> Spread {
+        let compress: Vec<f64> = self.kept_samples().map(|s| s.compress_secs).collect();
+        let decompress: Vec<f64> = self.kept_samples().map(|s| s.decompress_secs).collect();
+        Spread {
+            trials: compress.len(),
+            outliers: self.samples.iter().filter(|s| s.outlier).count(),
+            outliers_excluded: self.samples.iter().any(|s| s.excluded),
+            compress: TimingSpread::from_secs(&compress),
+            decompress: TimingSpread::from_secs(&decompress),
+        }
+    }
+
+    pub fn compute_confidence(&self) -> Confidence {
+        let ratios: Vec<f64> = self
+            .kept_samples()
+            .map(|s| self.original_size as f64 / s.compressed_size as f64)
+            .collect();
+        let compress: Vec<f64> = self
+            .kept_samples()
+            .map(|s| mbps(self.original_size, s.compress_secs))
+            .collect();
+        let decompress: Vec<f64> = self
+            .kept_samples()
+            .map(|s| mbps(self.original_size, s.decompress_secs))
+            .collect();
+        Confidence {
+            ratio: Interval::from_samples(&ratios),
+            compress_mbps: Interval::from_samples(&compress),
+            decompress_mbps: Interval::from_samples(&decompress),
+        }
+    }
+
+    pub fn compute_throughput(&self) -> Throughput {
+        Throughput {
+            compress_input_mbps: self.compress_mbps(),
+            compress_output_mbps: mbps(self.compressed_size, self.compress_secs),
+            decompress_input_mbps: mbps(self.compressed_size, self.decompress_secs),
+            decompress_output_mbps: self.decompress_mbps(),
+        }
+    }
+}
+
+impl RunResults {
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let io_error = |source| CompressionBenchError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let mut writer = BufWriter::new(File::create(path).map_err(io_error)?);
+        serde_json::to_writer_pretty(&mut writer, self).map_err(|source| {
+            CompressionBenchError::Io {
+                path: path.to_path_buf(),
+                source: source.into(),
+            }
+        })?;
+        writer.flush().map_err(io_error)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path).map_err(|source| CompressionBenchError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut results: RunResults =
+            serde_json::from_reader(BufReader::new(file)).map_err(|source| {
+                CompressionBenchError::Parse {
+                    path: path.to_path_buf(),
+                    source,
+                }
+            })?;
+        // Files written before throughput and efficiency were exported carry zeroed rates
+        for cell in &mut results.cells {
+            cell.throughput = cell.compute_throughput();
+            cell.efficiency = cell.compute_efficiency();
+        }
+        Ok(results)
+    }
+
+    // The cell measuring the same (data type, algorithm, level) as `other`, if any
+    pub fn matching(&self, other: &CellResult) -> Option<&CellResult> {
+        self.cells.iter().find(|c| {
+            c.data_type == other.data_type
+                && c.algorithm == other.algorithm
+                && c.level == other.level
+        })
+    }
+
+    // Distinct data types in the order they were benchmarked
+    pub fn data_types(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for cell in &self.cells {
+            if !names.contains(&cell.data_type.as_str()) {
+                names.push(&cell.data_type);
+            }
+        }
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} is not within {} of {}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    fn trials(compress_secs: &[f64]) -> Vec<TrialSample> {
+        compress_secs
+            .iter()
+            .enumerate()
+            .map(|(trial, &secs)| TrialSample {
+                trial,
+                compressed_size: 0,
+                compress_secs: secs,
+                decompress_secs: 1.0,
+                outlier: false,
+                excluded: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn percentile_endpoints_are_the_extremes() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 1.0), 4.0);
+        assert_eq!(percentile(&sorted, 0.5), 2.5);
+        assert_eq!(percentile(&[7.0], 0.0), 7.0);
+        assert_eq!(percentile(&[7.0], 1.0), 7.0);
+    }
+
+    #[test]
+    fn fewer_than_four_trials_are_never_flagged() {
+        let mut samples = trials(&[1.0, 1.0, 1000.0]);
+        flag_outliers(&mut samples);
+        assert!(samples.iter().all(|s| !s.outlier));
+    }
+
+    #[test]
+    fn planted_outlier_is_flagged_alone() {
+        // Quartiles 0.975 and 1.075, so the upper fence is 1.225
+        let mut samples = trials(&[1.0, 1.1, 0.9, 1.0, 1.05, 0.95, 10.0]);
+        flag_outliers(&mut samples);
+        let flagged: Vec<usize> = samples
+            .iter()
+            .filter(|s| s.outlier)
+            .map(|s| s.trial)
+            .collect();
+        assert_eq!(flagged, [6]);
+    }
+
+    #[test]
+    fn single_trial_interval_is_the_value() {
+        let interval = Interval::from_samples(&[5.0]);
+        assert_eq!((interval.low, interval.high), (5.0, 5.0));
+    }
+
+    #[test]
+    fn two_trial_interval_uses_one_degree_of_freedom() {
+        // Mean 2 and s = sqrt(2), so the half-width is t(0.975, 1) * sqrt(2) / sqrt(2)
+        let interval = Interval::from_samples(&[1.0, 3.0]);
+        close(interval.low, 2.0 - 12.706, 1e-9);
+        close(interval.high, 2.0 + 12.706, 1e-9);
+    }
+}
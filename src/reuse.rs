@@ -0,0 +1,151 @@
+use crate::{generate_test_data, TestData};
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress};
+use std::error::Error;
+use std::hint::black_box;
+use std::io;
+use std::time::Instant;
+
+pub const DEFAULT_PAYLOADS: [usize; 4] = [256, 1024, 4096, 16 * 1024];
+// Distinct messages cycled through, so no single buffer stays hot in cache
+const DISTINCT_MESSAGES: usize = 256;
+const ZSTD_LEVEL: i32 = 3;
+
+type Call = Box<dyn FnMut(&[u8]) -> io::Result<usize>>;
+
+// One operation done with a context built per message and with one context reset between them
+struct Case {
+    name: &'static str,
+    // What each call is handed: the messages, or their compressed forms
+    inputs: Vec<Vec<u8>>,
+    fresh: Call,
+    reused: Call,
+}
+
+fn deflate(context: &mut Compress, payload: &[u8]) -> io::Result<usize> {
+    let mut output = Vec::with_capacity(payload.len() + 64);
+    context
+        .compress_vec(payload, &mut output, FlushCompress::Finish)
+        .map_err(io::Error::other)?;
+    Ok(output.len())
+}
+
+fn inflate(context: &mut Decompress, compressed: &[u8], capacity: usize) -> io::Result<usize> {
+    let mut output = Vec::with_capacity(capacity);
+    context
+        .decompress_vec(compressed, &mut output, FlushDecompress::Finish)
+        .map_err(io::Error::other)?;
+    Ok(output.len())
+}
+
+fn raw_deflate(payload: &[u8]) -> io::Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(payload.len() + 64);
+    Compress::new(Compression::default(), false)
+        .compress_vec(payload, &mut output, FlushCompress::Finish)
+        .map_err(io::Error::other)?;
+    Ok(output)
+}
+
+fn cases(messages: &[Vec<u8>], size: usize) -> io::Result<Vec<Case>> {
+    let deflated = messages
+        .iter()
+        .map(|message| raw_deflate(message))
+        .collect::<io::Result<_>>()?;
+    let frames = messages
+        .iter()
+        .map(|message| zstd::bulk::compress(message, ZSTD_LEVEL))
+        .collect::<io::Result<_>>()?;
+    let mut compress = Compress::new(Compression::default(), false);
+    let mut decompress = Decompress::new(false);
+    let mut cctx = zstd::bulk::Compressor::new(ZSTD_LEVEL)?;
+    let mut dctx = zstd::bulk::Decompressor::new()?;
+    Ok(vec![
+        Case {
+            name: "Deflate compress",
+            inputs: messages.to_vec(),
+            fresh: Box::new(|payload| {
+                deflate(&mut Compress::new(Compression::default(), false), payload)
+            }),
+            reused: Box::new(move |payload| {
+                compress.reset();
+                deflate(&mut compress, payload)
+            }),
+        },
+        Case {
+            name: "Deflate decompress",
+            inputs: deflated,
+            fresh: Box::new(move |compressed| {
+                inflate(&mut Decompress::new(false), compressed, size)
+            }),
+            reused: Box::new(move |compressed| {
+                decompress.reset(false);
+                inflate(&mut decompress, compressed, size)
+            }),
+        },
+        Case {
+            name: "Zstd compress",
+            inputs: messages.to_vec(),
+            fresh: Box::new(|payload| Ok(zstd::bulk::compress(payload, ZSTD_LEVEL)?.len())),
+            reused: Box::new(move |payload| Ok(cctx.compress(payload)?.len())),
+        },
+        Case {
+            name: "Zstd decompress",
+            inputs: frames,
+            fresh: Box::new(move |frame| Ok(zstd::bulk::decompress(frame, size)?.len())),
+            reused: Box::new(move |frame| Ok(dctx.decompress(frame, size)?.len())),
+        },
+    ])
+}
+
+// Seconds per call over `calls` calls cycling through `inputs`, and the total bytes produced
+fn time(call: &mut Call, inputs: &[Vec<u8>], calls: usize) -> io::Result<(f64, usize)> {
+    for input in inputs {
+        call(input)?;
+    }
+    let mut produced = 0;
+    let start = Instant::now();
+    for i in 0..calls {
+        produced += call(black_box(&inputs[i % inputs.len()]))?;
+    }
+    Ok((start.elapsed().as_secs_f64() / calls as f64, produced))
+}
+
+// Per-message cost of building a codec context for every payload versus resetting a kept one
+pub fn run(payloads: &[usize], calls: usize) -> Result<(), Box<dyn Error>> {
+    println!(
+        "\nContext reuse ({} calls per cell, mixed data, deflate level {}, zstd level {})",
+        calls,
+        Compression::default().level(),
+        ZSTD_LEVEL
+    );
+    for &size in payloads {
+        let messages: Vec<Vec<u8>> = (0..DISTINCT_MESSAGES.min(calls))
+            .map(|_| generate_test_data(&TestData::Mixed, size))
+            .collect();
+        println!("\n=== {} byte payloads ===\n", size);
+        println!(
+            "{:<20} {:>12} {:>12} {:>14} {:>9}",
+            "Operation", "Fresh us", "Reused us", "Overhead us", "Overhead"
+        );
+        println!("{}", "-".repeat(71));
+        for mut case in cases(&messages, size)? {
+            let (fresh, fresh_bytes) = time(&mut case.fresh, &case.inputs, calls)?;
+            let (reused, reused_bytes) = time(&mut case.reused, &case.inputs, calls)?;
+            if fresh_bytes != reused_bytes {
+                return Err(format!(
+                    "{}: reused context produced {} bytes, fresh contexts {}",
+                    case.name, reused_bytes, fresh_bytes
+                )
+                .into());
+            }
+            println!(
+                "{:<20} {:>12.2} {:>12.2} {:>14.2} {:>8.1}%",
+                case.name,
+                fresh * 1e6,
+                reused * 1e6,
+                (fresh - reused) * 1e6,
+                (fresh - reused) / fresh * 100.0
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use crate::footprint::build_crate;
+use rust_compress_comparison::harness::{generate_test_data, TEST_CASES};
+use rust_compress_comparison::results::CellResult;
+use rust_compress_comparison::suite::{CompressionStats, DATA_SIZE};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+
+// lz4_flex built with one feature set. Cargo unifies a crate's features across a build, so each
+// variant is its own probe binary rather than a row in this one
+struct Variant {
+    id: &'static str,
+    features: &'static str,
+}
+
+const VARIANTS: [Variant; 2] = [
+    Variant {
+        id: "safe",
+        features: r#"["std", "safe-encode", "safe-decode"]"#,
+    },
+    Variant {
+        id: "unsafe",
+        features: r#"["std"]"#,
+    },
+];
+
+// Round-trips the file in argv[1] argv[2] times after one untimed pass, printing
+// `compressed_size compress_secs decompress_secs` per trial, as the LZ4-Flex backend calls it
+const PROBE_MAIN: &str = r#"use std::time::Instant;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let data = std::fs::read(&args[1]).unwrap();
+    let trials: usize = args[2].parse().unwrap();
+    let compressed = lz4_flex::compress_prepend_size(&data);
+    assert!(lz4_flex::decompress_size_prepended(&compressed).unwrap() == data);
+    for _ in 0..trials {
+        let start = Instant::now();
+        let compressed = lz4_flex::compress_prepend_size(&data);
+        let compress_secs = start.elapsed().as_secs_f64();
+        let start = Instant::now();
+        let restored = lz4_flex::decompress_size_prepended(&compressed).unwrap();
+        let decompress_secs = start.elapsed().as_secs_f64();
+        assert!(restored.len() == data.len());
+        println!("{} {} {}", compressed.len(), compress_secs, decompress_secs);
+    }
+}
+"#;
+
+fn run_probe(
+    binary: &Path,
+    input: &Path,
+    trials: usize,
+) -> Result<CompressionStats, Box<dyn Error>> {
+    let output = Command::new(binary)
+        .arg(input)
+        .arg(trials.to_string())
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed ({}): {}",
+            binary.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    let original = fs::metadata(input)?.len() as usize;
+    let mut stats = CompressionStats::default();
+    for line in String::from_utf8(output.stdout)?.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [compressed, compress_secs, decompress_secs] = fields[..] else {
+            return Err(format!("unexpected probe output `{}`", line).into());
+        };
+        stats.record(original, compressed.parse()?, compress_secs.parse()?);
+        stats.record_decompression(decompress_secs.parse()?);
+    }
+    Ok(stats)
+}
+
+// The C library's block format through the lz4 crate, timed the same way in this process
+fn c_block_reference(data: &[u8], trials: usize) -> Result<CompressionStats, Box<dyn Error>> {
+    let compressed = lz4::block::compress(data, None, true)?;
+    if lz4::block::decompress(&compressed, None)? != data {
+        return Err("lz4 block round trip mismatch".into());
+    }
+    let mut stats = CompressionStats::default();
+    for _ in 0..trials {
+        let start = Instant::now();
+        let compressed = lz4::block::compress(data, None, true)?;
+        let compress_secs = start.elapsed().as_secs_f64();
+        let start = Instant::now();
+        lz4::block::decompress(&compressed, None)?;
+        stats.record(data.len(), compressed.len(), compress_secs);
+        stats.record_decompression(start.elapsed().as_secs_f64());
+    }
+    Ok(stats)
+}
+
+fn print_row(cell: &CellResult, safe: &CellResult) {
+    println!(
+        "{:<10} {:<16} {:>7.2}x {:>11.1} {:>13.1} {:>+9.1}% {:>+11.1}%",
+        cell.data_type,
+        format!("{} {}", cell.algorithm, cell.level),
+        cell.ratio,
+        cell.compress_mbps(),
+        cell.decompress_mbps(),
+        (cell.compress_mbps() / safe.compress_mbps() - 1.0) * 100.0,
+        (cell.decompress_mbps() / safe.decompress_mbps() - 1.0) * 100.0
+    );
+}
+
+// lz4_flex with its safe-encode/safe-decode features on and off, next to the C block API
+pub fn run(dir: &Path, trials: usize, offline: bool) -> Result<(), Box<dyn Error>> {
+    println!(
+        "\nBuilding lz4_flex probes in {} ({})...",
+        dir.display(),
+        VARIANTS.map(|variant| variant.id).join(", ")
+    );
+    let mut binaries = Vec::new();
+    for variant in &VARIANTS {
+        let dependencies = format!(
+            "lz4_flex = {{ version = \"0.11\", default-features = false, features = {} }}",
+            variant.features
+        );
+        let name = format!("lz4-flex-{}", variant.id);
+        let (binary, _) = build_crate(&dir.join(&name), &name, &dependencies, PROBE_MAIN, offline)?;
+        binaries.push((variant.id, binary));
+    }
+
+    println!(
+        "\n=== lz4_flex Safe vs Unsafe ({}MB per data type, {} trials) ===\n",
+        DATA_SIZE / 1024 / 1024,
+        trials
+    );
+    println!(
+        "{:<10} {:<16} {:>8} {:>11} {:>13} {:>10} {:>12}",
+        "Data", "Build", "Ratio", "Comp MB/s", "Decomp MB/s", "Comp", "Decomp"
+    );
+    println!("{}", "-".repeat(86));
+    for (data_name, data_type) in TEST_CASES {
+        let data = generate_test_data(&data_type, DATA_SIZE);
+        let input = dir.join(format!("{}.bin", data_name.to_lowercase()));
+        fs::write(&input, &data)?;
+
+        let mut cells = Vec::new();
+        for (id, binary) in &binaries {
+            let stats = run_probe(binary, &input, trials)?;
+            cells.push(stats.to_cell(data_name, "LZ4-Flex", id));
+        }
+        let reference = c_block_reference(&data, trials)?;
+        cells.push(reference.to_cell(data_name, "LZ4 (C)", "block"));
+        for cell in &cells {
+            print_row(cell, &cells[0]);
+        }
+        fs::remove_file(&input)?;
+    }
+    println!("\nComp and Decomp are relative to the safe build");
+    Ok(())
+}
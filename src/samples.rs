@@ -0,0 +1,59 @@
+use crate::results::{CellResult, TrialSample};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+// A trial flattened together with the cell it belongs to
+#[derive(Serialize)]
+struct SampleRecord<'a> {
+    data_type: &'a str,
+    algorithm: &'a str,
+    level: &'a str,
+    original_size: usize,
+    #[serde(flatten)]
+    sample: &'a TrialSample,
+}
+
+// Writes one row per trial, as CSV for `.csv` paths and JSON Lines otherwise
+pub fn write(path: &Path, cells: &[CellResult]) -> io::Result<()> {
+    let csv = path.extension().is_some_and(|ext| ext == "csv");
+    let mut writer = BufWriter::new(File::create(path)?);
+    if csv {
+        writeln!(
+            writer,
+            "data_type,algorithm,level,original_size,trial,compressed_size,compress_secs,decompress_secs,outlier,excluded"
+        )?;
+    }
+    for cell in cells {
+        for sample in &cell.samples {
+            if csv {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{},{},{},{}",
+                    cell.data_type,
+                    cell.algorithm,
+                    cell.level,
+                    cell.original_size,
+                    sample.trial,
+                    sample.compressed_size,
+                    sample.compress_secs,
+                    sample.decompress_secs,
+                    sample.outlier,
+                    sample.excluded
+                )?;
+            } else {
+                let record = SampleRecord {
+                    data_type: &cell.data_type,
+                    algorithm: &cell.algorithm,
+                    level: &cell.level,
+                    original_size: cell.original_size,
+                    sample,
+                };
+                serde_json::to_writer(&mut writer, &record)?;
+                writeln!(writer)?;
+            }
+        }
+    }
+    writer.flush()
+}
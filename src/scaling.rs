@@ -0,0 +1,79 @@
+use rust_compress_comparison::backends;
+use rust_compress_comparison::harness::TestData;
+use rust_compress_comparison::BenchmarkSuite;
+use std::error::Error;
+
+const SCALING_DATA_SIZE: usize = 1024 * 1024 * 10; // 10MB
+const SCALING_TRIALS: usize = 5;
+
+// Thread counts doubling from 1, always ending at `max_threads`
+pub fn thread_counts(max_threads: usize) -> Vec<usize> {
+    assert!(max_threads >= 1, "thread count must be at least 1");
+    let mut counts: Vec<usize> = std::iter::successors(Some(1), |n| Some(n * 2))
+        .take_while(|n| *n < max_threads)
+        .collect();
+    counts.push(max_threads);
+    counts
+}
+
+// Compression throughput of each multithreaded backend as threads are added, against 1 thread
+pub fn run(max_threads: usize) -> Result<(), Box<dyn Error>> {
+    println!(
+        "\nMultithreaded compression scaling ({}MB mixed data, {} trials, up to {} threads)\n",
+        SCALING_DATA_SIZE / 1024 / 1024,
+        SCALING_TRIALS,
+        max_threads
+    );
+    println!(
+        "{:<16} {:>8} {:>8} {:>12} {:>9} {:>11}",
+        "Algorithm", "Threads", "Ratio", "Comp MB/s", "Speedup", "Efficiency"
+    );
+    println!("{}", "-".repeat(69));
+
+    // One row per (algorithm, thread count), keyed by algorithm for the 1-thread baseline
+    let mut baselines: Vec<(String, f64)> = Vec::new();
+    let mut rows = Vec::new();
+    for threads in thread_counts(max_threads) {
+        let results = BenchmarkSuite::builder()
+            .data([TestData::Mixed])
+            .algorithms(backends::multithreaded(threads)?)
+            .data_size(SCALING_DATA_SIZE)
+            .trials(SCALING_TRIALS)
+            .run()?;
+        for failure in &results.failures {
+            println!(
+                "FAILED {} {}: {}",
+                failure.algorithm, failure.level, failure.error
+            );
+        }
+        for cell in results.cells {
+            if threads == 1 {
+                baselines.push((cell.algorithm.clone(), cell.compress_mbps()));
+            }
+            rows.push((threads, cell));
+        }
+    }
+
+    rows.sort_by(|a, b| a.1.algorithm.cmp(&b.1.algorithm).then(a.0.cmp(&b.0)));
+    for (threads, cell) in rows {
+        let baseline = baselines
+            .iter()
+            .find(|(algorithm, _)| *algorithm == cell.algorithm)
+            .map_or(0.0, |(_, mbps)| *mbps);
+        let speedup = if baseline > 0.0 {
+            cell.compress_mbps() / baseline
+        } else {
+            0.0
+        };
+        println!(
+            "{:<16} {:>8} {:>7.2}x {:>12.1} {:>8.2}x {:>10.0}%",
+            cell.algorithm,
+            threads,
+            cell.ratio,
+            cell.compress_mbps(),
+            speedup,
+            speedup / threads as f64 * 100.0
+        );
+    }
+    Ok(())
+}
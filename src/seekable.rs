@@ -0,0 +1,221 @@
+use crate::results::percentile;
+use crate::{generate_test_data, TestData};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rand::{thread_rng, Rng};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::time::Instant;
+
+// bgzip's level, zstd's default, lz4's default (fast) level
+const GZIP_LEVEL: u32 = 6;
+const ZSTD_LEVEL: i32 = 3;
+// A BGZF block holds at most 64KB compressed, so bgzip fills each with 0xff00 bytes of input
+const BGZF_BLOCK: usize = 0xff00;
+// Seek table: one compressed and one decompressed u32 size per frame, a 9-byte footer, all in a
+// skippable frame with an 8-byte header
+const ZSTD_SEEK_ENTRY: usize = 8;
+const ZSTD_SEEK_FIXED: usize = 17;
+// A .gzi-style index: a compressed and an uncompressed u64 offset per block
+const OFFSET_PAIR: usize = 16;
+
+// A format built from independently decompressible blocks, located through an index
+struct Format {
+    name: &'static str,
+    // Fixed by the format, or None to use --frame-kb
+    block: Option<usize>,
+    index_bytes: fn(usize) -> usize,
+    compress: fn(&[u8]) -> io::Result<Vec<u8>>,
+    decompress: fn(&[u8], usize) -> io::Result<Vec<u8>>,
+}
+
+fn zstd_compress(block: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::bulk::compress(block, ZSTD_LEVEL)
+}
+
+fn zstd_decompress(compressed: &[u8], size: usize) -> io::Result<Vec<u8>> {
+    zstd::bulk::decompress(compressed, size)
+}
+
+fn gzip(block: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(GZIP_LEVEL));
+    encoder.write_all(block)?;
+    encoder.finish()
+}
+
+fn gunzip(compressed: &[u8], size: usize) -> io::Result<Vec<u8>> {
+    let mut restored = Vec::with_capacity(size);
+    GzDecoder::new(compressed).read_to_end(&mut restored)?;
+    Ok(restored)
+}
+
+fn lz4_compress(block: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = lz4::EncoderBuilder::new().build(Vec::new())?;
+    encoder.write_all(block)?;
+    let (output, result) = encoder.finish();
+    result.map(|()| output)
+}
+
+fn lz4_decompress(compressed: &[u8], size: usize) -> io::Result<Vec<u8>> {
+    let mut restored = Vec::with_capacity(size);
+    lz4::Decoder::new(compressed)?.read_to_end(&mut restored)?;
+    Ok(restored)
+}
+
+const FORMATS: [Format; 3] = [
+    Format {
+        name: "zstd seekable",
+        block: None,
+        index_bytes: |blocks| blocks * ZSTD_SEEK_ENTRY + ZSTD_SEEK_FIXED,
+        compress: zstd_compress,
+        decompress: zstd_decompress,
+    },
+    Format {
+        name: "BGZF",
+        block: Some(BGZF_BLOCK),
+        index_bytes: |blocks| blocks * OFFSET_PAIR,
+        compress: gzip,
+        decompress: gunzip,
+    },
+    Format {
+        name: "lz4 frames",
+        block: None,
+        index_bytes: |blocks| blocks * OFFSET_PAIR,
+        compress: lz4_compress,
+        decompress: lz4_decompress,
+    },
+];
+
+// A blocked file on disk and the in-memory index a reader loads once when opening it
+struct Blocked {
+    file: File,
+    block: usize,
+    // Compressed offset and length of each block
+    index: Vec<(u64, usize)>,
+}
+
+impl Blocked {
+    fn write(format: &Format, data: &[u8], block: usize) -> io::Result<Self> {
+        let path =
+            std::env::temp_dir().join(format!("compress-seekable-{}.bin", std::process::id()));
+        let mut file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        // The handle keeps the file alive on unix; elsewhere it is left behind in temp
+        let _ = fs::remove_file(&path);
+        let mut index = Vec::with_capacity(data.len().div_ceil(block));
+        let mut offset = 0;
+        for chunk in data.chunks(block) {
+            let compressed = (format.compress)(chunk)?;
+            file.write_all(&compressed)?;
+            index.push((offset, compressed.len()));
+            offset += compressed.len() as u64;
+        }
+        file.flush()?;
+        Ok(Blocked { file, block, index })
+    }
+
+    fn compressed_size(&self) -> usize {
+        self.index.iter().map(|&(_, len)| len).sum()
+    }
+
+    // `len` bytes at `offset`, reading and decompressing every block the range touches; returns
+    // the bytes decompressed alongside
+    fn read_range(
+        &mut self,
+        format: &Format,
+        total: usize,
+        offset: usize,
+        len: usize,
+    ) -> io::Result<(Vec<u8>, usize)> {
+        let mut range = Vec::with_capacity(len);
+        let mut decompressed = 0;
+        let mut compressed = Vec::new();
+        for block in offset / self.block..(offset + len).div_ceil(self.block) {
+            let (at, size) = self.index[block];
+            compressed.resize(size, 0);
+            self.file.seek(SeekFrom::Start(at))?;
+            self.file.read_exact(&mut compressed)?;
+            let start = block * self.block;
+            let restored = (format.decompress)(&compressed, self.block.min(total - start))?;
+            decompressed += restored.len();
+            let from = offset.max(start) - start;
+            let to = (offset + len).min(start + restored.len()) - start;
+            range.extend_from_slice(&restored[from..to]);
+        }
+        Ok((range, decompressed))
+    }
+}
+
+// Random-range reads from blocked compressed files against the ratio their blocking costs
+pub fn run(
+    size_mb: usize,
+    frame_kb: usize,
+    read_kb: usize,
+    reads: usize,
+) -> Result<(), Box<dyn Error>> {
+    let data = generate_test_data(&TestData::Mixed, size_mb * 1024 * 1024);
+    let read_len = (read_kb * 1024).min(data.len());
+    let mut rng = thread_rng();
+    let offsets: Vec<usize> = (0..reads)
+        .map(|_| rng.gen_range(0..=data.len() - read_len))
+        .collect();
+
+    println!(
+        "\nSeekable formats ({}MB mixed data, {} random {}KB reads)\n",
+        size_mb, reads, read_kb
+    );
+    println!(
+        "{:<14} {:>8} {:>7} {:>10} {:>10} {:>10} {:>10} {:>14}",
+        "Format", "Block", "Ratio", "Overhead", "Build s", "Read p50", "Read p99", "Decomp/read"
+    );
+    println!("{}", "-".repeat(90));
+    for format in &FORMATS {
+        let block = format.block.unwrap_or(frame_kb * 1024);
+        let start = Instant::now();
+        let mut blocked = Blocked::write(format, &data, block)?;
+        let build_secs = start.elapsed().as_secs_f64();
+        let stored = blocked.compressed_size() + (format.index_bytes)(blocked.index.len());
+        // The unblocked reference: the same codec over the whole input, which can only be read
+        // from the start
+        let single = (format.compress)(&data)?.len();
+
+        let mut secs = Vec::with_capacity(reads);
+        let mut decompressed = 0;
+        for &offset in &offsets {
+            let start = Instant::now();
+            let (range, bytes) = blocked.read_range(format, data.len(), offset, read_len)?;
+            secs.push(start.elapsed().as_secs_f64());
+            if range != data[offset..offset + read_len] {
+                return Err(format!(
+                    "{} read at {} returned the wrong bytes",
+                    format.name, offset
+                )
+                .into());
+            }
+            decompressed += bytes;
+        }
+        secs.sort_by(f64::total_cmp);
+        println!(
+            "{:<14} {:>6}KB {:>6.2}x {:>9.2}% {:>10.2} {:>8.1}us {:>8.1}us {:>12.0}KB",
+            format.name,
+            block / 1024,
+            data.len() as f64 / stored as f64,
+            (stored as f64 / single as f64 - 1.0) * 100.0,
+            build_secs,
+            percentile(&secs, 0.5) * 1e6,
+            percentile(&secs, 0.99) * 1e6,
+            decompressed as f64 / reads as f64 / 1024.0
+        );
+    }
+    println!(
+        "\nOverhead: stored size, index included, over the same codec compressing the data as one \
+         stream. Reads come from a file the page cache still holds"
+    );
+    Ok(())
+}
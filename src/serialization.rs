@@ -0,0 +1,196 @@
+use crate::records::generate_records;
+use crate::results::percentile;
+use rust_compress_comparison::backends;
+use rust_compress_comparison::Compressor;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::time::Instant;
+
+// One log event, as the application holds it before serializing
+#[derive(Clone, PartialEq, Serialize, Deserialize, prost::Message)]
+struct Event {
+    #[prost(uint64, tag = "1")]
+    ts: u64,
+    #[prost(string, tag = "2")]
+    level: String,
+    #[prost(string, tag = "3")]
+    service: String,
+    #[prost(string, tag = "4")]
+    msg: String,
+    #[prost(uint32, tag = "5")]
+    user_id: u32,
+    #[prost(double, tag = "6")]
+    latency_ms: f64,
+    #[prost(string, tag = "7")]
+    trace_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Batch {
+    #[prost(message, repeated, tag = "1")]
+    events: Vec<Event>,
+}
+
+type Outcome<T> = Result<T, Box<dyn Error>>;
+
+// A wire format the whole batch is serialized into as one payload
+struct Serializer {
+    name: &'static str,
+    serialize: fn(&[Event]) -> Outcome<Vec<u8>>,
+    deserialize: fn(&[u8]) -> Outcome<Vec<Event>>,
+}
+
+const SERIALIZERS: [Serializer; 3] = [
+    Serializer {
+        name: "serde_json",
+        serialize: |events| Ok(serde_json::to_vec(events)?),
+        deserialize: |bytes| Ok(serde_json::from_slice(bytes)?),
+    },
+    Serializer {
+        name: "bincode",
+        serialize: |events| Ok(bincode::serialize(events)?),
+        deserialize: |bytes| Ok(bincode::deserialize(bytes)?),
+    },
+    Serializer {
+        name: "prost",
+        // Field by field as `Batch` would encode, without cloning the events into one first
+        serialize: |events| {
+            let mut bytes = Vec::new();
+            for event in events {
+                prost::encoding::message::encode(1, event, &mut bytes);
+            }
+            Ok(bytes)
+        },
+        deserialize: |bytes| {
+            use prost::Message;
+            Ok(Batch::decode(bytes)?.events)
+        },
+    },
+];
+
+struct Measured {
+    serialized: usize,
+    compressed: usize,
+    // Median seconds to serialize then compress, and to decompress then deserialize
+    write: f64,
+    read: f64,
+}
+
+fn measure(
+    serializer: &Serializer,
+    codec: Option<&dyn Compressor>,
+    events: &[Event],
+    trials: usize,
+) -> Outcome<Measured> {
+    let mut serialized = 0;
+    let mut compressed = 0;
+    let mut write = Vec::with_capacity(trials);
+    let mut read = Vec::with_capacity(trials);
+    for _ in 0..trials {
+        let start = Instant::now();
+        let bytes = (serializer.serialize)(events)?;
+        let payload = match codec {
+            Some(codec) => codec.compress(&bytes)?,
+            None => bytes.clone(),
+        };
+        write.push(start.elapsed().as_secs_f64());
+
+        let start = Instant::now();
+        let restored = match codec {
+            Some(codec) => codec.decompress(&payload)?,
+            None => payload.clone(),
+        };
+        let decoded = (serializer.deserialize)(&restored)?;
+        read.push(start.elapsed().as_secs_f64());
+        if decoded != events {
+            return Err(format!("{} round trip mismatch", serializer.name).into());
+        }
+        serialized = bytes.len();
+        compressed = payload.len();
+    }
+    write.sort_by(f64::total_cmp);
+    read.sort_by(f64::total_cmp);
+    Ok(Measured {
+        serialized,
+        compressed,
+        write: percentile(&write, 0.5),
+        read: percentile(&read, 0.5),
+    })
+}
+
+// The (serializer, compressor) matrix for one batch of events: final size and combined time
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    record_count: usize,
+    trials: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::REGISTRY
+            .iter()
+            .filter_map(|backend| backend.level("Default"))
+            .collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let events = generate_records(record_count)
+        .iter()
+        .map(|record| serde_json::from_slice(record))
+        .collect::<Result<Vec<Event>, _>>()?;
+    println!(
+        "\nSerialize then compress ({} events in one batch, median of {} trials)\n",
+        record_count, trials
+    );
+    println!(
+        "{:<11} {:<18} {:>11} {:>11} {:>8} {:>10} {:>10}",
+        "Serializer", "Compressor", "Serialized", "Final", "vs JSON", "Write ms", "Read ms"
+    );
+    println!("{}", "-".repeat(85));
+
+    let mut json_size = None;
+    let mut smallest: Option<(String, usize)> = None;
+    let mut fastest: Option<(String, f64)> = None;
+    for serializer in &SERIALIZERS {
+        let codecs = std::iter::once(None).chain(algorithms.iter().map(|codec| Some(*codec)));
+        for codec in codecs {
+            let label = codec.map_or("none".to_string(), |codec| {
+                format!("{} {}", codec.name(), codec.level())
+            });
+            let measured = match measure(serializer, codec, &events, trials) {
+                Ok(measured) => measured,
+                Err(e) => {
+                    println!("{:<11} {:<18} FAILED: {}", serializer.name, label, e);
+                    continue;
+                }
+            };
+            let json_size = *json_size.get_or_insert(measured.serialized);
+            println!(
+                "{:<11} {:<18} {:>9}KB {:>9}KB {:>7.1}% {:>10.2} {:>10.2}",
+                serializer.name,
+                label,
+                measured.serialized / 1024,
+                measured.compressed / 1024,
+                measured.compressed as f64 / json_size as f64 * 100.0,
+                measured.write * 1e3,
+                measured.read * 1e3
+            );
+            let pair = format!("{} + {}", serializer.name, label);
+            if smallest
+                .as_ref()
+                .is_none_or(|(_, size)| measured.compressed < *size)
+            {
+                smallest = Some((pair.clone(), measured.compressed));
+            }
+            if fastest
+                .as_ref()
+                .is_none_or(|(_, secs)| measured.write < *secs)
+            {
+                fastest = Some((pair, measured.write));
+            }
+        }
+    }
+    if let (Some((smallest, _)), Some((fastest, _))) = (smallest, fastest) {
+        println!("\nSmallest: {}\nFastest write: {}", smallest, fastest);
+    }
+    println!("\nvs JSON: final size against the uncompressed serde_json batch");
+    Ok(())
+}
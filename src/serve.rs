@@ -0,0 +1,237 @@
+use crate::metadata::RunMetadata;
+use crate::results::{CellFailure, RunResults};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::config::SuiteConfig;
+use rust_compress_comparison::harness;
+use rust_compress_comparison::suite::CompressionStats;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::io::{Cursor, Read};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// Data type name the cells of a POSTed payload are reported under
+const PAYLOAD: &str = "Payload";
+
+type Reply = Response<Cursor<Vec<u8>>>;
+
+fn json(status: u16, body: String) -> Reply {
+    Response::from_string(body)
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").expect("valid header"))
+}
+
+fn error(status: u16, message: impl std::fmt::Display) -> Reply {
+    json(
+        status,
+        serde_json::json!({ "error": message.to_string() }).to_string(),
+    )
+}
+
+// Undoes the %XX escapes clients put in query values, e.g. `gzip%3Abest`
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn query(url: &str) -> Vec<(String, String)> {
+    let Some((_, query)) = url.split_once('?') else {
+        return Vec::new();
+    };
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn read_body(request: &mut Request, max_bytes: usize) -> Result<Vec<u8>, Reply> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| error(400, format!("could not read request body: {}", e)))?;
+    if body.len() > max_bytes {
+        return Err(error(
+            413,
+            format!("body is larger than the {} byte limit", max_bytes),
+        ));
+    }
+    Ok(body)
+}
+
+// Every configuration, or those named by `algorithm=BACKEND[:LEVEL]`, round-tripped over the
+// payload `trials` times (or `trials=N`, up to `max_trials`) after one untimed, verified pass
+fn compare_payload(
+    payload: &[u8],
+    params: &[(String, String)],
+    default_trials: usize,
+    max_trials: usize,
+) -> Result<RunResults, Reply> {
+    let mut algorithms: Vec<&'static dyn Compressor> = Vec::new();
+    let mut trials = default_trials;
+    for (key, value) in params {
+        match key.as_str() {
+            "algorithm" => algorithms.push(backends::find(value).map_err(|e| error(400, e))?),
+            "trials" => {
+                trials = value
+                    .parse()
+                    .ok()
+                    .filter(|&trials| trials > 0)
+                    .ok_or_else(|| error(400, format!("invalid trials `{}`", value)))?;
+                if trials > max_trials {
+                    return Err(error(
+                        400,
+                        format!("trials is above the limit of {}", max_trials),
+                    ));
+                }
+            }
+            _ => return Err(error(400, format!("unknown parameter `{}`", key))),
+        }
+    }
+    if algorithms.is_empty() {
+        algorithms = backends::configurations().collect();
+    }
+
+    let mut results = RunResults {
+        metadata: RunMetadata::new(Vec::new(), Vec::new()),
+        cells: Vec::new(),
+        failures: Vec::new(),
+    };
+    results.metadata.verified = true;
+    for codec in algorithms {
+        let mut stats = CompressionStats::default();
+        let outcome = harness::round_trip(codec, payload, true).and_then(|_| {
+            for _ in 0..trials {
+                let trial = harness::round_trip(codec, payload, false)?;
+                stats.record(payload.len(), trial.compressed_size, trial.compress_secs);
+                stats.record_decompression(trial.decompress_secs);
+            }
+            Ok(())
+        });
+        match outcome {
+            Ok(()) => results
+                .cells
+                .push(stats.to_cell(PAYLOAD, codec.name(), &codec.level())),
+            Err(e) => results.failures.push(CellFailure {
+                data_type: PAYLOAD.to_string(),
+                algorithm: codec.name().to_string(),
+                level: codec.level(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    Ok(results)
+}
+
+// A suite described by a `SuiteConfig` JSON body, as `runBenchmark` and `ccb_run` take it
+fn run_config(body: &[u8], max_bytes: usize, max_trials: usize) -> Result<RunResults, Reply> {
+    let body = std::str::from_utf8(body).map_err(|e| error(400, e))?;
+    let config = SuiteConfig::from_json(body).map_err(|e| error(400, e))?;
+    if config.data_size.is_some_and(|size| size > max_bytes) {
+        return Err(error(
+            413,
+            format!("data_size is larger than the {} byte limit", max_bytes),
+        ));
+    }
+    for (name, count) in [("trials", config.trials), ("warmup", config.warmup)] {
+        if count.is_some_and(|count| count > max_trials) {
+            return Err(error(
+                400,
+                format!("{} is above the limit of {}", name, max_trials),
+            ));
+        }
+    }
+    let builder = config.builder().map_err(|e| error(400, e))?;
+    builder.run().map_err(|e| error(500, e))
+}
+
+fn list_backends() -> String {
+    let backends: Vec<serde_json::Value> = backends::REGISTRY
+        .iter()
+        .map(|backend| {
+            let levels: Vec<String> = backend.levels.iter().map(|codec| codec.level()).collect();
+            serde_json::json!({ "id": backend.id, "levels": levels })
+        })
+        .collect();
+    serde_json::Value::from(backends).to_string()
+}
+
+fn handle(request: &mut Request, max_bytes: usize, trials: usize, max_trials: usize) -> Reply {
+    let url = request.url().to_string();
+    let path = url.split('?').next().unwrap_or_default();
+    let results = match (request.method(), path) {
+        (Method::Get, "/backends") => return json(200, list_backends()),
+        (Method::Post, "/compare") => read_body(request, max_bytes).and_then(|payload| {
+            if payload.is_empty() {
+                return Err(error(
+                    400,
+                    "POST the payload to compare as the request body",
+                ));
+            }
+            compare_payload(&payload, &query(&url), trials, max_trials)
+        }),
+        (Method::Post, "/run") => {
+            read_body(request, max_bytes).and_then(|body| run_config(&body, max_bytes, max_trials))
+        }
+        (_, "/backends" | "/compare" | "/run") => Err(error(405, "method not allowed")),
+        _ => Err(error(404, "not found")),
+    };
+    match results.map(|results| serde_json::to_string(&results)) {
+        Ok(Ok(body)) => json(200, body),
+        Ok(Err(e)) => error(500, e),
+        Err(reply) => reply,
+    }
+}
+
+// Answers one request at a time, so concurrent callers queue instead of skewing each other's
+// timings
+pub fn run(
+    listen: &str,
+    max_bytes: usize,
+    trials: usize,
+    max_trials: usize,
+) -> Result<(), Box<dyn Error>> {
+    if trials > max_trials {
+        return Err(format!("--trials {} is above --max-trials {}", trials, max_trials).into());
+    }
+    let server =
+        Server::http(listen).map_err(|e| format!("could not listen on {}: {}", listen, e))?;
+    println!(
+        "Serving comparisons at http://{} (POST /compare, POST /run, GET /backends)",
+        server.server_addr()
+    );
+    for mut request in server.incoming_requests() {
+        println!("{} {}", request.method(), request.url());
+        let response = handle(&mut request, max_bytes, trials, max_trials);
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to send response: {}", e);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use crate::records::generate_records;
+use crate::{generate_test_data, TestData};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use lz4_flex::frame::{FrameDecoder, FrameEncoder};
+use std::error::Error;
+use std::hint::black_box;
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+use std::time::Instant;
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
+
+pub const DEFAULT_PAYLOADS: [usize; 4] = [256, 4096, 64 * 1024, 1024 * 1024];
+const LZ4_LEVEL: u32 = 4;
+const ZSTD_LEVEL: i32 = 3;
+const DICT_SIZE: usize = 16 * 1024;
+const TRAINING_RECORDS: usize = 1000;
+
+type Construct = Box<dyn Fn() -> io::Result<()>>;
+type Process = Box<dyn Fn(&[u8]) -> io::Result<usize>>;
+type Prepare = Box<dyn Fn(&[u8]) -> io::Result<Vec<u8>>>;
+
+// An encoder or decoder timed on its own and together with the work it is built for
+struct Setup {
+    name: &'static str,
+    // Builds the encoder or decoder and drops it unused
+    construct: Construct,
+    // Builds one and runs it over an input, returning the bytes produced
+    process: Process,
+    // Turns a message into what `process` is fed: the message for encoders, its compressed
+    // form for decoders
+    prepare: Prepare,
+}
+
+fn read_all<R: Read>(mut decoder: R) -> io::Result<usize> {
+    let mut output = Vec::new();
+    decoder.read_to_end(&mut output)
+}
+
+fn gzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+fn lz4(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = lz4::EncoderBuilder::new()
+        .level(LZ4_LEVEL)
+        .build(Vec::new())?;
+    encoder.write_all(data)?;
+    let (output, result) = encoder.finish();
+    result.map(|()| output)
+}
+
+fn lz4_flex(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = FrameEncoder::new(Vec::new());
+    encoder.write_all(data)?;
+    encoder.finish().map_err(io::Error::other)
+}
+
+fn zstd_with(dictionary: &EncoderDictionary, data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder =
+        zstd::stream::write::Encoder::with_prepared_dictionary(Vec::new(), dictionary)?;
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+fn setups(dictionary: &[u8]) -> Vec<Setup> {
+    let dictionary: Arc<[u8]> = dictionary.into();
+    let identity: fn() -> Prepare = || Box::new(|data| Ok(data.to_vec()));
+    let (encoder_dict, process_encoder_dict) = (dictionary.clone(), dictionary.clone());
+    let (decoder_dict, process_decoder_dict) = (dictionary.clone(), dictionary.clone());
+    let prepare_dict = dictionary;
+    vec![
+        Setup {
+            name: "GzEncoder::new",
+            construct: Box::new(|| {
+                black_box(GzEncoder::new(Vec::new(), Compression::default()));
+                Ok(())
+            }),
+            process: Box::new(|data| Ok(gzip(data)?.len())),
+            prepare: identity(),
+        },
+        Setup {
+            name: "GzDecoder::new",
+            construct: Box::new(|| {
+                black_box(GzDecoder::new(&[][..]));
+                Ok(())
+            }),
+            process: Box::new(|data| read_all(GzDecoder::new(data))),
+            prepare: Box::new(gzip),
+        },
+        Setup {
+            name: "lz4 EncoderBuilder::build",
+            construct: Box::new(|| {
+                black_box(
+                    lz4::EncoderBuilder::new()
+                        .level(LZ4_LEVEL)
+                        .build(Vec::new())?,
+                );
+                Ok(())
+            }),
+            process: Box::new(|data| Ok(lz4(data)?.len())),
+            prepare: identity(),
+        },
+        Setup {
+            name: "lz4 Decoder::new",
+            construct: Box::new(|| {
+                black_box(lz4::Decoder::new(&[][..])?);
+                Ok(())
+            }),
+            process: Box::new(|data| read_all(lz4::Decoder::new(data)?)),
+            prepare: Box::new(lz4),
+        },
+        Setup {
+            name: "lz4_flex FrameEncoder::new",
+            construct: Box::new(|| {
+                black_box(FrameEncoder::new(Vec::new()));
+                Ok(())
+            }),
+            process: Box::new(|data| Ok(lz4_flex(data)?.len())),
+            prepare: identity(),
+        },
+        Setup {
+            name: "lz4_flex FrameDecoder::new",
+            construct: Box::new(|| {
+                black_box(FrameDecoder::new(&[][..]));
+                Ok(())
+            }),
+            process: Box::new(|data| read_all(FrameDecoder::new(data))),
+            prepare: Box::new(lz4_flex),
+        },
+        Setup {
+            name: "zstd Encoder::new",
+            construct: Box::new(|| {
+                black_box(zstd::stream::write::Encoder::new(Vec::new(), ZSTD_LEVEL)?);
+                Ok(())
+            }),
+            process: Box::new(|data| Ok(zstd::stream::encode_all(data, ZSTD_LEVEL)?.len())),
+            prepare: identity(),
+        },
+        Setup {
+            name: "zstd Decoder::new",
+            construct: Box::new(|| {
+                black_box(zstd::stream::read::Decoder::new(&[][..])?);
+                Ok(())
+            }),
+            process: Box::new(|data| read_all(zstd::stream::read::Decoder::new(data)?)),
+            prepare: Box::new(|data| zstd::stream::encode_all(data, ZSTD_LEVEL)),
+        },
+        Setup {
+            name: "zstd dictionary load (compress)",
+            construct: Box::new(move || {
+                black_box(EncoderDictionary::copy(&encoder_dict, ZSTD_LEVEL));
+                Ok(())
+            }),
+            process: Box::new(move |data| {
+                let dictionary = EncoderDictionary::copy(&process_encoder_dict, ZSTD_LEVEL);
+                Ok(zstd_with(&dictionary, data)?.len())
+            }),
+            prepare: identity(),
+        },
+        Setup {
+            name: "zstd dictionary load (decompress)",
+            construct: Box::new(move || {
+                black_box(DecoderDictionary::copy(&decoder_dict));
+                Ok(())
+            }),
+            process: Box::new(move |data| {
+                let dictionary = DecoderDictionary::copy(&process_decoder_dict);
+                read_all(zstd::stream::read::Decoder::with_prepared_dictionary(
+                    data,
+                    &dictionary,
+                )?)
+            }),
+            prepare: Box::new(move |data| {
+                zstd_with(&EncoderDictionary::copy(&prepare_dict, ZSTD_LEVEL), data)
+            }),
+        },
+    ]
+}
+
+// Mean seconds per call of `call` over `iterations` calls, after one untimed call
+fn mean_secs(iterations: usize, mut call: impl FnMut() -> io::Result<usize>) -> io::Result<f64> {
+    call()?;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(call()?);
+    }
+    Ok(start.elapsed().as_secs_f64() / iterations as f64)
+}
+
+// Cost of building each encoder and decoder on its own, and its share of a whole call per size
+pub fn run(payloads: &[usize], iterations: usize) -> Result<(), Box<dyn Error>> {
+    let dictionary = zstd::dict::from_samples(&generate_records(TRAINING_RECORDS), DICT_SIZE)?;
+    let setups = setups(&dictionary);
+
+    println!(
+        "\nEncoder/decoder construction ({} iterations, build + drop, {}KB zstd dictionary)\n",
+        iterations,
+        dictionary.len() / 1024
+    );
+    println!("{:<34} {:>13}", "Constructor", "Construct us");
+    println!("{}", "-".repeat(48));
+    let mut construct_secs = Vec::with_capacity(setups.len());
+    for setup in &setups {
+        let secs = mean_secs(iterations, || (setup.construct)().map(|()| 0))?;
+        println!("{:<34} {:>13.2}", setup.name, secs * 1e6);
+        construct_secs.push(secs);
+    }
+    println!(
+        "\nlz4_flex frames and zstd contexts allocate their working memory on first use, so for \
+         those most of the setup cost lands in Work below"
+    );
+
+    for &size in payloads {
+        let message = generate_test_data(&TestData::Mixed, size);
+        // Large payloads take long enough per call that fewer iterations give the same precision
+        let calls = (iterations * 4096 / size.max(4096)).max(10);
+        println!("\n=== {} byte payloads ({} calls) ===\n", size, calls);
+        println!(
+            "{:<34} {:>12} {:>12} {:>12}",
+            "Constructor", "Call us", "Work us", "Setup share"
+        );
+        println!("{}", "-".repeat(73));
+        for (setup, construct) in setups.iter().zip(&construct_secs) {
+            let input = (setup.prepare)(&message)?;
+            let call = mean_secs(calls, || (setup.process)(&input))?;
+            println!(
+                "{:<34} {:>12.2} {:>12.2} {:>11.1}%",
+                setup.name,
+                call * 1e6,
+                (call - construct).max(0.0) * 1e6,
+                (construct / call * 100.0).min(100.0)
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::results::mean_and_stddev;
+use crate::{generate_test_data, TestData};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::harness;
+use rust_compress_comparison::memory;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const BLOCK_SIZE: usize = 1024 * 1024; // 1MB
+                                       // Distinct blocks cycled through so the stream isn't one buffer sitting in cache
+const BLOCK_COUNT: usize = 32;
+
+// Throughput and resident memory over one sampling window
+struct Window {
+    end_secs: f64,
+    mbps: f64,
+    rss_bytes: Option<u64>,
+}
+
+struct SoakSummary {
+    label: String,
+    windows: Vec<Window>,
+}
+
+fn mb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+// Compresses blocks back to back for `duration`, printing a sample every `interval`
+fn soak(
+    codec: &dyn Compressor,
+    blocks: &[Vec<u8>],
+    duration: Duration,
+    interval: Duration,
+) -> Result<SoakSummary, Box<dyn Error>> {
+    let label = format!("{} {}", codec.name(), codec.level());
+    let mut windows = Vec::new();
+    let start = Instant::now();
+    let mut window_start = start;
+    let mut window_bytes = 0usize;
+    let mut next = 0;
+    while start.elapsed() < duration {
+        black_box(harness::compress(codec, &blocks[next])?);
+        window_bytes += blocks[next].len();
+        next = (next + 1) % blocks.len();
+
+        let now = Instant::now();
+        if now.duration_since(window_start) >= interval || now.duration_since(start) >= duration {
+            let window = Window {
+                end_secs: now.duration_since(start).as_secs_f64(),
+                mbps: window_bytes as f64
+                    / (1024.0 * 1024.0)
+                    / now.duration_since(window_start).as_secs_f64(),
+                rss_bytes: memory::resident_bytes(),
+            };
+            println!(
+                "[{:>7.0}s] {:<18} {:>10.1} MB/s   RSS {}",
+                window.end_secs,
+                label,
+                window.mbps,
+                window
+                    .rss_bytes
+                    .map_or("-".to_string(), |rss| format!("{:.1} MB", mb(rss)))
+            );
+            windows.push(window);
+            window_start = now;
+            window_bytes = 0;
+        }
+    }
+    Ok(SoakSummary { label, windows })
+}
+
+// Compresses a continuous stream per configuration for a fixed wall-clock time, so throttling
+// and allocator fragmentation show up as throughput or RSS drifting across the run
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    duration: Duration,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::REGISTRY
+            .iter()
+            .filter_map(|backend| backend.level("Default"))
+            .collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let blocks: Vec<Vec<u8>> = (0..BLOCK_COUNT)
+        .map(|_| generate_test_data(&TestData::Mixed, BLOCK_SIZE))
+        .collect();
+
+    println!(
+        "\nSoak: {} per configuration, sampled every {} ({}MB mixed blocks)\n",
+        humantime::format_duration(duration),
+        humantime::format_duration(interval),
+        BLOCK_SIZE / 1024 / 1024
+    );
+    let mut summaries = Vec::new();
+    for codec in algorithms {
+        summaries.push(soak(codec, &blocks, duration, interval)?);
+    }
+
+    println!("\n=== Soak Summary ===\n");
+    println!(
+        "{:<18} {:>8} {:>11} {:>11} {:>9} {:>11} {:>8} {:>12}",
+        "Configuration",
+        "Windows",
+        "First MB/s",
+        "Last MB/s",
+        "Drift",
+        "Mean MB/s",
+        "CV",
+        "RSS growth"
+    );
+    println!("{}", "-".repeat(96));
+    for summary in &summaries {
+        let (Some(first), Some(last)) = (summary.windows.first(), summary.windows.last()) else {
+            continue;
+        };
+        let mbps: Vec<f64> = summary.windows.iter().map(|w| w.mbps).collect();
+        let (mean, stddev) = mean_and_stddev(&mbps);
+        let rss_growth = match (first.rss_bytes, last.rss_bytes) {
+            (Some(first), Some(last)) => format!("{:+.1} MB", mb(last) - mb(first)),
+            _ => "-".to_string(),
+        };
+        println!(
+            "{:<18} {:>8} {:>11.1} {:>11.1} {:>8.1}% {:>11.1} {:>7.1}% {:>12}",
+            summary.label,
+            summary.windows.len(),
+            first.mbps,
+            last.mbps,
+            (last.mbps - first.mbps) / first.mbps * 100.0,
+            mean,
+            if mean > 0.0 {
+                stddev / mean * 100.0
+            } else {
+                0.0
+            },
+            rss_growth
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use crate::results::RunResults;
+use rusqlite::{params, Connection};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    recorded_at TEXT NOT NULL DEFAULT (datetime('now')),
+    git_commit TEXT NOT NULL,
+    crate_versions TEXT NOT NULL,
+    hostname TEXT,
+    cpu_model TEXT NOT NULL,
+    cores INTEGER NOT NULL,
+    os TEXT NOT NULL,
+    arch TEXT NOT NULL,
+    labels TEXT NOT NULL,
+    notes TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS cells (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    data_type TEXT NOT NULL,
+    algorithm TEXT NOT NULL,
+    level TEXT NOT NULL,
+    original_size INTEGER NOT NULL,
+    compressed_size INTEGER NOT NULL,
+    ratio REAL NOT NULL,
+    compress_secs REAL NOT NULL,
+    decompress_secs REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS cells_by_config ON cells (data_type, algorithm, level);
+";
+
+fn open(path: &Path) -> rusqlite::Result<Connection> {
+    let connection = Connection::open(path)?;
+    connection.execute_batch(SCHEMA)?;
+    Ok(connection)
+}
+
+// Appends a run with its build and host details; returns the new run id
+pub fn append(path: &Path, results: &RunResults) -> Result<i64, Box<dyn std::error::Error>> {
+    let mut connection = open(path)?;
+    let transaction = connection.transaction()?;
+    let metadata = &results.metadata;
+    transaction.execute(
+        "INSERT INTO runs (git_commit, crate_versions, hostname, cpu_model, cores, os, arch, labels, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            env!("GIT_COMMIT"),
+            env!("CODEC_CRATE_VERSIONS"),
+            metadata.machine.hostname,
+            metadata.machine.cpu_model,
+            metadata.machine.cores,
+            metadata.machine.os,
+            metadata.machine.arch,
+            serde_json::to_string(&metadata.labels)?,
+            serde_json::to_string(&metadata.notes)?,
+        ],
+    )?;
+    let run_id = transaction.last_insert_rowid();
+    {
+        let mut insert = transaction.prepare(
+            "INSERT INTO cells (run_id, data_type, algorithm, level, original_size, compressed_size, ratio, compress_secs, decompress_secs)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+        for cell in &results.cells {
+            insert.execute(params![
+                run_id,
+                cell.data_type,
+                cell.algorithm,
+                cell.level,
+                cell.original_size,
+                cell.compressed_size,
+                cell.ratio,
+                cell.compress_secs,
+                cell.decompress_secs,
+            ])?;
+        }
+    }
+    transaction.commit()?;
+    Ok(run_id)
+}
+
+// Narrows `history` to matching cells; `None` matches everything
+pub struct HistoryFilter {
+    pub algorithm: Option<String>,
+    pub data_type: Option<String>,
+    // Runs must carry every one of these labels; empty matches everything
+    pub labels: Vec<(String, String)>,
+    pub limit: usize,
+}
+
+struct HistoryRow {
+    run_id: i64,
+    recorded_at: String,
+    git_commit: String,
+    data_type: String,
+    label: String,
+    ratio: f64,
+    compress_mbps: f64,
+    decompress_mbps: f64,
+}
+
+fn mbps(bytes: i64, secs: f64) -> f64 {
+    if secs > 0.0 {
+        bytes as f64 / (1024.0 * 1024.0) / secs
+    } else {
+        0.0
+    }
+}
+
+// Prints the most recent runs per configuration, oldest first, with change vs the previous run
+pub fn print_history(path: &Path, filter: &HistoryFilter) -> rusqlite::Result<()> {
+    let connection = open(path)?;
+    let mut query = connection.prepare(
+        "SELECT runs.id, runs.recorded_at, runs.git_commit, cells.data_type, cells.algorithm,
+                cells.level, cells.original_size, cells.ratio, cells.compress_secs, cells.decompress_secs
+         FROM cells JOIN runs ON runs.id = cells.run_id
+         WHERE (?1 IS NULL OR cells.algorithm = ?1) AND (?2 IS NULL OR cells.data_type = ?2)
+           AND NOT EXISTS (
+               SELECT 1 FROM json_each(?3) AS wanted
+               WHERE NOT EXISTS (
+                   SELECT 1 FROM json_each(runs.labels) AS have
+                   WHERE have.key = wanted.key AND have.value = wanted.value))
+         ORDER BY cells.data_type, cells.algorithm, cells.level, runs.id",
+    )?;
+    // Stored the way `append` writes `runs.labels`, as one JSON object
+    let labels: BTreeMap<&str, &str> = filter
+        .labels
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+    let labels = serde_json::json!(labels).to_string();
+    let rows = query
+        .query_map(params![filter.algorithm, filter.data_type, labels], |row| {
+            let original_size: i64 = row.get(6)?;
+            Ok(HistoryRow {
+                run_id: row.get(0)?,
+                recorded_at: row.get(1)?,
+                git_commit: row.get(2)?,
+                data_type: row.get(3)?,
+                label: format!("{} {}", row.get::<_, String>(4)?, row.get::<_, String>(5)?),
+                ratio: row.get(7)?,
+                compress_mbps: mbps(original_size, row.get(8)?),
+                decompress_mbps: mbps(original_size, row.get(9)?),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    if rows.is_empty() {
+        println!("No stored runs match.");
+        return Ok(());
+    }
+
+    let mut start = 0;
+    while start < rows.len() {
+        let key = (&rows[start].data_type, &rows[start].label);
+        let end = start
+            + rows[start..]
+                .iter()
+                .take_while(|r| (&r.data_type, &r.label) == key)
+                .count();
+        let series = &rows[start..end];
+        let shown = &series[series.len().saturating_sub(filter.limit)..];
+
+        println!("\n--- {} / {} ---", key.0, key.1);
+        println!(
+            "{:>5} {:<20} {:<10} {:>8} {:>12} {:>8} {:>14}",
+            "Run", "Recorded", "Commit", "Ratio", "Comp MB/s", "Change", "Decomp MB/s"
+        );
+        let mut previous: Option<&HistoryRow> = series
+            .len()
+            .checked_sub(shown.len() + 1)
+            .map(|i| &series[i]);
+        for row in shown {
+            let change = previous
+                .filter(|p| p.compress_mbps > 0.0)
+                .map_or("".to_string(), |p| {
+                    format!(
+                        "{:+.1}%",
+                        (row.compress_mbps - p.compress_mbps) / p.compress_mbps * 100.0
+                    )
+                });
+            println!(
+                "{:>5} {:<20} {:<10} {:>7.2}x {:>12.1} {:>8} {:>14.1}",
+                row.run_id,
+                row.recorded_at,
+                row.git_commit,
+                row.ratio,
+                row.compress_mbps,
+                change,
+                row.decompress_mbps
+            );
+            previous = Some(row);
+        }
+        start = end;
+    }
+    Ok(())
+}
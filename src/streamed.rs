@@ -0,0 +1,46 @@
+// Adapter that benchmarks a backend's streaming encoder fed fixed-size writes, the way network
+// and file pipelines hand data over, rather than one contiguous buffer
+use crate::Compressor;
+use std::io;
+
+pub struct Streamed {
+    inner: &'static dyn Compressor,
+    write_size: usize,
+}
+
+impl Streamed {
+    // None when the backend has no streaming encoder
+    pub fn new(inner: &'static dyn Compressor, write_size: usize) -> Option<Self> {
+        assert!(write_size > 0, "write size must be positive");
+        match inner.compress_streamed(&[], write_size) {
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => None,
+            _ => Some(Streamed { inner, write_size }),
+        }
+    }
+
+    pub fn write_size(&self) -> usize {
+        self.write_size
+    }
+}
+
+impl Compressor for Streamed {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn level(&self) -> String {
+        format!("{} w{}KB", self.inner.level(), self.write_size / 1024)
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        self.inner.compress_streamed(data, self.write_size)
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        self.inner.decompress(data)
+    }
+
+    fn decompress_limited(&self, data: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+        self.inner.decompress_limited(data, limit)
+    }
+}
@@ -0,0 +1,104 @@
+use rust_compress_comparison::backends;
+use rust_compress_comparison::harness::TestData;
+use rust_compress_comparison::streamed::Streamed;
+use rust_compress_comparison::{BenchmarkSuite, Compressor};
+use std::error::Error;
+
+const STREAMING_DATA_SIZE: usize = 1024 * 1024 * 10; // 10MB
+const STREAMING_TRIALS: usize = 3;
+
+pub const DEFAULT_WRITE_KB: [usize; 5] = [4, 16, 64, 256, 1024];
+
+// Each configuration's streaming encoder fed `write_kb`-sized writes, against one whole-buffer call
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    write_kb: &[usize],
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::configurations().collect()
+    } else {
+        algorithms.to_vec()
+    };
+
+    // (label, whole-buffer label, write size) for every configuration the suite runs
+    let mut dimensions: Vec<(String, String, Option<usize>)> = Vec::new();
+    let mut configurations: Vec<&'static dyn Compressor> = Vec::new();
+    for codec in algorithms {
+        let base = format!("{} {}", codec.name(), codec.level());
+        if Streamed::new(codec, 1).is_none() {
+            println!("Skipping {}: no streaming encoder", base);
+            continue;
+        }
+        dimensions.push((base.clone(), base.clone(), None));
+        configurations.push(codec);
+        for kb in write_kb {
+            let streamed: &'static Streamed = Box::leak(Box::new(
+                Streamed::new(codec, kb * 1024).expect("checked above"),
+            ));
+            dimensions.push((
+                format!("{} {}", streamed.name(), streamed.level()),
+                base.clone(),
+                Some(streamed.write_size()),
+            ));
+            configurations.push(streamed);
+        }
+    }
+
+    if configurations.is_empty() {
+        return Err("none of the selected configurations has a streaming encoder".into());
+    }
+
+    println!(
+        "\nStreaming compression ({}MB mixed data, {} trials)\n",
+        STREAMING_DATA_SIZE / 1024 / 1024,
+        STREAMING_TRIALS
+    );
+    let results = BenchmarkSuite::builder()
+        .data([TestData::Mixed])
+        .algorithms(configurations)
+        .data_size(STREAMING_DATA_SIZE)
+        .trials(STREAMING_TRIALS)
+        .verify(true)
+        .run()?;
+    for failure in &results.failures {
+        println!(
+            "FAILED {} {}: {}",
+            failure.algorithm, failure.level, failure.error
+        );
+    }
+
+    println!(
+        "{:<18} {:>8} {:>8} {:>11} {:>16}",
+        "Configuration", "Writes", "Ratio", "Comp MB/s", "vs write_all"
+    );
+    println!("{}", "-".repeat(65));
+    let whole = |base: &str| {
+        results
+            .cells
+            .iter()
+            .find(|cell| cell.label() == base)
+            .map(|cell| cell.compress_mbps())
+    };
+    for cell in &results.cells {
+        let Some((_, base, write_size)) = dimensions.iter().find(|(l, _, _)| *l == cell.label())
+        else {
+            continue;
+        };
+        let writes = write_size.map_or("whole".to_string(), |size| format!("{}KB", size / 1024));
+        let relative = match whole(base) {
+            Some(mbps) if mbps > 0.0 => {
+                format!("{:+.1}%", (cell.compress_mbps() - mbps) / mbps * 100.0)
+            }
+            _ => "-".to_string(),
+        };
+        println!(
+            "{:<18} {:>8} {:>7.2}x {:>11.1} {:>16}",
+            base,
+            writes,
+            cell.ratio,
+            cell.compress_mbps(),
+            relative
+        );
+    }
+    Ok(())
+}
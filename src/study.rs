@@ -0,0 +1,225 @@
+use crate::pareto::{self, ParetoPoint};
+use crate::records::generate_records;
+use crate::results::CellResult;
+use crate::{generate_test_data, timed, CompressionStats, TestData};
+use clap::ValueEnum;
+use flate2::Compression;
+use rust_compress_comparison::backends::{Gzip, Lz4Flex, Lz4Rs, Zstd};
+use rust_compress_comparison::Compressor;
+use std::fmt::Write;
+
+// Ratios within this fraction of each other count as "equal" in the narrative
+const RATIO_TOLERANCE: f64 = 0.02;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Preset {
+    /// 8KB JSON event payloads, as sent between services
+    Json8k,
+    /// 64KB chunks of mixed text, as in log shipping
+    Logs64k,
+    /// One 10MB mixed buffer, as in bulk file compression
+    Bulk10m,
+}
+
+impl Preset {
+    fn describe(self) -> &'static str {
+        match self {
+            Preset::Json8k => "JSON-like 8KB payloads",
+            Preset::Logs64k => "64KB log chunks",
+            Preset::Bulk10m => "10MB bulk buffers",
+        }
+    }
+
+    // Payloads to compress, all the same size
+    fn payloads(self) -> Vec<Vec<u8>> {
+        match self {
+            Preset::Json8k => {
+                let json: Vec<u8> = generate_records(20_000).concat();
+                json.chunks_exact(8 * 1024)
+                    .take(500)
+                    .map(<[u8]>::to_vec)
+                    .collect()
+            }
+            Preset::Logs64k => generate_test_data(&TestData::Mixed, 64 * 1024 * 100)
+                .chunks_exact(64 * 1024)
+                .map(<[u8]>::to_vec)
+                .collect(),
+            Preset::Bulk10m => vec![generate_test_data(&TestData::Mixed, 1024 * 1024 * 10)],
+        }
+    }
+}
+
+type StudyCodec = (&'static str, &'static str, &'static dyn Compressor);
+
+const STUDY_CODECS: [StudyCodec; 8] = [
+    ("gzip", "1", &Gzip::new(Compression::fast())),
+    ("gzip", "6", &Gzip::new(Compression::new(6))),
+    ("gzip", "9", &Gzip::new(Compression::best())),
+    ("zstd", "1", &Zstd::new(1)),
+    ("zstd", "3", &Zstd::new(3)),
+    ("zstd", "9", &Zstd::new(9)),
+    ("lz4-flex", "default", &Lz4Flex),
+    ("lz4-rs", "4", &Lz4Rs::new(4)),
+];
+
+// The configuration most people start from, used as the yardstick
+const REFERENCE: (&str, &str) = ("gzip", "6");
+
+fn name(cell: &CellResult) -> String {
+    if cell.level == "default" {
+        cell.algorithm.clone()
+    } else {
+        format!("{}-{}", cell.algorithm, cell.level)
+    }
+}
+
+fn measure(preset: Preset) -> Vec<CellResult> {
+    let payloads = preset.payloads();
+    STUDY_CODECS
+        .iter()
+        .map(|(algorithm, level, codec)| {
+            let mut stats = CompressionStats::default();
+            for payload in &payloads {
+                let (compressed, duration) = timed(|| codec.compress(payload));
+                stats.record(payload.len(), compressed.len(), duration);
+                let (_, decompress_duration) = timed(|| codec.decompress(&compressed));
+                stats.record_decompression(decompress_duration);
+            }
+            stats.to_cell(preset.describe(), algorithm, level)
+        })
+        .collect()
+}
+
+// Assembles a few paste-able paragraphs from the Pareto and crossover analyses
+fn narrate(preset: Preset, cells: &[CellResult]) -> String {
+    let refs: Vec<&CellResult> = cells.iter().collect();
+    let mut text = String::new();
+    let subject = format!("For {} on this machine", preset.describe());
+
+    // Speed at equal ratio against the reference
+    let reference = cells
+        .iter()
+        .find(|c| (c.algorithm.as_str(), c.level.as_str()) == REFERENCE)
+        .expect("reference codec is always measured");
+    let rival = cells
+        .iter()
+        .filter(|c| !std::ptr::eq(*c, reference))
+        .filter(|c| c.ratio >= reference.ratio * (1.0 - RATIO_TOLERANCE))
+        .max_by(|a, b| a.compress_mbps().total_cmp(&b.compress_mbps()));
+    match rival {
+        Some(rival) if rival.compress_mbps() > reference.compress_mbps() => {
+            let relation = if rival.ratio > reference.ratio * (1.0 + RATIO_TOLERANCE) {
+                "a better"
+            } else {
+                "an equal"
+            };
+            write!(
+                text,
+                "{}, {} gives {:.1}x {}'s compression speed at {} ratio ({:.2}x vs {:.2}x). ",
+                subject,
+                name(rival),
+                rival.compress_mbps() / reference.compress_mbps(),
+                name(reference),
+                relation,
+                rival.ratio,
+                reference.ratio
+            )
+            .unwrap();
+        }
+        _ => write!(
+            text,
+            "{}, nothing compresses faster than {} without giving up ratio ({:.2}x at {:.1} MB/s). ",
+            subject,
+            name(reference),
+            reference.ratio,
+            reference.compress_mbps()
+        )
+        .unwrap(),
+    }
+
+    // The ends of the frontier: what the fastest option costs in size
+    let compress_frontier = pareto::frontier(&refs, |c| c.compress_secs);
+    let optimal: Vec<&ParetoPoint> = compress_frontier
+        .iter()
+        .filter(|p| p.dominated_by.is_none())
+        .collect();
+    let names: Vec<String> = optimal.iter().map(|p| name(p.cell)).collect();
+    write!(
+        text,
+        "Only {} of {} configurations are Pareto-optimal for ratio vs compression time: {}. ",
+        optimal.len(),
+        cells.len(),
+        names.join(", ")
+    )
+    .unwrap();
+    if let (Some(fastest), Some(densest)) = (optimal.first(), optimal.last()) {
+        if !std::ptr::eq(fastest.cell, densest.cell) {
+            write!(
+                text,
+                "Moving from {} to {} shrinks output by a further {:.0}% but compresses {:.1}x slower. ",
+                name(fastest.cell),
+                name(densest.cell),
+                (1.0 - fastest.cell.ratio / densest.cell.ratio) * 100.0,
+                densest.cell.compress_secs / fastest.cell.compress_secs
+            )
+            .unwrap();
+        }
+    }
+
+    // Crossover: the cheapest configuration that beats the fastest one's ratio meaningfully
+    if let Some(fastest) = optimal.first() {
+        let crossover = optimal
+            .iter()
+            .skip(1)
+            .find(|p| p.cell.ratio >= fastest.cell.ratio * (1.0 + RATIO_TOLERANCE * 5.0));
+        if let Some(crossover) = crossover {
+            write!(
+                text,
+                "The first real step up in ratio comes from {} ({:.2}x vs {:.2}x), at {:.1} MB/s instead of {:.1} MB/s. ",
+                name(crossover.cell),
+                crossover.cell.ratio,
+                fastest.cell.ratio,
+                crossover.cell.compress_mbps(),
+                fastest.cell.compress_mbps()
+            )
+            .unwrap();
+        }
+    }
+
+    // Read side
+    if let Some(reader) = cells
+        .iter()
+        .max_by(|a, b| a.decompress_mbps().total_cmp(&b.decompress_mbps()))
+    {
+        write!(
+            text,
+            "On the read side {} decompresses fastest at {:.0} MB/s, {:.1}x {}.",
+            name(reader),
+            reader.decompress_mbps(),
+            reader.decompress_mbps() / reference.decompress_mbps(),
+            name(reference)
+        )
+        .unwrap();
+    }
+    text
+}
+
+pub fn run(preset: Preset) -> String {
+    println!("\nRunning study preset: {}...", preset.describe());
+    let cells = measure(preset);
+    println!(
+        "\n{:<12} {:>8} {:>12} {:>14}",
+        "Codec", "Ratio", "Comp MB/s", "Decomp MB/s"
+    );
+    println!("{}", "-".repeat(49));
+    for cell in &cells {
+        println!(
+            "{:<12} {:>7.2}x {:>12.1} {:>14.1}",
+            name(cell),
+            cell.ratio,
+            cell.compress_mbps(),
+            cell.decompress_mbps()
+        );
+    }
+    narrate(preset, &cells)
+}
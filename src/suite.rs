@@ -0,0 +1,483 @@
+// Runs the (data type, algorithm, level) matrix and summarizes each cell, for the CLI and for
+// programs embedding the comparison
+use crate::cache::CacheState;
+use crate::counters::{CounterValues, HardwareCounters};
+use crate::error::{CompressionBenchError, Result};
+use crate::harness::{self, generate_test_data, TestData};
+use crate::memory::{AllocationCount, Allocations, PeakRss};
+use crate::metadata::RunMetadata;
+use crate::noise::{self, Calibration};
+use crate::results::{
+    self, CellFailure, CellResult, Confidence, DataQuality, Efficiency, RunResults, Spread,
+    Throughput, TrialSample,
+};
+use crate::{backends, Compressor};
+use clap::ValueEnum;
+use serde::Deserialize;
+
+pub const DATA_SIZE: usize = 1024 * 1024 * 10; // 10MB
+pub const NUM_TRIALS: usize = 10;
+// Untimed compress/decompress rounds per cell before the first measured trial
+pub const DEFAULT_WARMUP_ITERATIONS: usize = 2;
+// Input size for `BenchmarkSuite::self_test`, small enough that every configuration finishes in
+// well under a second
+pub const SELF_TEST_SIZE: usize = 64 * 1024;
+
+// Struct to Store Benchmark Results
+#[derive(Default)]
+pub struct CompressionStats {
+    trials: usize,
+    original_size: usize,
+    factor_sum: f64,
+    time_sum: f64,
+    decompress_time_sum: f64,
+    size_sum: usize,
+    samples: Vec<TrialSample>,
+    peak_rss: PeakRss,
+    compress_allocations: Option<AllocationCount>,
+    decompress_allocations: Option<AllocationCount>,
+    allocation_trials: u64,
+    compress_counters: Option<CounterValues>,
+    decompress_counters: Option<CounterValues>,
+    counter_trials: u64,
+}
+
+impl CompressionStats {
+    pub fn record(&mut self, original_size: usize, compressed_size: usize, duration: f64) {
+        self.trials += 1;
+        self.original_size = original_size;
+        self.factor_sum += original_size as f64 / compressed_size as f64;
+        self.time_sum += duration;
+        self.size_sum += compressed_size;
+        self.samples.push(TrialSample {
+            trial: self.trials,
+            compressed_size,
+            compress_secs: duration,
+            decompress_secs: 0.0,
+            outlier: false,
+            excluded: false,
+        });
+    }
+
+    pub fn record_decompression(&mut self, duration: f64) {
+        self.decompress_time_sum += duration;
+        if let Some(sample) = self.samples.last_mut() {
+            sample.decompress_secs = duration;
+        }
+    }
+
+    pub fn record_peak_rss(&mut self, compress: Option<u64>, decompress: Option<u64>) {
+        self.peak_rss.record(compress, decompress);
+    }
+
+    pub fn record_allocations(
+        &mut self,
+        compress: Option<AllocationCount>,
+        decompress: Option<AllocationCount>,
+    ) {
+        let (Some(compress), Some(decompress)) = (compress, decompress) else {
+            return;
+        };
+        let add = |total: Option<AllocationCount>, trial: AllocationCount| {
+            let total = total.unwrap_or_default();
+            Some(AllocationCount {
+                count: total.count + trial.count,
+                bytes: total.bytes + trial.bytes,
+            })
+        };
+        self.compress_allocations = add(self.compress_allocations, compress);
+        self.decompress_allocations = add(self.decompress_allocations, decompress);
+        self.allocation_trials += 1;
+    }
+
+    pub fn record_counters(
+        &mut self,
+        compress: Option<CounterValues>,
+        decompress: Option<CounterValues>,
+    ) {
+        let (Some(compress), Some(decompress)) = (compress, decompress) else {
+            return;
+        };
+        let add = |total: Option<CounterValues>, trial| Some(total.unwrap_or_default().plus(trial));
+        self.compress_counters = add(self.compress_counters, compress);
+        self.decompress_counters = add(self.decompress_counters, decompress);
+        self.counter_trials += 1;
+    }
+
+    fn mean_counters(&self) -> HardwareCounters {
+        let trials = self.counter_trials.max(1);
+        HardwareCounters {
+            compress: self.compress_counters.map(|c| c.divide(trials)),
+            decompress: self.decompress_counters.map(|c| c.divide(trials)),
+        }
+    }
+
+    fn mean_allocations(&self) -> Allocations {
+        let trials = self.allocation_trials.max(1);
+        Allocations {
+            compress_count: self.compress_allocations.map(|a| a.count / trials),
+            compress_bytes: self.compress_allocations.map(|a| a.bytes / trials),
+            decompress_count: self.decompress_allocations.map(|a| a.count / trials),
+            decompress_bytes: self.decompress_allocations.map(|a| a.bytes / trials),
+        }
+    }
+
+    // Trials counted in the summary
+    pub fn trials(&self) -> usize {
+        self.trials
+    }
+
+    // Drops outlier trials from the running sums so the summary ignores them
+    pub fn exclude_outliers(&mut self) {
+        results::flag_outliers(&mut self.samples);
+        for sample in self.samples.iter_mut().filter(|s| s.outlier) {
+            sample.excluded = true;
+            self.trials -= 1;
+            self.factor_sum -= self.original_size as f64 / sample.compressed_size as f64;
+            self.time_sum -= sample.compress_secs;
+            self.decompress_time_sum -= sample.decompress_secs;
+            self.size_sum -= sample.compressed_size;
+        }
+    }
+
+    pub fn to_cell(&self, data_name: &str, algorithm: &str, level: &str) -> CellResult {
+        let mut samples = self.samples.clone();
+        results::flag_outliers(&mut samples);
+        let mut cell = CellResult {
+            data_type: data_name.to_string(),
+            algorithm: algorithm.to_string(),
+            level: level.to_string(),
+            original_size: self.original_size,
+            compressed_size: self.size_sum / self.trials,
+            ratio: self.factor_sum / self.trials as f64,
+            compress_secs: self.time_sum / self.trials as f64,
+            decompress_secs: self.decompress_time_sum / self.trials as f64,
+            throughput: Throughput::default(),
+            efficiency: Efficiency::default(),
+            spread: Spread::default(),
+            confidence: Confidence::default(),
+            quality: DataQuality::default(),
+            peak_rss: self.peak_rss,
+            allocations: self.mean_allocations(),
+            counters: self.mean_counters(),
+            samples,
+        };
+        cell.throughput = cell.compute_throughput();
+        cell.efficiency = cell.compute_efficiency();
+        cell.spread = cell.compute_spread();
+        cell.confidence = cell.compute_confidence();
+        cell
+    }
+}
+
+// Order in which a data type's trials are run
+#[derive(Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Schedule {
+    /// One trial of every cell per round, rotating which goes first, so drift hits all equally
+    Interleaved,
+    /// All trials of one cell before the next; cells are reported as soon as they finish
+    Sequential,
+}
+
+// Times one compress/decompress roundtrip of `data` into `stats`
+fn run_trial(
+    stats: &mut CompressionStats,
+    data: &[u8],
+    codec: &dyn Compressor,
+    verify: bool,
+    cache: CacheState,
+) -> Result<()> {
+    let trial = harness::round_trip_with(codec, data, verify, cache)?;
+    stats.record(data.len(), trial.compressed_size, trial.compress_secs);
+    stats.record_decompression(trial.decompress_secs);
+    stats.record_peak_rss(trial.compress_peak_rss, trial.decompress_peak_rss);
+    stats.record_allocations(trial.compress_allocations, trial.decompress_allocations);
+    stats.record_counters(trial.compress_counters, trial.decompress_counters);
+    Ok(())
+}
+
+// A configured benchmark run; build one with `BenchmarkSuite::builder()`
+#[derive(Clone)]
+pub struct BenchmarkSuite {
+    data: Vec<(&'static str, TestData)>,
+    algorithms: Vec<&'static dyn Compressor>,
+    data_size: usize,
+    trials: usize,
+    warmup: usize,
+    exclude_outliers: bool,
+    schedule: Schedule,
+    noisy_cv: f64,
+    verify: bool,
+    cache: CacheState,
+}
+
+impl BenchmarkSuite {
+    pub fn builder() -> BenchmarkSuiteBuilder {
+        BenchmarkSuiteBuilder {
+            suite: BenchmarkSuite {
+                data: harness::TEST_CASES.to_vec(),
+                algorithms: backends::configurations().collect(),
+                data_size: DATA_SIZE,
+                trials: NUM_TRIALS,
+                warmup: DEFAULT_WARMUP_ITERATIONS,
+                exclude_outliers: false,
+                schedule: Schedule::Interleaved,
+                noisy_cv: noise::DEFAULT_NOISY_CV,
+                verify: false,
+                cache: CacheState::Warm,
+            },
+            metadata: None,
+        }
+    }
+
+    pub fn data(&self) -> &[(&'static str, TestData)] {
+        &self.data
+    }
+
+    pub fn data_size(&self) -> usize {
+        self.data_size
+    }
+
+    pub fn trials(&self) -> usize {
+        self.trials
+    }
+
+    // Whether every output is decompressed and compared with the original
+    pub fn verifies(&self) -> bool {
+        self.verify
+    }
+
+    pub fn cache(&self) -> CacheState {
+        self.cache
+    }
+
+    // Number of cells a full run produces, failed or not
+    pub fn cell_count(&self) -> usize {
+        self.data.len() * self.algorithms.len()
+    }
+
+    // Measures the machine's noise floor that cells are assessed against
+    pub fn calibrate(&self) -> Result<Calibration> {
+        Calibration::measure(self.noisy_cv).map_err(CompressionBenchError::Calibration)
+    }
+
+    // Round-trips a small input of each data type, and an empty one, through every configuration
+    // and checks the output; run before a long benchmark so a broken backend or feature
+    // combination fails in seconds rather than hours in
+    pub fn self_test(&self) -> Result<()> {
+        let inputs = self
+            .data
+            .iter()
+            .map(|(_, data_type)| generate_test_data(data_type, SELF_TEST_SIZE))
+            .chain([Vec::new()]);
+        for data in inputs {
+            for codec in &self.algorithms {
+                harness::round_trip(*codec, &data, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Runs every configuration on every data type, with metadata detected for this machine
+    pub fn run(&self) -> Result<RunResults> {
+        self.run_with_metadata(RunMetadata::new(Vec::new(), Vec::new()))
+    }
+
+    fn run_with_metadata(&self, metadata: RunMetadata) -> Result<RunResults> {
+        let mut results = RunResults {
+            metadata,
+            cells: Vec::new(),
+            failures: Vec::new(),
+        };
+        results.metadata.verified = self.verify;
+        results.metadata.cache = self.cache;
+        results.metadata.calibration = self.calibrate()?;
+        let calibration = results.metadata.calibration;
+        for (data_name, data_type) in &self.data {
+            self.run_data_type(data_name, data_type, calibration, |cell| match cell {
+                Ok(cell) => results.cells.push(cell),
+                Err(failure) => results.failures.push(failure),
+            });
+        }
+        Ok(results)
+    }
+
+    // Benchmarks every configuration on one data type, handing each cell over as it finishes;
+    // a codec that errors is reported as a failed cell and skipped for the rest of the data type
+    pub fn run_data_type(
+        &self,
+        data_name: &str,
+        data_type: &TestData,
+        calibration: Calibration,
+        mut on_cell: impl FnMut(std::result::Result<CellResult, CellFailure>),
+    ) {
+        let codecs = &self.algorithms;
+        let mut errors: Vec<Option<CompressionBenchError>> = codecs.iter().map(|_| None).collect();
+
+        if self.warmup > 0 {
+            let data = generate_test_data(data_type, self.data_size);
+            for (codec, error) in codecs.iter().zip(&mut errors) {
+                for _ in 0..self.warmup {
+                    if let Err(e) = run_trial(
+                        &mut CompressionStats::default(),
+                        &data,
+                        *codec,
+                        self.verify,
+                        CacheState::Warm,
+                    ) {
+                        *error = Some(e);
+                        break;
+                    }
+                }
+            }
+        }
+
+        let mut finish = |mut stats: CompressionStats,
+                          codec: &dyn Compressor,
+                          error: Option<CompressionBenchError>| {
+            if let Some(error) = error {
+                on_cell(Err(CellFailure {
+                    data_type: data_name.to_string(),
+                    algorithm: codec.name().to_string(),
+                    level: codec.level(),
+                    error: error.to_string(),
+                }));
+                return;
+            }
+            if self.exclude_outliers {
+                stats.exclude_outliers();
+            }
+            let mut cell = stats.to_cell(data_name, codec.name(), &codec.level());
+            cell.quality = calibration.assess(&cell);
+            on_cell(Ok(cell));
+        };
+
+        match self.schedule {
+            Schedule::Interleaved => {
+                let mut stats: Vec<CompressionStats> =
+                    codecs.iter().map(|_| CompressionStats::default()).collect();
+                for round in 0..self.trials {
+                    let data = generate_test_data(data_type, self.data_size);
+                    for offset in 0..codecs.len() {
+                        let index = (round + offset) % codecs.len();
+                        if errors[index].is_none() {
+                            if let Err(e) = run_trial(
+                                &mut stats[index],
+                                &data,
+                                codecs[index],
+                                self.verify,
+                                self.cache,
+                            ) {
+                                errors[index] = Some(e);
+                            }
+                        }
+                    }
+                }
+                for ((stats, codec), error) in stats.into_iter().zip(codecs).zip(errors) {
+                    finish(stats, *codec, error);
+                }
+            }
+            Schedule::Sequential => {
+                for (codec, mut error) in codecs.iter().zip(errors) {
+                    let mut stats = CompressionStats::default();
+                    for _ in 0..self.trials {
+                        if error.is_some() {
+                            break;
+                        }
+                        let data = generate_test_data(data_type, self.data_size);
+                        error = run_trial(&mut stats, &data, *codec, self.verify, self.cache).err();
+                    }
+                    finish(stats, *codec, error);
+                }
+            }
+        }
+    }
+}
+
+// Configures a `BenchmarkSuite`; anything left unset matches the `bench` subcommand's defaults
+pub struct BenchmarkSuiteBuilder {
+    suite: BenchmarkSuite,
+    metadata: Option<RunMetadata>,
+}
+
+impl BenchmarkSuiteBuilder {
+    // Data types to benchmark, in report order
+    pub fn data(mut self, data: impl IntoIterator<Item = TestData>) -> Self {
+        self.suite.data = data
+            .into_iter()
+            .map(|data_type| (data_type.name(), data_type))
+            .collect();
+        self
+    }
+
+    // Configurations to benchmark, e.g. from `backends::configurations()` or `backends::find`
+    pub fn algorithms(
+        mut self,
+        algorithms: impl IntoIterator<Item = &'static dyn Compressor>,
+    ) -> Self {
+        self.suite.algorithms = algorithms.into_iter().collect();
+        self
+    }
+
+    // Measured trials per cell; at least one, since cells are averaged over them
+    pub fn trials(mut self, trials: usize) -> Self {
+        self.suite.trials = trials.max(1);
+        self
+    }
+
+    // Bytes of test data per trial
+    pub fn data_size(mut self, bytes: usize) -> Self {
+        self.suite.data_size = bytes;
+        self
+    }
+
+    pub fn warmup(mut self, iterations: usize) -> Self {
+        self.suite.warmup = iterations;
+        self
+    }
+
+    pub fn exclude_outliers(mut self, exclude: bool) -> Self {
+        self.suite.exclude_outliers = exclude;
+        self
+    }
+
+    pub fn schedule(mut self, schedule: Schedule) -> Self {
+        self.suite.schedule = schedule;
+        self
+    }
+
+    pub fn noisy_cv(mut self, noisy_cv: f64) -> Self {
+        self.suite.noisy_cv = noisy_cv;
+        self
+    }
+
+    // Byte-compare every decompressed output with its input, failing cells that differ
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.suite.verify = verify;
+        self
+    }
+
+    // Cold evicts each trial's input from the CPU caches before it is compressed or decompressed
+    pub fn cache(mut self, cache: CacheState) -> Self {
+        self.suite.cache = cache;
+        self
+    }
+
+    // Labels and notes to record with the run; machine details are detected when unset
+    pub fn metadata(mut self, metadata: RunMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    pub fn build(self) -> BenchmarkSuite {
+        self.suite
+    }
+
+    pub fn run(self) -> Result<RunResults> {
+        let metadata = self
+            .metadata
+            .unwrap_or_else(|| RunMetadata::new(Vec::new(), Vec::new()));
+        self.suite.run_with_metadata(metadata)
+    }
+}
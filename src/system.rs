@@ -0,0 +1,94 @@
+// Reference command-line tools driven over pipes, to sanity-check the crate bindings against.
+// Their timings include spawning the process, which is noise next to a multi-megabyte input
+use crate::Compressor;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+// A system binary at its default level, compressing stdin to stdout
+pub struct SystemTool {
+    name: &'static str,
+    program: &'static str,
+    compress_args: &'static [&'static str],
+    decompress_args: &'static [&'static str],
+}
+
+pub const TOOLS: [SystemTool; 3] = [
+    SystemTool {
+        name: "gzip CLI",
+        program: "gzip",
+        compress_args: &["-c", "-6"],
+        decompress_args: &["-d", "-c"],
+    },
+    SystemTool {
+        name: "zstd CLI",
+        program: "zstd",
+        compress_args: &["-c", "-q", "-3"],
+        decompress_args: &["-d", "-c", "-q"],
+    },
+    SystemTool {
+        name: "xz CLI",
+        program: "xz",
+        compress_args: &["-c", "-6"],
+        decompress_args: &["-d", "-c"],
+    },
+];
+
+impl SystemTool {
+    pub fn program(&self) -> &'static str {
+        self.program
+    }
+
+    pub fn is_installed(&self) -> bool {
+        Command::new(self.program)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    fn pipe(&self, args: &[&str], data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut child = Command::new(self.program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        // Feed stdin from another thread so a full stdout pipe can't deadlock the two
+        let output = thread::scope(|scope| {
+            let writer = scope.spawn(move || stdin.write_all(data));
+            let output = child.wait_with_output();
+            writer.join().expect("stdin writer panicked")?;
+            output
+        })?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "{} exited with {}: {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(output.stdout)
+    }
+}
+
+impl Compressor for SystemTool {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn level(&self) -> String {
+        "Default".to_string()
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        self.pipe(self.compress_args, data)
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        self.pipe(self.decompress_args, data)
+    }
+}
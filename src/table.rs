@@ -0,0 +1,183 @@
+use crate::results::{CellFailure, CellResult, DataQuality};
+use comfy_table::presets::UTF8_FULL_CONDENSED;
+use comfy_table::{Cell, CellAlignment, Color, Table};
+
+const BAR_WIDTH: usize = 8;
+const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+// Fixed-width unicode bar proportional to value / max, in eighth-block steps
+fn bar(value: f64, max: f64) -> String {
+    let filled = if max > 0.0 {
+        (value / max * (BAR_WIDTH * 8) as f64).round() as usize
+    } else {
+        0
+    };
+    let filled = filled.min(BAR_WIDTH * 8);
+    let mut bar = "█".repeat(filled / 8);
+    if filled % 8 > 0 {
+        bar.push(EIGHTHS[filled % 8]);
+    }
+    format!("{:<width$}", bar, width = BAR_WIDTH)
+}
+
+// Value followed by its bar, colored by where it falls relative to the best in the table
+fn bar_cell(text: String, value: f64, max: f64, color: bool) -> Cell {
+    let cell = Cell::new(format!("{} {}", text, bar(value, max)));
+    if !color || max <= 0.0 {
+        return cell;
+    }
+    let share = value / max;
+    cell.fg(if share >= 2.0 / 3.0 {
+        Color::Green
+    } else if share >= 1.0 / 3.0 {
+        Color::Yellow
+    } else {
+        Color::Red
+    })
+}
+
+// Outlier count, noting when they were left out of the statistics
+pub fn outliers(cell: &CellResult) -> String {
+    let spread = &cell.spread;
+    if spread.outliers_excluded {
+        format!("{} (excluded)", spread.outliers)
+    } else {
+        spread.outliers.to_string()
+    }
+}
+
+fn quality_cell(cell: &CellResult, color: bool) -> Cell {
+    let text = Cell::new(cell.quality.label());
+    match cell.quality {
+        DataQuality::Noisy | DataQuality::TimerBound if color => text.fg(Color::Red),
+        _ => text,
+    }
+}
+
+// One aligned table per data type, one row per (algorithm, level)
+pub fn print_cells(data_name: &str, cells: &[CellResult], color: bool) {
+    let max_ratio = cells.iter().map(|c| c.ratio).fold(0.0, f64::max);
+    let max_compress = cells
+        .iter()
+        .map(|c| c.throughput.compress_input_mbps)
+        .fold(0.0, f64::max);
+    let max_decompress = cells
+        .iter()
+        .map(|c| c.throughput.decompress_output_mbps)
+        .fold(0.0, f64::max);
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED).set_header(vec![
+        "Algorithm",
+        "Level",
+        "Ratio",
+        "Comp MB/s",
+        "Decomp MB/s",
+        "Size (MB)",
+    ]);
+    if !color {
+        table.force_no_tty();
+    }
+    for cell in cells {
+        let throughput = &cell.throughput;
+        table.add_row(vec![
+            Cell::new(&cell.algorithm),
+            Cell::new(&cell.level),
+            bar_cell(format!("{:.2}x", cell.ratio), cell.ratio, max_ratio, color),
+            bar_cell(
+                format!("{:.1}", throughput.compress_input_mbps),
+                throughput.compress_input_mbps,
+                max_compress,
+                color,
+            ),
+            bar_cell(
+                format!("{:.1}", throughput.decompress_output_mbps),
+                throughput.decompress_output_mbps,
+                max_decompress,
+                color,
+            ),
+            Cell::new(format!(
+                "{:.2}",
+                cell.compressed_size as f64 / (1024.0 * 1024.0)
+            )),
+        ]);
+    }
+    for index in 2..6 {
+        if let Some(column) = table.column_mut(index) {
+            column.set_cell_alignment(CellAlignment::Right);
+        }
+    }
+
+    println!("\n=== {} Data ===", data_name);
+    println!("{}", table);
+}
+
+// The rest of each cell's measurements for `bench --detail`, printed under its main table
+pub fn print_detail(cells: &[CellResult], color: bool) {
+    let max_efficiency = cells
+        .iter()
+        .map(|c| c.efficiency.saved_mb_per_cpu_sec)
+        .fold(0.0, f64::max);
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED).set_header(vec![
+        "Algorithm",
+        "Level",
+        "Comp out MB/s",
+        "Decomp in MB/s",
+        "Saved MB/CPU-s",
+        "Comp ms ± sd [min, max]",
+        "Comp ms p50 / p90 / p99",
+        "Decomp ms ± sd [min, max]",
+        "Decomp ms p50 / p90 / p99",
+        "Peak RSS MB comp / decomp",
+        "Allocs (MB) comp / decomp",
+        "Instr (IPC, LLC misses) comp / decomp",
+        "Outliers",
+        "Quality",
+    ]);
+    if !color {
+        table.force_no_tty();
+    }
+    for cell in cells {
+        let throughput = &cell.throughput;
+        table.add_row(vec![
+            Cell::new(&cell.algorithm),
+            Cell::new(&cell.level),
+            Cell::new(format!("{:.1}", throughput.compress_output_mbps)),
+            Cell::new(format!("{:.1}", throughput.decompress_input_mbps)),
+            bar_cell(
+                format!("{:.1}", cell.efficiency.saved_mb_per_cpu_sec),
+                cell.efficiency.saved_mb_per_cpu_sec,
+                max_efficiency,
+                color,
+            ),
+            Cell::new(cell.spread.compress.describe_ms(cell.compress_secs)),
+            Cell::new(cell.spread.compress.percentiles_ms()),
+            Cell::new(cell.spread.decompress.describe_ms(cell.decompress_secs)),
+            Cell::new(cell.spread.decompress.percentiles_ms()),
+            Cell::new(cell.peak_rss.describe_mb()),
+            Cell::new(cell.allocations.describe()),
+            Cell::new(cell.counters.describe()),
+            Cell::new(outliers(cell)),
+            quality_cell(cell, color),
+        ]);
+    }
+    for index in 2..13 {
+        if let Some(column) = table.column_mut(index) {
+            column.set_cell_alignment(CellAlignment::Right);
+        }
+    }
+
+    println!("{}", table);
+}
+
+// Cells of one data type that errored, listed under its table
+pub fn print_failures(data_name: &str, failures: &[CellFailure]) {
+    for failure in failures.iter().filter(|f| f.data_type == data_name) {
+        println!(
+            "FAILED {} {}: {}",
+            failure.algorithm, failure.level, failure.error
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use rust_compress_comparison::backends;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+
+const MB: f64 = 1024.0 * 1024.0;
+
+// `dir` archived in memory, as `tar -cf - dir` would write it
+fn archive(dir: &Path) -> io::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    builder.follow_symlinks(false);
+    builder.append_dir_all(".", dir)?;
+    builder.into_inner()
+}
+
+// The archive fed to the backend's streaming encoder `write_size` bytes at a time; the bool is
+// false for backends without one, which compress the whole archive as one buffer
+fn compress(
+    codec: &dyn Compressor,
+    archive: &[u8],
+    write_size: usize,
+) -> io::Result<(Vec<u8>, bool)> {
+    match codec.compress_streamed(archive, write_size) {
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => Ok((codec.compress(archive)?, false)),
+        result => result.map(|compressed| (compressed, true)),
+    }
+}
+
+// tar and compress of a directory end to end, the cost a backup or artifact upload pays
+pub fn run(
+    dir: &Path,
+    algorithms: &[&'static dyn Compressor],
+    write_kb: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::REGISTRY
+            .iter()
+            .filter_map(|backend| backend.level("Default"))
+            .collect()
+    } else {
+        algorithms.to_vec()
+    };
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()).into());
+    }
+    // A first pass reads the tree into the page cache, so every configuration starts warm
+    let warm = archive(dir)?;
+    let entries = tar::Archive::new(warm.as_slice()).entries()?.count();
+    println!(
+        "\ntar + compress of {} ({} entries, {:.2}MB archive, {}KB writes)\n",
+        dir.display(),
+        entries,
+        warm.len() as f64 / MB,
+        write_kb
+    );
+    println!(
+        "{:<18} {:>8} {:>10} {:>8} {:>9} {:>12} {:>7}",
+        "Configuration", "Tar s", "Compress s", "Total s", "MB/s", "Output MB", "Ratio"
+    );
+    println!("{}", "-".repeat(79));
+    let mut whole_buffer = false;
+    for codec in algorithms {
+        let mut label = format!("{} {}", codec.name(), codec.level());
+        let start = Instant::now();
+        let tarred = archive(dir)?;
+        let tar_secs = start.elapsed().as_secs_f64();
+        let start = Instant::now();
+        let (compressed, streamed) = match compress(codec, &tarred, write_kb * 1024) {
+            Ok(result) => result,
+            Err(e) => {
+                println!("{:<18} FAILED: {}", label, e);
+                continue;
+            }
+        };
+        let compress_secs = start.elapsed().as_secs_f64();
+        if codec.decompress(&compressed)? != tarred {
+            println!("{:<18} FAILED: round trip mismatch", label);
+            continue;
+        }
+        if !streamed {
+            label.push('*');
+            whole_buffer = true;
+        }
+        let total = tar_secs + compress_secs;
+        println!(
+            "{:<18} {:>8.3} {:>10.3} {:>8.3} {:>9.1} {:>12.2} {:>6.2}x",
+            label,
+            tar_secs,
+            compress_secs,
+            total,
+            tarred.len() as f64 / MB / total,
+            compressed.len() as f64 / MB,
+            tarred.len() as f64 / compressed.len() as f64
+        );
+    }
+    if whole_buffer {
+        println!("\n* no streaming encoder; the archive was compressed as one buffer");
+    }
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use crate::Instant;
+use clap::ValueEnum;
+use std::hint::black_box;
+use std::time::Duration;
+
+pub const CALIBRATION_ROUNDS: usize = 10_000;
+const TSC_CALIBRATION: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ClockChoice {
+    /// TSC on x86_64 with an invariant TSC, the monotonic clock otherwise
+    Auto,
+    /// Always use the CPU timestamp counter (x86_64 only)
+    Tsc,
+    /// Always use std::time::Instant
+    Monotonic,
+}
+
+#[derive(Clone, Copy)]
+enum Clock {
+    Monotonic,
+    #[cfg(target_arch = "x86_64")]
+    Tsc {
+        secs_per_tick: f64,
+    },
+}
+
+// A raw reading from whichever clock the timer uses
+#[derive(Clone, Copy)]
+enum Stamp {
+    Monotonic(Instant),
+    #[cfg(target_arch = "x86_64")]
+    Tsc(u64),
+}
+
+#[cfg(target_arch = "x86_64")]
+fn rdtsc() -> u64 {
+    use std::arch::x86_64::{_mm_lfence, _rdtsc};
+    // The fences keep the measured work from being reordered around the read
+    unsafe {
+        _mm_lfence();
+        let ticks = _rdtsc();
+        _mm_lfence();
+        ticks
+    }
+}
+
+// CPUID 0x80000007 EDX bit 8: the TSC ticks at a constant rate across P/C-states
+#[cfg(target_arch = "x86_64")]
+fn has_invariant_tsc() -> bool {
+    use std::arch::x86_64::__cpuid;
+    __cpuid(0x8000_0000).eax >= 0x8000_0007 && __cpuid(0x8000_0007).edx & (1 << 8) != 0
+}
+
+// Ticks per second, measured against the monotonic clock over a short spin
+#[cfg(target_arch = "x86_64")]
+fn calibrate_tsc() -> f64 {
+    let start = Instant::now();
+    let start_ticks = rdtsc();
+    while start.elapsed() < TSC_CALIBRATION {}
+    let ticks = rdtsc() - start_ticks;
+    ticks as f64 / start.elapsed().as_secs_f64()
+}
+
+// Times closures on a calibrated clock and knows its own per-measurement cost
+pub struct Timer {
+    clock: Clock,
+    overhead_secs: f64,
+}
+
+impl Timer {
+    pub fn calibrate(choice: ClockChoice) -> Result<Timer, String> {
+        let clock = Self::select_clock(choice)?;
+        let mut timer = Timer {
+            clock,
+            overhead_secs: 0.0,
+        };
+        // Overhead is the median reading around an empty body
+        let mut empty: Vec<f64> = (0..CALIBRATION_ROUNDS)
+            .map(|_| timer.time(|| ()).1)
+            .collect();
+        empty.sort_by(f64::total_cmp);
+        timer.overhead_secs = empty[empty.len() / 2];
+        Ok(timer)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn select_clock(choice: ClockChoice) -> Result<Clock, String> {
+        let tsc = || Clock::Tsc {
+            secs_per_tick: 1.0 / calibrate_tsc(),
+        };
+        match choice {
+            ClockChoice::Monotonic => Ok(Clock::Monotonic),
+            ClockChoice::Tsc => Ok(tsc()),
+            ClockChoice::Auto if has_invariant_tsc() => Ok(tsc()),
+            ClockChoice::Auto => Ok(Clock::Monotonic),
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    fn select_clock(choice: ClockChoice) -> Result<Clock, String> {
+        match choice {
+            ClockChoice::Tsc => Err("the TSC clock is only available on x86_64".to_string()),
+            _ => Ok(Clock::Monotonic),
+        }
+    }
+
+    fn now(&self) -> Stamp {
+        match self.clock {
+            Clock::Monotonic => Stamp::Monotonic(Instant::now()),
+            #[cfg(target_arch = "x86_64")]
+            Clock::Tsc { .. } => Stamp::Tsc(rdtsc()),
+        }
+    }
+
+    fn secs_between(&self, start: Stamp, end: Stamp) -> f64 {
+        match (self.clock, start, end) {
+            #[cfg(target_arch = "x86_64")]
+            (Clock::Tsc { secs_per_tick }, Stamp::Tsc(start), Stamp::Tsc(end)) => {
+                end.saturating_sub(start) as f64 * secs_per_tick
+            }
+            (_, Stamp::Monotonic(start), Stamp::Monotonic(end)) => {
+                end.duration_since(start).as_secs_f64()
+            }
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("stamps come from the timer's own clock"),
+        }
+    }
+
+    // Raw elapsed seconds for one call, timer overhead included
+    pub fn time<T>(&self, f: impl FnOnce() -> T) -> (T, f64) {
+        let start = self.now();
+        let value = black_box(f());
+        let end = self.now();
+        (value, self.secs_between(start, end))
+    }
+
+    // Raw reading with the calibrated overhead taken off, never below zero
+    pub fn corrected(&self, raw_secs: f64) -> f64 {
+        (raw_secs - self.overhead_secs).max(0.0)
+    }
+
+    pub fn overhead_secs(&self) -> f64 {
+        self.overhead_secs
+    }
+
+    pub fn name(&self) -> String {
+        match self.clock {
+            Clock::Monotonic => "monotonic (std::time::Instant)".to_string(),
+            #[cfg(target_arch = "x86_64")]
+            Clock::Tsc { secs_per_tick } => {
+                format!("TSC ({:.3} GHz)", 1.0 / secs_per_tick / 1e9)
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::timer::{ClockChoice, Timer, CALIBRATION_ROUNDS};
+use crate::{generate_test_data, TestData};
+#[cfg(feature = "gzip")]
+use flate2::write::GzEncoder;
+#[cfg(feature = "gzip")]
+use flate2::Compression;
+#[cfg(feature = "lz4")]
+use lz4::EncoderBuilder;
+use std::hint::black_box;
+#[cfg(any(feature = "gzip", feature = "lz4"))]
+use std::io::Write;
+
+const TIMING_ITERATIONS: usize = 1_000;
+const TIMING_PAYLOADS: [usize; 4] = [64, 256, 1024, 4096];
+// Cases where the timer eats more than this share of the raw reading get flagged
+const OVERHEAD_WARNING: f64 = 0.05;
+
+type TimingCodec = (&'static str, fn(&[u8]) -> Vec<u8>);
+
+// Untimed codecs, so the only clock reads inside a measurement are the timer's own; each
+// compiled in with its backend
+const TIMING_CODECS: &[TimingCodec] = &[
+    #[cfg(feature = "gzip")]
+    ("Gzip Fast", |data| {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }),
+    #[cfg(feature = "lz4-flex")]
+    ("LZ4-Flex", lz4_flex::compress_prepend_size),
+    #[cfg(feature = "lz4")]
+    ("LZ4-RS Fast", |data| {
+        let mut encoder = EncoderBuilder::new().level(0).build(Vec::new()).unwrap();
+        encoder.write_all(data).unwrap();
+        let (compressed, result) = encoder.finish();
+        result.unwrap();
+        compressed
+    }),
+];
+
+pub fn run(choice: ClockChoice) -> Result<(), String> {
+    let timer = Timer::calibrate(choice)?;
+    println!("\nTimer: {}", timer.name());
+    println!(
+        "Calibrated overhead: {:.1} ns per measurement (median of {} empty reads)\n",
+        timer.overhead_secs() * 1e9,
+        CALIBRATION_ROUNDS
+    );
+    println!(
+        "{:>8} {:<12} {:>10} {:>10} {:>14} {:>9}",
+        "Payload", "Algorithm", "Raw (ns)", "Overhead", "Corrected (ns)", "Share"
+    );
+    println!("{}", "-".repeat(68));
+
+    let mut noisy = 0;
+    for &size in &TIMING_PAYLOADS {
+        let data = generate_test_data(&TestData::Mixed, size);
+        for (name, compress) in TIMING_CODECS {
+            let mut raw: Vec<f64> = (0..TIMING_ITERATIONS)
+                .map(|_| timer.time(|| compress(black_box(&data))).1)
+                .collect();
+            raw.sort_by(f64::total_cmp);
+            let median = raw[raw.len() / 2];
+            let share = timer.overhead_secs() / median;
+            if share > OVERHEAD_WARNING {
+                noisy += 1;
+            }
+            println!(
+                "{:>7}B {:<12} {:>10.1} {:>10.1} {:>14.1} {:>8.1}%",
+                size,
+                name,
+                median * 1e9,
+                timer.overhead_secs() * 1e9,
+                timer.corrected(median) * 1e9,
+                share * 100.0
+            );
+        }
+    }
+
+    if noisy > 0 {
+        println!(
+            "\n{} case(s) spend more than {:.0}% of the raw reading in the timer itself; \
+             compare corrected times only.",
+            noisy,
+            OVERHEAD_WARNING * 100.0
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use crate::pipeline::RateLimitedWriter;
+use crate::{generate_test_data, TestData};
+use rust_compress_comparison::backends;
+use rust_compress_comparison::Compressor;
+use std::error::Error;
+use std::io::{self, Write};
+use std::sync::mpsc::sync_channel;
+use std::thread;
+use std::time::Instant;
+
+// Blocks queued between stages before the one upstream has to wait
+const DEPTH: usize = 2;
+
+struct Completed {
+    wire_bytes: usize,
+    // Busy time of each stage, summed over blocks
+    compress_secs: f64,
+    decompress_secs: f64,
+    // From the first block starting compression to the last one decompressed
+    total_secs: f64,
+}
+
+// Sender compresses block by block, a link thread pushes each through the throttled channel, and
+// the receiver decompresses and checks it as it arrives, all three overlapping
+fn send(
+    codec: &dyn Compressor,
+    blocks: &[&[u8]],
+    bytes_per_sec: f64,
+) -> Result<Completed, Box<dyn Error>> {
+    let start = Instant::now();
+    let (compressed, sent) = sync_channel::<Vec<u8>>(DEPTH);
+    let (delivered, arrived) = sync_channel::<Vec<u8>>(DEPTH);
+    let (sender, link, receiver) = thread::scope(|scope| {
+        let link = scope.spawn(move || -> io::Result<()> {
+            let mut link = RateLimitedWriter::new(io::sink(), bytes_per_sec);
+            for block in sent {
+                link.write_all(&block)?;
+                if delivered.send(block).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        });
+        let receiver = scope.spawn(move || -> io::Result<(usize, f64)> {
+            let mut wire_bytes = 0;
+            let mut secs = 0.0;
+            for (i, block) in arrived.into_iter().enumerate() {
+                let decompress = Instant::now();
+                let restored = codec.decompress(&block)?;
+                secs += decompress.elapsed().as_secs_f64();
+                if restored != blocks[i] {
+                    return Err(io::Error::other(format!("block {} differs", i)));
+                }
+                wire_bytes += block.len();
+            }
+            Ok((wire_bytes, secs))
+        });
+        let sender = (|| -> io::Result<f64> {
+            let mut secs = 0.0;
+            for block in blocks {
+                let compress = Instant::now();
+                let output = codec.compress(block)?;
+                secs += compress.elapsed().as_secs_f64();
+                if compressed.send(output).is_err() {
+                    break;
+                }
+            }
+            Ok(secs)
+        })();
+        drop(compressed);
+        (sender, link.join(), receiver.join())
+    });
+    let compress_secs = sender?;
+    link.map_err(|_| "link panicked")??;
+    let (wire_bytes, decompress_secs) = receiver.map_err(|_| "receiver panicked")??;
+    Ok(Completed {
+        wire_bytes,
+        compress_secs,
+        decompress_secs,
+        total_secs: start.elapsed().as_secs_f64(),
+    })
+}
+
+// Completion time of sending a dataset over a rate-limited link with each codec, compression
+// and decompression overlapping the transfer
+pub fn run(
+    algorithms: &[&'static dyn Compressor],
+    bandwidths: &[f64],
+    size_mb: usize,
+    block_kb: usize,
+) -> Result<(), Box<dyn Error>> {
+    let algorithms: Vec<&'static dyn Compressor> = if algorithms.is_empty() {
+        backends::REGISTRY
+            .iter()
+            .filter_map(|backend| backend.level("Default"))
+            .collect()
+    } else {
+        algorithms.to_vec()
+    };
+    let data = generate_test_data(&TestData::Mixed, size_mb * 1024 * 1024);
+    let blocks: Vec<&[u8]> = data.chunks(block_kb * 1024).collect();
+    println!(
+        "\nConstrained-bandwidth transfer ({}MB mixed data in {}KB blocks)",
+        size_mb, block_kb
+    );
+    for &bandwidth in bandwidths {
+        let bytes_per_sec = bandwidth * 1e6 / 8.0;
+        // Raw bytes keep the link busy the whole time, so this is exact rather than measured
+        let raw_secs = data.len() as f64 / bytes_per_sec;
+        println!(
+            "\n=== {} Mbit/s (uncompressed takes {:.2}s) ===\n",
+            bandwidth, raw_secs
+        );
+        println!(
+            "{:<18} {:>7} {:>9} {:>8} {:>8} {:>10} {:>10} {:>8}",
+            "Configuration",
+            "Ratio",
+            "Wire MB",
+            "Comp s",
+            "Link s",
+            "Decomp s",
+            "Complete s",
+            "Speedup"
+        );
+        println!("{}", "-".repeat(85));
+        let mut fastest: Option<(String, f64)> = None;
+        for codec in &algorithms {
+            let label = format!("{} {}", codec.name(), codec.level());
+            let completed = match send(*codec, &blocks, bytes_per_sec) {
+                Ok(completed) => completed,
+                Err(e) => {
+                    println!("{:<18} FAILED: {}", label, e);
+                    continue;
+                }
+            };
+            println!(
+                "{:<18} {:>6.2}x {:>9.2} {:>8.2} {:>8.2} {:>10.2} {:>10.2} {:>7.2}x",
+                label,
+                data.len() as f64 / completed.wire_bytes as f64,
+                completed.wire_bytes as f64 / (1024.0 * 1024.0),
+                completed.compress_secs,
+                completed.wire_bytes as f64 / bytes_per_sec,
+                completed.decompress_secs,
+                completed.total_secs,
+                raw_secs / completed.total_secs
+            );
+            if fastest
+                .as_ref()
+                .is_none_or(|(_, secs)| completed.total_secs < *secs)
+            {
+                fastest = Some((label, completed.total_secs));
+            }
+        }
+        match fastest {
+            Some((label, secs)) if secs < raw_secs => println!("\nFastest: {}", label),
+            _ => println!("\nFastest: uncompressed"),
+        }
+    }
+    println!(
+        "\nLink s: time the compressed bytes occupy the link; Complete s: wall time end to end"
+    );
+    Ok(())
+}
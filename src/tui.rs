@@ -0,0 +1,229 @@
+use crate::results::CellResult;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::{Axis, Block, Chart, Dataset, GraphType, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const SERIES_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Red,
+];
+
+#[derive(Clone, Copy)]
+enum SortKey {
+    Order,
+    DataType,
+    Algorithm,
+    Ratio,
+    Compress,
+    Decompress,
+}
+
+const SORT_KEYS: [(SortKey, &str); 6] = [
+    (SortKey::Order, "Run order"),
+    (SortKey::DataType, "Data"),
+    (SortKey::Algorithm, "Algorithm"),
+    (SortKey::Ratio, "Ratio"),
+    (SortKey::Compress, "Comp MB/s"),
+    (SortKey::Decompress, "Decomp MB/s"),
+];
+
+struct Dashboard {
+    cells: Vec<CellResult>,
+    total: usize,
+    sort: usize,
+    descending: bool,
+    finished: bool,
+}
+
+impl Dashboard {
+    fn sorted(&self) -> Vec<&CellResult> {
+        let mut rows: Vec<&CellResult> = self.cells.iter().collect();
+        let key = SORT_KEYS[self.sort].0;
+        rows.sort_by(|a, b| match key {
+            SortKey::Order => std::cmp::Ordering::Equal,
+            SortKey::DataType => a.data_type.cmp(&b.data_type),
+            SortKey::Algorithm => a.label().cmp(&b.label()),
+            SortKey::Ratio => a.ratio.total_cmp(&b.ratio),
+            SortKey::Compress => a.compress_mbps().total_cmp(&b.compress_mbps()),
+            SortKey::Decompress => a.decompress_mbps().total_cmp(&b.decompress_mbps()),
+        });
+        if self.descending {
+            rows.reverse();
+        }
+        rows
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [status, body] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(frame.area());
+        let [table_area, chart_area] =
+            Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .areas(body);
+
+        let progress = if self.finished { "done" } else { "running" };
+        frame.render_widget(
+            Paragraph::new(format!(
+                " {}/{} cells ({})  sort: {}{}  [s] next column  [r] reverse  [q] quit",
+                self.cells.len(),
+                self.total,
+                progress,
+                SORT_KEYS[self.sort].1,
+                if self.descending { " desc" } else { " asc" },
+            )),
+            status,
+        );
+
+        let header_style = Style::default().add_modifier(Modifier::BOLD);
+        let rows = self.sorted().into_iter().map(|cell| {
+            Row::new(vec![
+                cell.data_type.clone(),
+                cell.algorithm.clone(),
+                cell.level.clone(),
+                format!("{:.2}x", cell.ratio),
+                format!("{:.1}", cell.compress_mbps()),
+                format!("{:.1}", cell.decompress_mbps()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(10),
+                Constraint::Length(12),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "Data",
+                "Algorithm",
+                "Level",
+                "Ratio",
+                "Comp MB/s",
+                "Decomp MB/s",
+            ])
+            .style(header_style),
+        )
+        .block(Block::bordered().title("Results"));
+        frame.render_widget(table, table_area);
+
+        // One scatter series per data type: x = compression MB/s, y = ratio
+        let mut series: Vec<(&str, Vec<(f64, f64)>)> = Vec::new();
+        for cell in &self.cells {
+            let point = (cell.compress_mbps(), cell.ratio);
+            match series.iter_mut().find(|(name, _)| *name == cell.data_type) {
+                Some((_, points)) => points.push(point),
+                None => series.push((&cell.data_type, vec![point])),
+            }
+        }
+        let max_speed = self
+            .cells
+            .iter()
+            .map(|c| c.compress_mbps())
+            .fold(1.0, f64::max);
+        let max_ratio = self.cells.iter().map(|c| c.ratio).fold(1.0, f64::max);
+        let datasets = series
+            .iter()
+            .enumerate()
+            .map(|(i, (name, points))| {
+                Dataset::default()
+                    .name(*name)
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Scatter)
+                    .style(Style::default().fg(SERIES_COLORS[i % SERIES_COLORS.len()]))
+                    .data(points)
+            })
+            .collect();
+        let chart = Chart::new(datasets)
+            .block(Block::bordered().title("Ratio vs compression speed"))
+            .x_axis(
+                Axis::default()
+                    .title("MB/s")
+                    .bounds([0.0, max_speed * 1.05])
+                    .labels([Line::from("0"), Line::from(format!("{:.0}", max_speed))]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("Ratio")
+                    .bounds([0.0, max_ratio * 1.05])
+                    .labels([Line::from("0"), Line::from(format!("{:.1}", max_ratio))]),
+            );
+        frame.render_widget(chart, chart_area);
+    }
+}
+
+// Redraws as cells arrive until the sweep ends and the user quits; returns every cell
+pub fn run(receiver: Receiver<CellResult>, total: usize) -> io::Result<Vec<CellResult>> {
+    let mut terminal = ratatui::init();
+    let mut dashboard = Dashboard {
+        cells: Vec::new(),
+        total,
+        sort: 0,
+        descending: false,
+        finished: false,
+    };
+    let result = event_loop(&mut terminal, &mut dashboard, &receiver);
+    ratatui::restore();
+    result?;
+
+    // Closing the dashboard early doesn't abandon the sweep
+    if !dashboard.finished {
+        println!("Waiting for the remaining benchmark cells...");
+        dashboard.cells.extend(receiver.iter());
+    }
+    Ok(dashboard.cells)
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    dashboard: &mut Dashboard,
+    receiver: &Receiver<CellResult>,
+) -> io::Result<()> {
+    loop {
+        loop {
+            match receiver.try_recv() {
+                Ok(cell) => dashboard.cells.push(cell),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    dashboard.finished = true;
+                    break;
+                }
+            }
+        }
+        terminal.draw(|frame| dashboard.draw(frame))?;
+
+        if !event::poll(POLL_INTERVAL)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('s') | KeyCode::Right => {
+                    dashboard.sort = (dashboard.sort + 1) % SORT_KEYS.len()
+                }
+                KeyCode::Left => {
+                    dashboard.sort = (dashboard.sort + SORT_KEYS.len() - 1) % SORT_KEYS.len()
+                }
+                KeyCode::Char('r') => dashboard.descending = !dashboard.descending,
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::flushing::{Backend, FlushingEncoder, BACKENDS};
+use crate::records::generate_records;
+use crate::results::percentile;
+use crate::timer::{ClockChoice, Timer};
+use std::error::Error;
+use std::io::Read;
+
+struct Appended {
+    // Per-append latencies, sorted; an append that closes a group includes its flush, and one
+    // that fills a segment its rotation
+    latencies: Vec<f64>,
+    total_secs: f64,
+    segments: Vec<Vec<u8>>,
+}
+
+// Appends every record to a long-lived stream: flushed after each group commit of `group`
+// records, finished and replaced by a fresh stream once `rotate` uncompressed bytes went in
+fn append(
+    timer: &Timer,
+    backend: &Backend,
+    records: &[Vec<u8>],
+    group: usize,
+    rotate: usize,
+) -> Result<Appended, Box<dyn Error>> {
+    let mut encoder: Box<dyn FlushingEncoder> = (backend.encoder)()?;
+    let mut in_segment = 0;
+    let mut segments = Vec::new();
+    let mut latencies = Vec::with_capacity(records.len());
+    for (i, record) in records.iter().enumerate() {
+        in_segment += record.len();
+        let rotating = in_segment >= rotate;
+        let (result, secs) = timer.time(|| -> Result<(), Box<dyn Error>> {
+            encoder.write_all(record)?;
+            if rotating {
+                let full = std::mem::replace(&mut encoder, (backend.encoder)()?);
+                segments.push(full.finish()?);
+            } else if (i + 1) % group == 0 {
+                encoder.flush()?;
+            }
+            Ok(())
+        });
+        result?;
+        latencies.push(timer.corrected(secs));
+        if rotating {
+            in_segment = 0;
+        }
+    }
+    segments.push(encoder.finish()?);
+    let total_secs = latencies.iter().sum();
+    latencies.sort_by(f64::total_cmp);
+    Ok(Appended {
+        latencies,
+        total_secs,
+        segments,
+    })
+}
+
+// Replays every segment in order, as recovery would
+fn replay(backend: &Backend, segments: &[Vec<u8>]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut restored = Vec::new();
+    for segment in segments {
+        (backend.decoder)(segment)?.read_to_end(&mut restored)?;
+    }
+    Ok(restored)
+}
+
+// Sustained append latency and overall ratio of a compressed write-ahead log, per codec
+pub fn run(record_count: usize, group: usize, rotate_mb: usize) -> Result<(), Box<dyn Error>> {
+    let timer = Timer::calibrate(ClockChoice::Auto)?;
+    let records = generate_records(record_count);
+    let log = records.concat();
+    println!(
+        "\nWrite-ahead log ({} JSON records averaging {} bytes, flushed every {}, rotated every \
+         {}MB; {}, overhead {:.1} ns subtracted)\n",
+        record_count,
+        log.len() / record_count,
+        group,
+        rotate_mb,
+        timer.name(),
+        timer.overhead_secs() * 1e9
+    );
+    println!(
+        "{:<18} {:>9} {:>9} {:>10} {:>10} {:>9} {:>7} {:>9}",
+        "Codec", "p50 us", "p99 us", "p99.9 us", "Max us", "MB/s", "Ratio", "Segments"
+    );
+    println!("{}", "-".repeat(88));
+    for backend in BACKENDS {
+        let appended = match append(&timer, backend, &records, group, rotate_mb * 1024 * 1024) {
+            Ok(appended) => appended,
+            Err(e) => {
+                println!("{:<18} FAILED: {}", backend.name, e);
+                continue;
+            }
+        };
+        if replay(backend, &appended.segments)? != log {
+            println!("{:<18} FAILED: replayed log differs", backend.name);
+            continue;
+        }
+        let compressed: usize = appended.segments.iter().map(Vec::len).sum();
+        let us = |p: f64| percentile(&appended.latencies, p) * 1e6;
+        println!(
+            "{:<18} {:>9.2} {:>9.2} {:>10.2} {:>10.2} {:>9.1} {:>6.2}x {:>9}",
+            backend.name,
+            us(0.5),
+            us(0.99),
+            us(0.999),
+            appended.latencies[appended.latencies.len() - 1] * 1e6,
+            log.len() as f64 / (1024.0 * 1024.0) / appended.total_secs,
+            log.len() as f64 / compressed as f64,
+            appended.segments.len()
+        );
+    }
+    println!("\nMax is usually a rotation, which finishes one stream and starts the next");
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::{generate_test_data, timed, TestData};
+use rust_compress_comparison::backends;
+
+const WARMUP_DATA_SIZE: usize = 1024 * 1024; // 1MB
+const WARMUP_ITERATIONS: usize = 60;
+// A window of iterations all within this fraction of steady state counts as warmed up
+const STEADY_TOLERANCE: f64 = 0.05;
+const STEADY_WINDOW: usize = 3;
+
+struct WarmupProfile {
+    first_secs: f64,
+    steady_secs: f64,
+    // Iterations discarded before the first steady window
+    iterations_to_steady: Option<usize>,
+}
+
+fn median(samples: &[f64]) -> f64 {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted[sorted.len() / 2]
+}
+
+// Steady state is the median of the second half of the run
+fn profile(timings: &[f64]) -> WarmupProfile {
+    let steady_secs = median(&timings[timings.len() / 2..]);
+    let within = |t: &f64| (t - steady_secs).abs() <= steady_secs * STEADY_TOLERANCE;
+    let iterations_to_steady = timings
+        .windows(STEADY_WINDOW)
+        .position(|window| window.iter().all(within));
+    WarmupProfile {
+        first_secs: timings[0],
+        steady_secs,
+        iterations_to_steady,
+    }
+}
+
+pub fn run() {
+    let data = generate_test_data(&TestData::Mixed, WARMUP_DATA_SIZE);
+
+    println!(
+        "\nWarm-up sensitivity ({}MB Mixed data, {} iterations, steady = within {:.0}% for {} in a row)\n",
+        WARMUP_DATA_SIZE / 1024 / 1024,
+        WARMUP_ITERATIONS,
+        STEADY_TOLERANCE * 100.0,
+        STEADY_WINDOW
+    );
+    println!(
+        "{:<16} {:>12} {:>12} {:>10} {:>14}",
+        "Algorithm", "First (ms)", "Steady (ms)", "Slowdown", "Warm-up iters"
+    );
+    println!("{}", "-".repeat(68));
+
+    for codec in backends::configurations() {
+        let name = format!("{} {}", codec.name(), codec.level());
+        let timings: Vec<f64> = (0..WARMUP_ITERATIONS)
+            .map(|_| timed(|| codec.compress(&data)).1)
+            .collect();
+        let profile = profile(&timings);
+        println!(
+            "{:<16} {:>12.3} {:>12.3} {:>9.2}x {:>14}",
+            name,
+            profile.first_secs * 1000.0,
+            profile.steady_secs * 1000.0,
+            profile.first_secs / profile.steady_secs,
+            profile
+                .iterations_to_steady
+                .map_or("never".to_string(), |n| n.to_string())
+        );
+    }
+}
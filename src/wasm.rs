@@ -0,0 +1,11 @@
+// Browser entry point through wasm-bindgen, the JavaScript counterpart of `ffi::ccb_run`
+use crate::config::SuiteConfig;
+use wasm_bindgen::prelude::*;
+
+// Runs the suite described by `config_json` (see `SuiteConfig`) and returns the `RunResults`
+// JSON; a rejected config or failed run throws
+#[wasm_bindgen(js_name = runBenchmark)]
+pub fn run_benchmark(config_json: &str) -> Result<String, JsError> {
+    let results = SuiteConfig::from_json(config_json)?.builder()?.run()?;
+    Ok(serde_json::to_string(&results)?)
+}
@@ -0,0 +1,46 @@
+// Implementations of one format must decode each other's output, in every direction
+use rust_compress_comparison::harness::{generate_test_data, TEST_CASES};
+use rust_compress_comparison::interop::{self, FORMATS};
+
+fn inputs() -> Vec<Vec<u8>> {
+    let mut inputs: Vec<Vec<u8>> = TEST_CASES
+        .iter()
+        .map(|(_, data_type)| generate_test_data(data_type, 256 * 1024))
+        .collect();
+    inputs.push(Vec::new());
+    inputs.push(b"x".to_vec());
+    inputs
+}
+
+#[test]
+fn every_encoder_decodes_with_every_decoder_of_its_format() {
+    let inputs = inputs();
+    let mut failures = Vec::new();
+    for format in FORMATS {
+        for encoder in interop::implementations(format) {
+            for decoder in interop::implementations(format) {
+                for data in &inputs {
+                    if let Err(e) = interop::check(encoder, decoder, data) {
+                        failures.push(format!(
+                            "{}: {} -> {} ({} bytes): {}",
+                            format.name(),
+                            encoder.name,
+                            decoder.name,
+                            data.len(),
+                            e
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn lz4_frames_cross_between_crates() {
+    let names: Vec<&str> = interop::implementations(interop::Format::Lz4Frame)
+        .map(|i| i.name)
+        .collect();
+    assert_eq!(names, ["lz4 (liblz4)", "lz4_flex"]);
+}
@@ -0,0 +1,76 @@
+// Round-trip properties every registered configuration must hold, whatever it is fed
+use proptest::collection::vec;
+use proptest::prelude::*;
+use rust_compress_comparison::backends;
+
+const MB: usize = 1024 * 1024;
+
+// Bytes in the shapes benchmarks and callers feed codecs: uniform noise, a small alphabet,
+// long runs of one byte, and a short unit repeated
+fn input(max: usize) -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        vec(any::<u8>(), 0..=max),
+        vec(prop::sample::select(b"etaoin ".to_vec()), 0..=max),
+        (any::<u8>(), 0..=max).prop_map(|(byte, len)| vec![byte; len]),
+        (vec(any::<u8>(), 1..=16), 0..=max).prop_map(|(unit, len)| unit
+            .iter()
+            .copied()
+            .cycle()
+            .take(len)
+            .collect()),
+    ]
+}
+
+// Worst-case expansion of any registered format on incompressible input, with room for headers
+fn size_bound(len: usize) -> usize {
+    len + len / 16 + 1024
+}
+
+fn check_round_trips(data: &[u8]) -> Result<(), TestCaseError> {
+    for codec in backends::configurations() {
+        let label = format!("{} {}", codec.name(), codec.level());
+        let compressed = codec
+            .compress(data)
+            .map_err(|e| TestCaseError::fail(format!("{} compress: {}", label, e)))?;
+        prop_assert!(
+            compressed.len() <= size_bound(data.len()),
+            "{} grew {} bytes into {}",
+            label,
+            data.len(),
+            compressed.len()
+        );
+        let restored = codec
+            .decompress(&compressed)
+            .map_err(|e| TestCaseError::fail(format!("{} decompress: {}", label, e)))?;
+        prop_assert!(restored == data, "{} did not restore its input", label);
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn round_trips_small_inputs(data in input(16 * 1024)) {
+        check_round_trips(&data)?;
+    }
+
+    // Arbitrary bytes handed to a decoder must come back as an error or some output, never a
+    // panic
+    #[test]
+    fn decompressing_arbitrary_bytes_does_not_panic(data in vec(any::<u8>(), 0..4096)) {
+        for codec in backends::configurations() {
+            let _ = codec.decompress(&data);
+        }
+    }
+}
+
+proptest! {
+    // Each case runs every configuration, the slowest levels included, over up to 1MB
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn round_trips_large_inputs(data in input(MB)) {
+        check_round_trips(&data)?;
+    }
+}